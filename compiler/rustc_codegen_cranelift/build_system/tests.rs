@@ -149,6 +149,27 @@ const fn new(config: &'static str, func: &'static dyn Fn(&TestRunner)) -> Self {
             &runner.host_triple,
         ]);
     }),
+    TestCase::new("jit.repl", &|runner| {
+        let repl_cmd = runner.rustc_command([
+            "-Zunstable-options",
+            "-Cllvm-args=mode=jit-repl",
+            "-Cprefer-dynamic",
+            "example/repl.rs",
+            "--target",
+            &runner.host_triple,
+        ]);
+        let input = "fn square(x: u32) -> u32 {\n    x * x\n}\nsquare(7)\n\
+            macro_rules! twice {\n    ($e:expr) => { $e * 2 };\n}\ntwice!(21)\n\
+            \"(\".len() + /* ( */ \"/*\".len()\nNone::<u32>.unwrap()\n\
+            unsafe { COUNTER += 1; COUNTER }\nunsafe { COUNTER += 1; COUNTER }\n";
+        let output = spawn_and_wait_with_input(repl_cmd, input.to_string());
+        let values =
+            output.lines().filter(|line| !line.contains("codegen mono items")).collect::<Vec<_>>();
+        if values != ["49", "42", "3", "1", "2"] {
+            eprintln!("Unexpected output of the REPL:\n{}", output);
+            std::process::exit(1);
+        }
+    }),
     TestCase::new("aot.std_example", &|runner| {
         runner.run_rustc([
             "example/std_example.rs",
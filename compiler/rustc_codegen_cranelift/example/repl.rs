@@ -0,0 +1,5 @@
+// The crate of the `jit.repl` test, on top of which the snippets of the test are evaluated.
+
+static mut COUNTER: u32 = 0;
+
+fn main() {}
@@ -14,6 +14,8 @@ pub enum CodegenMode {
     Jit,
     /// JIT compile and execute the crate, but only compile functions the first time they are used.
     JitLazy,
+    /// JIT compile the crate, then evaluate the items and expressions read from stdin.
+    JitRepl,
 }
 
 impl FromStr for CodegenMode {
@@ -24,6 +26,7 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "aot" => Ok(CodegenMode::Aot),
             "jit" => Ok(CodegenMode::Jit),
             "jit-lazy" => Ok(CodegenMode::JitLazy),
+            "jit-repl" => Ok(CodegenMode::JitRepl),
             _ => Err(format!("Unknown codegen mode `{}`", s)),
         }
     }
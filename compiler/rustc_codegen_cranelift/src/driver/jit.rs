@@ -61,7 +61,7 @@ fn send(self) -> Result<(), mpsc::SendError<UnsafeMessage>> {
     }
 }
 
-fn create_jit_module(
+pub(super) fn create_jit_module(
     tcx: TyCtxt<'_>,
     backend_config: &BackendConfig,
     hotswap: bool,
@@ -126,7 +126,7 @@ pub(crate) fn run_jit(tcx: TyCtxt<'_>, backend_config: BackendConfig) -> ! {
         for (mono_item, _) in mono_items {
             match mono_item {
                 MonoItem::Fn(inst) => match backend_config.codegen_mode {
-                    CodegenMode::Aot => unreachable!(),
+                    CodegenMode::Aot | CodegenMode::JitRepl => unreachable!(),
                     CodegenMode::Jit => {
                         tcx.sess.time("codegen fn", || {
                             crate::base::codegen_and_compile_fn(
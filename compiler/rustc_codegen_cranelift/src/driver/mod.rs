@@ -11,6 +11,8 @@
 pub(crate) mod aot;
 #[cfg(feature = "jit")]
 pub(crate) mod jit;
+#[cfg(feature = "jit")]
+pub(crate) mod repl;
 
 fn predefine_mono_items<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -0,0 +1,338 @@
+//! The REPL driver uses the JIT driver to evaluate snippets of code one after the other, on top
+//! of a crate that is compiled once.
+//!
+//! A snippet is either a list of items or an expression. Each snippet is compiled in a new
+//! session, as the source of the crate followed by the items of the previous snippets and by the
+//! snippet itself. An expression is wrapped into a `#[no_mangle]` function that prints its value
+//! with `Debug`, or the message of its panic, as a panic must not unwind out of the REPL. While
+//! the front end checks the whole source again, only the functions and statics that are not part
+//! of the [`JITModule`] yet are codegened. They are linked to the ones that are already defined,
+//! which are kept as is, so statics keep their values from one snippet to the next. As the symbol
+//! names of the items of a crate depend on their paths, the items of the previous snippets can't
+//! be redefined.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::ErrorGuaranteed;
+use rustc_hir::def_id::CRATE_DEF_ID;
+use rustc_lexer::{LiteralKind, RawStrError, TokenKind};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_session::config::{Input, Options, OutputType, OutputTypes};
+use rustc_session::lint;
+use rustc_span::{FileName, Symbol};
+
+use cranelift_jit::JITModule;
+use cranelift_module::FuncOrDataId;
+
+use crate::{prelude::*, BackendConfig};
+use crate::{CodegenCx, CraneliftCodegenBackend};
+
+/// The state of the REPL that is kept from one snippet to the next.
+struct ReplState {
+    backend_config: BackendConfig,
+    jit_module: JITModule,
+    /// The symbols of the functions and statics defined in `jit_module`.
+    defined_symbols: FxHashSet<String>,
+    opts: Options,
+    crate_cfg: FxHashSet<(String, Option<String>)>,
+    source_name: FileName,
+    /// The source of the crate, followed by the items of the previous snippets.
+    source: String,
+    /// The number of expressions evaluated so far, which numbers their functions.
+    evaluated: usize,
+}
+
+/// Each snippet is compiled on the thread of its session, while the rustc thread waits for the
+/// session to finish, so the state of the REPL is only used by one thread at a time.
+struct AssertSend<T>(T);
+unsafe impl<T> Send for AssertSend<T> {}
+
+impl<T> AssertSend<T> {
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+pub(crate) fn run_jit_repl(tcx: TyCtxt<'_>, backend_config: BackendConfig) -> ! {
+    if !tcx.sess.opts.output_types.should_codegen() {
+        tcx.sess.fatal("JIT mode doesn't work with `cargo check`");
+    }
+
+    if !tcx.sess.crate_types().contains(&rustc_session::config::CrateType::Executable) {
+        tcx.sess.fatal("can't jit non-executable crate");
+    }
+
+    let root_file = tcx.sess.source_map().lookup_source_file(tcx.def_span(CRATE_DEF_ID).lo());
+    let Some(source) = root_file.src.as_ref() else {
+        tcx.sess.fatal("the source of the crate root is not available");
+    };
+
+    let mut opts = tcx.sess.opts.clone();
+    opts.incremental = None;
+    opts.output_types = OutputTypes::new(&[(OutputType::Exe, None)]);
+    // The items of the previous snippets are usually not all used by the next one.
+    opts.lint_opts.push(("unused".to_string(), lint::Allow));
+    let crate_cfg = tcx
+        .sess
+        .parse_sess
+        .config
+        .iter()
+        .map(|&(name, value)| (name.to_string(), value.map(|value| value.to_string())))
+        .collect();
+
+    let (jit_module, cx) = super::jit::create_jit_module(tcx, &backend_config, false);
+    let mut state = ReplState {
+        backend_config,
+        jit_module,
+        defined_symbols: FxHashSet::default(),
+        opts,
+        crate_cfg,
+        source_name: root_file.name.clone(),
+        source: source.to_string(),
+        evaluated: 0,
+    };
+    codegen_new_mono_items(tcx, &mut state, cx);
+
+    eprintln!(
+        "Rustc codegen cranelift will JIT run a REPL, because -Cllvm-args=mode=jit-repl was passed"
+    );
+
+    loop {
+        let Some(snippet) = read_snippet() else { std::process::exit(0) };
+
+        if is_items(&snippet) {
+            let source = format!("{}\n{}", state.source, snippet);
+            if compile_source(&mut state, source.clone()).is_ok() {
+                state.source = source;
+            }
+            continue;
+        }
+
+        let name = format!("__clif_repl_eval_{}", state.evaluated);
+        let source = format!(
+            r#"{}
+#[no_mangle]
+pub extern "C" fn {}() {{
+    // The panic hook of rustc would report the panic as an ICE.
+    let hook = ::std::panic::take_hook();
+    ::std::panic::set_hook(Box::new(|_| {{}}));
+    let result = ::std::panic::catch_unwind(|| {{
+        println!("{{:?}}", {{
+{}
+        }});
+    }});
+    ::std::panic::set_hook(hook);
+    if let Err(payload) = result {{
+        let message = match payload.downcast::<&'static str>() {{
+            Ok(message) => message.to_string(),
+            Err(payload) => match payload.downcast::<String>() {{
+                Ok(message) => *message,
+                Err(_) => "Box<dyn Any>".to_string(),
+            }},
+        }};
+        eprintln!("error: the expression panicked: {{}}", message);
+    }}
+}}
+"#,
+            state.source, name, snippet,
+        );
+        if compile_source(&mut state, source).is_err() {
+            continue;
+        }
+        state.evaluated += 1;
+
+        let Some(FuncOrDataId::Func(func_id)) = state.jit_module.get_name(&name) else {
+            tcx.sess.err(&format!("the function of the expression `{}` is not defined", name));
+            continue;
+        };
+        let eval_fn: extern "C" fn() =
+            unsafe { ::std::mem::transmute(state.jit_module.get_finalized_function(func_id)) };
+        eval_fn();
+    }
+}
+
+/// Compiles `source` in a new session, and codegens the functions and statics of the crate
+/// that are not defined in the JIT module yet.
+fn compile_source(state: &mut ReplState, source: String) -> Result<(), ErrorGuaranteed> {
+    let backend_config = state.backend_config.clone();
+    let config = rustc_interface::Config {
+        opts: state.opts.clone(),
+        crate_cfg: state.crate_cfg.clone(),
+        crate_check_cfg: Default::default(),
+        input: Input::Str { name: state.source_name.clone(), input: source },
+        input_path: None,
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        lint_caps: Default::default(),
+        parse_sess_created: None,
+        register_lints: None,
+        override_queries: None,
+        register_mir_passes: None,
+        make_codegen_backend: Some(Box::new(move |_| {
+            Box::new(CraneliftCodegenBackend { config: RefCell::new(Some(backend_config)) })
+        })),
+        registry: rustc_driver::diagnostics_registry(),
+    };
+
+    let state = AssertSend(state);
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_interface::run_compiler(config, move |compiler| {
+            let state = state.into_inner();
+            compiler.enter(|queries| {
+                queries.global_ctxt()?.peek_mut().enter(|tcx| {
+                    tcx.analysis(()).ok();
+                    tcx.sess.compile_status()?;
+
+                    let cx = CodegenCx::new(
+                        tcx,
+                        state.backend_config.clone(),
+                        state.jit_module.isa(),
+                        false,
+                        Symbol::intern("dummy_cgu_name"),
+                    );
+                    codegen_new_mono_items(tcx, state, cx);
+                    Ok(())
+                })
+            })
+        })
+    })
+    .and_then(|result| result)
+}
+
+/// Codegens the mono items of the crate whose symbols are not defined in the JIT module yet,
+/// and finalizes them.
+fn codegen_new_mono_items(tcx: TyCtxt<'_>, state: &mut ReplState, mut cx: CodegenCx) {
+    let mut cached_context = Context::new();
+
+    let (_, cgus) = tcx.collect_and_partition_mono_items(());
+    let mono_items = cgus
+        .iter()
+        .map(|cgu| cgu.items_in_deterministic_order(tcx).into_iter())
+        .flatten()
+        .filter(|(mono_item, _)| !state.defined_symbols.contains(mono_item.symbol_name(tcx).name))
+        .collect::<FxHashMap<_, (_, _)>>()
+        .into_iter()
+        .collect::<Vec<(_, (_, _))>>();
+
+    super::time(tcx, state.backend_config.display_cg_time, "codegen mono items", || {
+        super::predefine_mono_items(tcx, &mut state.jit_module, &mono_items);
+        for (mono_item, _) in mono_items {
+            match mono_item {
+                MonoItem::Fn(inst) => {
+                    tcx.sess.time("codegen fn", || {
+                        crate::base::codegen_and_compile_fn(
+                            tcx,
+                            &mut cx,
+                            &mut cached_context,
+                            &mut state.jit_module,
+                            inst,
+                        )
+                    });
+                }
+                MonoItem::Static(def_id) => {
+                    crate::constant::codegen_static(tcx, &mut state.jit_module, def_id);
+                }
+                MonoItem::GlobalAsm(item_id) => {
+                    let item = tcx.hir().item(item_id);
+                    tcx.sess.span_fatal(item.span, "Global asm is not supported in JIT mode");
+                }
+            }
+            state.defined_symbols.insert(mono_item.symbol_name(tcx).name.to_string());
+        }
+    });
+
+    if !cx.global_asm.is_empty() {
+        tcx.sess.fatal("Inline asm is not supported in JIT mode");
+    }
+
+    tcx.sess.abort_if_errors();
+
+    state.jit_module.finalize_definitions();
+    unsafe { cx.unwind_context.register_jit(&state.jit_module) };
+}
+
+/// Reads the next snippet from stdin, up to the end of the first line that closes all the
+/// brackets opened before it, and that doesn't end inside a literal or a comment. Returns `None`
+/// at the end of the input, or if stdin or stderr can't be used anymore.
+fn read_snippet() -> Option<String> {
+    let mut snippet = String::new();
+    loop {
+        // The prompts are written to stderr, so that stdout only has the values of the
+        // expressions.
+        let prompt = if snippet.is_empty() { ">> " } else { ".. " };
+        let mut stderr = io::stderr();
+        if stderr.write_all(prompt.as_bytes()).and_then(|()| stderr.flush()).is_err() {
+            return None;
+        }
+
+        // An I/O error ends the input like the end of the input does.
+        let mut line = String::new();
+        if matches!(io::stdin().read_line(&mut line), Ok(0) | Err(_)) {
+            return if snippet.is_empty() { None } else { Some(snippet) };
+        }
+        if snippet.is_empty() && line.trim().is_empty() {
+            continue;
+        }
+
+        snippet.push_str(&line);
+        if is_complete(&snippet) {
+            return Some(snippet);
+        }
+    }
+}
+
+/// Whether `snippet` closes all the brackets it opens, not counting the ones in literals and
+/// comments, and doesn't end inside a string literal or a block comment.
+fn is_complete(snippet: &str) -> bool {
+    let mut depth = 0;
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(snippet) {
+        let text = &snippet[pos..pos + token.len as usize];
+        pos += token.len as usize;
+        match token.kind {
+            TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::OpenBrace => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket | TokenKind::CloseBrace => depth -= 1,
+            TokenKind::BlockComment { terminated: false, .. } => return false,
+            TokenKind::Literal { kind, .. } => match kind {
+                LiteralKind::Str { terminated: false }
+                | LiteralKind::ByteStr { terminated: false } => return false,
+                LiteralKind::RawStr { n_hashes: None } if is_unterminated_raw_str(text, 1) => {
+                    return false;
+                }
+                LiteralKind::RawByteStr { n_hashes: None } if is_unterminated_raw_str(text, 2) => {
+                    return false;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+fn is_unterminated_raw_str(text: &str, prefix_len: u32) -> bool {
+    matches!(rustc_lexer::validate_raw_str(text, prefix_len), Err(RawStrError::NoTerminator { .. }))
+}
+
+/// Whether a snippet is a list of items rather than an expression.
+fn is_items(snippet: &str) -> bool {
+    let snippet = snippet.trim_start();
+    if snippet.starts_with("#[") {
+        return true;
+    }
+
+    let mut words =
+        snippet.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|word| !word.is_empty());
+    match words.next() {
+        Some(
+            "const" | "enum" | "extern" | "fn" | "impl" | "mod" | "pub" | "static" | "struct"
+            | "trait" | "type" | "union" | "use" | "macro_rules",
+        ) => true,
+        Some("async") => matches!(words.next(), Some("fn" | "unsafe")),
+        Some("unsafe") => matches!(words.next(), Some("extern" | "fn" | "impl" | "trait")),
+        _ => false,
+    }
+}
@@ -16,6 +16,7 @@
 extern crate rustc_incremental;
 extern crate rustc_index;
 extern crate rustc_interface;
+extern crate rustc_lexer;
 extern crate rustc_metadata;
 extern crate rustc_session;
 extern crate rustc_span;
@@ -207,6 +208,13 @@ fn codegen_crate(
                 #[cfg(feature = "jit")]
                 driver::jit::run_jit(tcx, config);
 
+                #[cfg(not(feature = "jit"))]
+                tcx.sess.fatal("jit support was disabled when compiling rustc_codegen_cranelift");
+            }
+            CodegenMode::JitRepl => {
+                #[cfg(feature = "jit")]
+                driver::repl::run_jit_repl(tcx, config);
+
                 #[cfg(not(feature = "jit"))]
                 tcx.sess.fatal("jit support was disabled when compiling rustc_codegen_cranelift");
             }
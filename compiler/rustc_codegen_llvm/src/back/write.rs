@@ -201,7 +201,9 @@ pub fn target_machine_factory(
     let abi = SmallCStr::new(&sess.target.llvm_abiname);
     let trap_unreachable =
         sess.opts.unstable_opts.trap_unreachable.unwrap_or(sess.target.trap_unreachable);
-    let emit_stack_size_section = sess.opts.unstable_opts.emit_stack_sizes;
+    // The report of `-Z emit-stack-sizes-report` is computed from the stack size section.
+    let emit_stack_size_section =
+        sess.opts.unstable_opts.emit_stack_sizes || sess.opts.unstable_opts.emit_stack_sizes_report;
 
     let asm_comments = sess.asm_comments();
     let relax_elf_relocations =
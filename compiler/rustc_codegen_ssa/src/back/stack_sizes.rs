@@ -0,0 +1,271 @@
+//! The stack usage report printed by `-Z emit-stack-sizes-report`.
+//!
+//! The frame size of each function is read from the `.stack_sizes` sections that LLVM emits
+//! in ELF object files when asked to (see `-Z emit-stack-sizes`). Each entry of such a
+//! section is the address of a function, relocated against its symbol, followed by the size
+//! of its frame as an ULEB128 number.
+//!
+//! The frame sizes are combined with the call graph of the `mono_call_graph` query: the
+//! stack usage of a function is its frame size plus the largest stack usage of the functions
+//! it calls, so the report gives the maximum stack depth along the deepest path of calls.
+//! A function whose calls can recurse, or that calls a function pointer or a trait object
+//! method, has an unbounded stack usage. Functions that were inlined into all of their
+//! callers have no frame of their own. Functions of other crates that are not part of the
+//! object files have an unknown frame size, so the usage of their callers is a lower bound.
+
+use crate::errors;
+use crate::CompiledModule;
+
+use object::{BinaryFormat, Object, ObjectSection, ObjectSymbol, RelocationTarget, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_middle::mir::mono::MonoCallGraph;
+use rustc_session::Session;
+
+use std::fs;
+use std::path::Path;
+
+pub fn print_stack_sizes_report<'a>(
+    sess: &Session,
+    call_graph: &MonoCallGraph,
+    modules: impl Iterator<Item = &'a CompiledModule>,
+) {
+    let mut frame_sizes = FxHashMap::default();
+    for path in modules.filter_map(|module| module.object.as_deref()) {
+        match read_frame_sizes(path, &mut frame_sizes) {
+            Ok(true) => {}
+            Ok(false) => {
+                sess.emit_err(errors::StackSizesReportUnsupported);
+                return;
+            }
+            Err(error) => {
+                sess.emit_err(errors::StackSizesReadFailure { path: path.to_path_buf(), error });
+                return;
+            }
+        }
+    }
+
+    let mut analysis = Analysis {
+        call_graph,
+        frame_sizes: &frame_sizes,
+        summaries: FxHashMap::default(),
+        on_stack: FxHashSet::default(),
+    };
+    let mut report: Vec<_> = call_graph
+        .functions
+        .keys()
+        .map(|symbol| (symbol.as_str(), analysis.summarize(symbol)))
+        .collect();
+    // Show the unbounded functions first, then the functions using the most stack.
+    report.sort_by(|(a_symbol, a), (b_symbol, b)| {
+        let key = |summary: &Summary<'_>| match summary.usage {
+            Usage::Bounded(bytes) => (false, bytes),
+            Usage::Recursive | Usage::IndirectCall => (true, 0),
+        };
+        key(b).cmp(&key(a)).then_with(|| analysis.name(*a_symbol).cmp(analysis.name(*b_symbol)))
+    });
+
+    for (symbol, summary) in report {
+        let path = analysis.path(symbol);
+        let usage = match summary.usage {
+            Usage::Bounded(bytes) if summary.has_unknown_callees => {
+                format!("at least {bytes} bytes, calls functions of unknown stack usage")
+            }
+            Usage::Bounded(bytes) => format!("{bytes} bytes"),
+            Usage::Recursive => "unbounded, recursive".to_string(),
+            Usage::IndirectCall => "unbounded, indirect call".to_string(),
+        };
+        println!("STACK_SIZES `{}`: {usage}", analysis.name(symbol));
+        if path.len() > 1 || !matches!(summary.usage, Usage::Bounded(_)) {
+            let path: Vec<_> = path
+                .iter()
+                .map(|symbol| match analysis.frame_sizes.get(*symbol) {
+                    Some(bytes) => format!("`{}` ({bytes} bytes)", analysis.name(*symbol)),
+                    None => format!("`{}`", analysis.name(*symbol)),
+                })
+                .collect();
+            println!("    through {}", path.join(" -> "));
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Usage {
+    Bounded(u64),
+    /// The function can call itself, directly or not.
+    Recursive,
+    /// The function calls a function pointer or a trait object method, directly or not.
+    IndirectCall,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Summary<'a> {
+    usage: Usage,
+    /// The callee that the usage comes from, if it does not come from the function itself.
+    deepest_callee: Option<&'a str>,
+    /// Whether a function of unknown frame size can be called, directly or not.
+    has_unknown_callees: bool,
+}
+
+struct Analysis<'a> {
+    call_graph: &'a MonoCallGraph,
+    frame_sizes: &'a FxHashMap<String, u64>,
+    summaries: FxHashMap<&'a str, Summary<'a>>,
+    /// The functions being summarized, to detect recursion.
+    on_stack: FxHashSet<&'a str>,
+}
+
+impl<'a> Analysis<'a> {
+    fn summarize(&mut self, symbol: &'a str) -> Summary<'a> {
+        if let Some(&summary) = self.summaries.get(symbol) {
+            return summary;
+        }
+        if self.on_stack.contains(symbol) {
+            // Only the functions of the cycle are summarized from this, so it is not cached.
+            return Summary {
+                usage: Usage::Recursive,
+                deepest_callee: None,
+                has_unknown_callees: false,
+            };
+        }
+
+        let frame_size = self.frame_sizes.get(symbol).copied();
+        let Some(function) = self.call_graph.functions.get(symbol) else {
+            // A function of another crate, or a function generated by the backend.
+            let summary = Summary {
+                usage: Usage::Bounded(frame_size.unwrap_or(0)),
+                deepest_callee: None,
+                has_unknown_callees: frame_size.is_none(),
+            };
+            self.summaries.insert(symbol, summary);
+            return summary;
+        };
+
+        // Functions without a frame size were inlined into all of their callers.
+        let frame_size = frame_size.unwrap_or(0);
+        let usage = if function.has_indirect_calls {
+            Usage::IndirectCall
+        } else {
+            Usage::Bounded(frame_size)
+        };
+        let mut summary = Summary { usage, deepest_callee: None, has_unknown_callees: false };
+
+        self.on_stack.insert(symbol);
+        for callee in &function.callees {
+            let callee_summary = ensure_sufficient_stack(|| self.summarize(callee));
+            summary.has_unknown_callees |= callee_summary.has_unknown_callees;
+            match (summary.usage, callee_summary.usage) {
+                (Usage::Bounded(bytes), Usage::Bounded(callee_bytes)) => {
+                    if frame_size + callee_bytes > bytes {
+                        summary.usage = Usage::Bounded(frame_size + callee_bytes);
+                        summary.deepest_callee = Some(callee.as_str());
+                    }
+                }
+                (Usage::Bounded(_), callee_usage) => {
+                    summary.usage = callee_usage;
+                    summary.deepest_callee = Some(callee.as_str());
+                }
+                _ => {}
+            }
+        }
+        self.on_stack.remove(symbol);
+
+        self.summaries.insert(symbol, summary);
+        summary
+    }
+
+    /// Returns the path of calls from `symbol` that its stack usage comes from. For recursive
+    /// functions, the path ends with the first function called again.
+    fn path(&self, symbol: &'a str) -> Vec<&'a str> {
+        let mut path = vec![symbol];
+        let mut current = symbol;
+        while let Some(callee) = self.summaries.get(current).and_then(|s| s.deepest_callee) {
+            path.push(callee);
+            if path[..path.len() - 1].contains(&callee) {
+                break;
+            }
+            current = callee;
+        }
+        path
+    }
+
+    fn name(&self, symbol: &'a str) -> &'a str {
+        self.call_graph.names.get(symbol).map_or(symbol, |name| name.as_str())
+    }
+}
+
+/// Adds the frame sizes of the `.stack_sizes` sections of the object file at `path` to
+/// `frame_sizes`. Returns `false` if the file is not an ELF object file.
+fn read_frame_sizes(path: &Path, frame_sizes: &mut FxHashMap<String, u64>) -> Result<bool, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let file = object::File::parse(&*data).map_err(|e| e.to_string())?;
+    if file.format() != BinaryFormat::Elf {
+        return Ok(false);
+    }
+
+    // Relocations against functions with local symbols refer to the symbol of their section.
+    let mut functions = FxHashMap::default();
+    for symbol in file.symbols() {
+        let (SymbolKind::Text, Some(section)) = (symbol.kind(), symbol.section_index()) else {
+            continue;
+        };
+        let name = symbol.name().map_err(|e| e.to_string())?;
+        functions.insert((section, symbol.address()), name);
+    }
+
+    let address_size = if file.is_64() { 8 } else { 4 };
+    for section in file.sections() {
+        if !matches!(section.name(), Ok(".stack_sizes")) {
+            continue;
+        }
+        let data = section.data().map_err(|e| e.to_string())?;
+        for (offset, relocation) in section.relocations() {
+            let RelocationTarget::Symbol(index) = relocation.target() else { continue };
+            let symbol = file.symbol_by_index(index).map_err(|e| e.to_string())?;
+            let offset = offset as usize;
+            let addend = if relocation.has_implicit_addend() {
+                read_address(data, offset, address_size, file.is_little_endian())
+            } else {
+                Some(relocation.addend() as u64)
+            };
+            let name = match symbol.kind() {
+                SymbolKind::Section => symbol
+                    .section_index()
+                    .zip(addend)
+                    .and_then(|(section, addend)| functions.get(&(section, addend)).copied()),
+                _ => symbol.name().ok(),
+            };
+            let frame_size = data.get(offset + address_size..).and_then(read_uleb128);
+            let (Some(name), Some(frame_size)) = (name, frame_size) else {
+                return Err(format!("malformed `.stack_sizes` entry at offset {offset}"));
+            };
+
+            // Symbols promoted by ThinLTO get a suffix.
+            let name = name.split_once(".llvm.").map_or(name, |(name, _)| name);
+            let size = frame_sizes.entry(name.to_string()).or_insert(0);
+            *size = (*size).max(frame_size);
+        }
+    }
+    Ok(true)
+}
+
+fn read_address(data: &[u8], offset: usize, size: usize, little_endian: bool) -> Option<u64> {
+    let bytes = data.get(offset..offset + size)?;
+    let mut value = 0;
+    for i in 0..size {
+        let byte = if little_endian { bytes[size - 1 - i] } else { bytes[i] };
+        value = value << 8 | u64::from(byte);
+    }
+    Some(value)
+}
+
+fn read_uleb128(data: &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for (i, &byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
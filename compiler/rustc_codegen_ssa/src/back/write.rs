@@ -1,5 +1,6 @@
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::stack_sizes;
 use super::symbol_export::symbol_name_for_instance_in_crate;
 
 use crate::errors;
@@ -25,6 +26,7 @@
 use rustc_metadata::EncodedMetadata;
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::middle::exported_symbols::SymbolExportInfo;
+use rustc_middle::mir::mono::MonoCallGraph;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{self, CrateType, Lto, OutputFilenames, OutputType};
@@ -487,6 +489,11 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
             phantom: PhantomData,
         },
         output_filenames: tcx.output_filenames(()).clone(),
+        call_graph: sess
+            .opts
+            .unstable_opts
+            .emit_stack_sizes_report
+            .then(|| tcx.mono_call_graph(()).clone()),
     }
 }
 
@@ -687,6 +694,14 @@ pub fn module_kind(&self) -> ModuleKind {
         }
     }
 
+    fn module_name(&self) -> &str {
+        match self {
+            WorkItem::Optimize(m) => &m.name,
+            WorkItem::CopyPostLtoArtifacts(m) => &m.name,
+            WorkItem::LTO(m) => m.name(),
+        }
+    }
+
     fn start_profiling<'a>(&self, cgcx: &'a CodegenContext<B>) -> TimingGuard<'a> {
         match *self {
             WorkItem::Optimize(ref m) => {
@@ -1689,6 +1704,7 @@ fn drop(&mut self) {
         // surface that there was an error in this worker.
         bomb.result = {
             let _prof_timer = work.start_profiling(&cgcx);
+            let _cgu_timer = cgcx.prof.codegen_unit_activity(work.module_name());
             Some(execute_work_item(&cgcx, work))
         };
     })
@@ -1871,6 +1887,8 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub output_filenames: Arc<OutputFilenames>,
     pub coordinator: Coordinator<B>,
+    /// The call graph to compute the stack usage of, for `-Z emit-stack-sizes-report`.
+    pub call_graph: Option<MonoCallGraph>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1893,6 +1911,13 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxHashMap<WorkProductId, W
 
         sess.abort_if_errors();
 
+        // The object files of the codegen units may be removed along with the other
+        // intermediate artifacts, so they are read first.
+        if let Some(call_graph) = &self.call_graph {
+            let modules = compiled_modules.modules.iter().chain(&compiled_modules.allocator_module);
+            stack_sizes::print_stack_sizes_report(sess, call_graph, modules);
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
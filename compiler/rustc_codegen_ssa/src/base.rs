@@ -709,7 +709,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 
             let pre_compiled_cgus = par_iter(cgus)
                 .map(|(i, _)| {
-                    let module = backend.compile_codegen_unit(tcx, codegen_units[i].name());
+                    let cgu_name = codegen_units[i].name();
+                    let _timer = tcx.prof.codegen_unit_activity(cgu_name.as_str());
+                    let module = backend.compile_codegen_unit(tcx, cgu_name);
                     (i, module)
                 })
                 .collect();
@@ -735,6 +737,7 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     cgu
                 } else {
                     let start_time = Instant::now();
+                    let _timer = tcx.prof.codegen_unit_activity(cgu.name().as_str());
                     let module = backend.compile_codegen_unit(tcx, cgu.name());
                     total_codegen_time += start_time.elapsed();
                     module
@@ -468,6 +468,17 @@ pub struct FailedToWrite {
     pub error: Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_sizes_read_failure)]
+pub struct StackSizesReadFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_sizes_report_unsupported)]
+pub struct StackSizesReportUnsupported;
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_unable_to_write_debugger_visualizer)]
 pub struct UnableToWriteDebuggerVisualizer {
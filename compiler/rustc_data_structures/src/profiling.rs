@@ -98,6 +98,12 @@
 use measureme::{EventIdBuilder, Profiler, SerializableString, StringId};
 use parking_lot::RwLock;
 use smallvec::SmallVec;
+use tempfile::TempDir;
+
+mod summary;
+
+pub use summary::{ItemPhase, SelfProfileSummaryFormat};
+use summary::{ProfileSummary, SummaryEvent, SummaryTimer};
 
 bitflags::bitflags! {
     struct EventFilter: u32 {
@@ -121,6 +127,14 @@ struct EventFilter: u32 {
                         Self::ARTIFACT_SIZES.bits;
 
         const ARGS = Self::QUERY_KEYS.bits | Self::FUNCTION_ARGS.bits;
+
+        // The events aggregated by `-Z self-profile-summary` and `-Z time-by-item`.
+        const SUMMARY = Self::GENERIC_ACTIVITIES.bits |
+                        Self::QUERY_PROVIDERS.bits |
+                        Self::QUERY_CACHE_HITS.bits |
+                        Self::QUERY_BLOCKED.bits |
+                        Self::INCR_CACHE_LOADS.bits |
+                        Self::INCR_RESULT_HASHING.bits;
     }
 }
 
@@ -239,9 +253,15 @@ pub fn verbose_generic_activity_with_arg<'a, A>(
     #[inline(always)]
     pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+            let summary_event = SummaryEvent::GenericActivity(event_label);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = EventId::from_label(event_label);
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                summary_event,
+            )
         })
     }
 
@@ -267,6 +287,7 @@ pub fn generic_activity_with_arg<A>(
     {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
+            let summary_event = SummaryEvent::GenericActivity(event_label);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
                 let event_arg = profiler.get_or_alloc_cached_string(event_arg);
@@ -274,7 +295,12 @@ pub fn generic_activity_with_arg<A>(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                summary_event,
+            )
         })
     }
 
@@ -310,6 +336,7 @@ pub fn generic_activity_with_arg_recorder<F>(
         // Ensure this event will only be recorded when self-profiling is turned on.
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
+            let summary_event = SummaryEvent::GenericActivity(event_label);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
 
             // Ensure the closure to create event arguments will only be called when argument
@@ -334,7 +361,12 @@ pub fn generic_activity_with_arg_recorder<F>(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                summary_event,
+            )
         })
     }
 
@@ -373,6 +405,7 @@ pub fn generic_activity_with_args(
     ) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
+            let summary_event = SummaryEvent::GenericActivity(event_label);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
                 let event_args: Vec<_> = event_args
@@ -383,7 +416,12 @@ pub fn generic_activity_with_args(
             } else {
                 builder.from_label(event_label)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.generic_activity_event_kind,
+                event_id,
+                summary_event,
+            )
         })
     }
 
@@ -392,7 +430,12 @@ pub fn generic_activity_with_args(
     #[inline(always)]
     pub fn query_provider(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_PROVIDERS, |profiler| {
-            TimingGuard::start(profiler, profiler.query_event_kind, EventId::INVALID)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.query_event_kind,
+                EventId::INVALID,
+                SummaryEvent::QueryProvider,
+            )
         })
     }
 
@@ -404,6 +447,23 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
             query_invocation_id,
             EventFilter::QUERY_CACHE_HITS,
         );
+        self.summary_query_cache_hit(query_invocation_id);
+    }
+
+    /// Counts a query in-memory cache hit in the summary, if one is recorded.
+    #[inline(always)]
+    fn summary_query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
+        #[inline(never)]
+        #[cold]
+        fn cold_call(profiler: &SelfProfiler, query_invocation_id: QueryInvocationId) {
+            if let Some(summary) = &profiler.summary {
+                summary.record_cache_hit(query_invocation_id);
+            }
+        }
+
+        if self.event_filter_mask.contains(EventFilter::QUERY_CACHE_HITS) {
+            cold_call(self.profiler.as_ref().unwrap(), query_invocation_id)
+        }
     }
 
     /// Start profiling a query being blocked on a concurrent execution.
@@ -412,7 +472,12 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
     #[inline(always)]
     pub fn query_blocked(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_BLOCKED, |profiler| {
-            TimingGuard::start(profiler, profiler.query_blocked_event_kind, EventId::INVALID)
+            TimingGuard::start_with_summary(
+                profiler,
+                profiler.query_blocked_event_kind,
+                EventId::INVALID,
+                SummaryEvent::QueryBlocked,
+            )
         })
     }
 
@@ -422,10 +487,11 @@ pub fn query_blocked(&self) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::INCR_CACHE_LOADS, |profiler| {
-            TimingGuard::start(
+            TimingGuard::start_with_summary(
                 profiler,
                 profiler.incremental_load_result_event_kind,
                 EventId::INVALID,
+                SummaryEvent::IncrementalLoadResult,
             )
         })
     }
@@ -435,14 +501,45 @@ pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn incr_result_hashing(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::INCR_RESULT_HASHING, |profiler| {
-            TimingGuard::start(
+            TimingGuard::start_with_summary(
                 profiler,
                 profiler.incremental_result_hashing_event_kind,
                 EventId::INVALID,
+                SummaryEvent::IncrementalResultHashing,
             )
         })
     }
 
+    /// Start attributing time to the item returned by `item` (as its name and
+    /// location) for `-Z time-by-item`. Attribution continues until the
+    /// TimingGuard returned from this call is dropped. Does nothing, and does
+    /// not call `item`, if no item times are recorded.
+    #[inline(always)]
+    pub fn item_activity(
+        &self,
+        phase: ItemPhase,
+        item: impl FnOnce() -> (String, String),
+    ) -> TimingGuard<'_> {
+        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+            let Some(summary) = profiler.item_summary() else { return TimingGuard::none() };
+            let (name, location) = item();
+            let item = summary.intern_item(name, location);
+            TimingGuard(None, Some(SummaryTimer::start(summary, SummaryEvent::Item(phase, item))))
+        })
+    }
+
+    /// Start attributing time to the codegen unit `cgu_name` for
+    /// `-Z time-by-item`. Attribution continues until the TimingGuard returned
+    /// from this call is dropped.
+    #[inline(always)]
+    pub fn codegen_unit_activity(&self, cgu_name: &str) -> TimingGuard<'_> {
+        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+            let Some(summary) = profiler.item_summary() else { return TimingGuard::none() };
+            let cgu = summary.intern_codegen_unit(cgu_name);
+            TimingGuard(None, Some(SummaryTimer::start(summary, SummaryEvent::CodegenUnit(cgu))))
+        })
+    }
+
     #[inline(always)]
     fn instant_query_event(
         &self,
@@ -522,6 +619,9 @@ pub fn record_arg<A>(&mut self, event_arg: A)
 
 pub struct SelfProfiler {
     profiler: Profiler,
+    /// The directory the raw event data is written to when it is only recorded for the
+    /// summary. It is removed when the profiler is dropped, after `profiler` wrote its data.
+    _temp_output_directory: Option<TempDir>,
     event_filter_mask: EventFilter,
 
     string_cache: RwLock<FxHashMap<String, StringId>>,
@@ -533,15 +633,29 @@ pub struct SelfProfiler {
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
     artifact_size_event_kind: StringId,
+
+    /// Aggregated statistics for `-Z self-profile-summary` and `-Z time-by-item`.
+    summary: Option<ProfileSummary>,
+    record_item_times: bool,
 }
 
 impl SelfProfiler {
+    /// Creates a profiler writing the raw event data to `output_directory`. Without an output
+    /// directory, the raw event data is not kept, and only the summary is recorded.
     pub fn new(
-        output_directory: &Path,
+        output_directory: Option<&Path>,
         crate_name: Option<&str>,
         event_filters: Option<&[String]>,
         counter_name: &str,
+        record_summary: bool,
+        record_item_times: bool,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
+        let temp_output_directory = match output_directory {
+            Some(_) => None,
+            None => Some(tempfile::Builder::new().prefix("rustc-self-profile").tempdir()?),
+        };
+        let output_directory =
+            output_directory.unwrap_or_else(|| temp_output_directory.as_ref().unwrap().path());
         fs::create_dir_all(output_directory)?;
 
         let crate_name = crate_name.unwrap_or("unknown-crate");
@@ -592,12 +706,21 @@ pub fn new(
                         .join(", ")
                 );
             }
-        } else {
+        } else if temp_output_directory.is_none() {
             event_filter_mask = EventFilter::DEFAULT;
         }
 
+        // The summary is built from these events, regardless of which events
+        // were requested for the raw profile. If the raw profile is not kept,
+        // they are the only events recorded by default.
+        let record_summary = record_summary || record_item_times;
+        if record_summary {
+            event_filter_mask |= EventFilter::SUMMARY;
+        }
+
         Ok(SelfProfiler {
             profiler,
+            _temp_output_directory: temp_output_directory,
             event_filter_mask,
             string_cache: RwLock::new(FxHashMap::default()),
             query_event_kind,
@@ -607,6 +730,8 @@ pub fn new(
             query_blocked_event_kind,
             query_cache_hit_event_kind,
             artifact_size_event_kind,
+            summary: record_summary.then(ProfileSummary::default),
+            record_item_times,
         })
     }
 
@@ -665,10 +790,74 @@ pub fn query_key_recording_enabled(&self) -> bool {
     pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
         EventIdBuilder::new(&self.profiler)
     }
+
+    pub fn summary_enabled(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    /// Attributes the summary statistics of the given query invocations to the
+    /// query `query_name`. Does nothing if no summary is recorded.
+    pub fn attribute_query_invocations_to_summary(
+        &self,
+        query_name: &'static str,
+        ids: impl Iterator<Item = QueryInvocationId>,
+    ) {
+        if let Some(summary) = &self.summary {
+            summary.attribute_query_invocations(query_name, ids);
+        }
+    }
+
+    /// Renders the summary requested by `-Z self-profile-summary`, if any.
+    pub fn render_summary(&self, format: SelfProfileSummaryFormat) -> Option<String> {
+        self.summary.as_ref().map(|summary| summary.render(format))
+    }
+
+    fn item_summary(&self) -> Option<&ProfileSummary> {
+        self.summary.as_ref().filter(|_| self.record_item_times)
+    }
+
+    pub fn item_times_enabled(&self) -> bool {
+        self.record_item_times
+    }
+
+    /// Attributes the self time of the query invocations in `ids` to the items
+    /// they were keyed by, given as their name and location.
+    pub fn attribute_query_invocations_to_items(
+        &self,
+        phase: ItemPhase,
+        ids: impl Iterator<Item = (QueryInvocationId, (String, String))>,
+    ) {
+        let Some(summary) = self.item_summary() else { return };
+        for (id, (name, location)) in ids {
+            let item = summary.intern_item(name, location);
+            summary.attribute_query_invocation_to_item(id, phase, item);
+        }
+    }
+
+    /// Records the items of the codegen unit `cgu_name`, given as their name,
+    /// location and size estimate, for `-Z time-by-item`.
+    pub fn add_codegen_unit_items(
+        &self,
+        cgu_name: &str,
+        items: impl Iterator<Item = (String, String, u64)>,
+    ) {
+        let Some(summary) = self.item_summary() else { return };
+        let cgu = summary.intern_codegen_unit(cgu_name);
+        let items = items
+            .map(|(name, location, size)| (summary.intern_item(name, location), size))
+            .collect();
+        summary.add_codegen_unit_items(cgu, items);
+    }
+
+    /// Renders the `top_n` items with the most time attributed to them by
+    /// `-Z time-by-item`, if item times are recorded.
+    pub fn render_item_times(&self, top_n: usize) -> Option<String> {
+        self.item_summary().map(|summary| summary.render_items(top_n))
+    }
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>, Option<SummaryTimer<'a>>);
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -681,12 +870,29 @@ pub fn start(
         let raw_profiler = &profiler.profiler;
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        TimingGuard(Some(timing_guard), None)
+    }
+
+    /// Like `start`, but also records the event in the summary, if any.
+    #[inline]
+    fn start_with_summary(
+        profiler: &'a SelfProfiler,
+        event_kind: StringId,
+        event_id: EventId,
+        summary_event: SummaryEvent,
+    ) -> TimingGuard<'a> {
+        let summary_timer =
+            profiler.summary.as_ref().map(|summary| SummaryTimer::start(summary, summary_event));
+        TimingGuard(TimingGuard::start(profiler, event_kind, event_id).0, summary_timer)
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard(guard, mut summary_timer) = self;
+        if let Some(summary_timer) = &mut summary_timer {
+            summary_timer.set_query_invocation_id(query_invocation_id);
+        }
+        if let Some(guard) = guard {
             cold_path(|| {
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
@@ -697,7 +903,7 @@ pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocatio
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard(None, None)
     }
 
     #[inline(always)]
@@ -0,0 +1,507 @@
+//! In-process aggregation of self-profiling events.
+//!
+//! With `-Z self-profile-summary`, the `SelfProfiler` additionally keeps
+//! per-item statistics about the events it records, so that a summary can be
+//! printed at the end of compilation without post-processing the raw
+//! `measureme` data.
+//!
+//! Query events are recorded under their `QueryInvocationId`, since the query
+//! name is not known when the event happens. They are attributed to their query
+//! when the query caches are walked at the end of the session, see
+//! `ProfileSummary::attribute_query_invocations`.
+//!
+//! With `-Z time-by-item`, the same data is used to attribute time to source
+//! items instead: the query invocations of a few queries keyed by items are
+//! attributed to their key, monomorphization is timed per item, and the time
+//! spent in a codegen unit is split between its items according to their size
+//! estimates.
+//!
+//! Times are always measured with `std::time::Instant`, independently of the
+//! counter used for the raw event data.
+
+use super::QueryInvocationId;
+use crate::fx::{FxHashMap, FxIndexMap};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// The output format of `-Z self-profile-summary`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SelfProfileSummaryFormat {
+    Table,
+    Json,
+}
+
+/// The phases of compilation that `-Z time-by-item` attributes to items.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemPhase {
+    TypeCheck,
+    BorrowCheck,
+    MirOptimization,
+    Monomorphization,
+    Codegen,
+}
+
+impl ItemPhase {
+    const ALL: [ItemPhase; 5] = [
+        ItemPhase::TypeCheck,
+        ItemPhase::BorrowCheck,
+        ItemPhase::MirOptimization,
+        ItemPhase::Monomorphization,
+        ItemPhase::Codegen,
+    ];
+
+    fn description(self) -> &'static str {
+        match self {
+            ItemPhase::TypeCheck => "Type check",
+            ItemPhase::BorrowCheck => "Borrow check",
+            ItemPhase::MirOptimization => "MIR opt.",
+            ItemPhase::Monomorphization => "Mono.",
+            ItemPhase::Codegen => "Codegen",
+        }
+    }
+}
+
+/// The kinds of interval events that are aggregated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SummaryEvent {
+    GenericActivity(&'static str),
+    QueryProvider,
+    QueryBlocked,
+    IncrementalLoadResult,
+    IncrementalResultHashing,
+    /// Work done in `ItemPhase` for the item with the given index.
+    Item(ItemPhase, u32),
+    /// Work done for the codegen unit with the given index.
+    CodegenUnit(u32),
+}
+
+impl SummaryEvent {
+    /// Events that only attribute time to an item or codegen unit are not
+    /// nested in other events: the time spent in them stays the self time of
+    /// the enclosing event. Their own self time only excludes nested queries,
+    /// which do work for other items.
+    fn is_attribution(self) -> bool {
+        matches!(self, SummaryEvent::Item(..) | SummaryEvent::CodegenUnit(_))
+    }
+}
+
+/// Aggregated statistics of a query or generic activity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ItemStats {
+    /// Number of executions (of the provider, for queries).
+    count: u64,
+    /// Time spent in the item itself, excluding nested events.
+    self_time: Duration,
+    /// Time spent in the item, including nested events.
+    total_time: Duration,
+    cache_hits: u64,
+    blocked_time: Duration,
+    incremental_loads: u64,
+    incremental_load_time: Duration,
+    incremental_hashing_time: Duration,
+}
+
+impl ItemStats {
+    fn merge(&mut self, other: &ItemStats) {
+        self.count += other.count;
+        self.self_time += other.self_time;
+        self.total_time += other.total_time;
+        self.cache_hits += other.cache_hits;
+        self.blocked_time += other.blocked_time;
+        self.incremental_loads += other.incremental_loads;
+        self.incremental_load_time += other.incremental_load_time;
+        self.incremental_hashing_time += other.incremental_hashing_time;
+    }
+}
+
+/// The time attributed to a source item by `-Z time-by-item`.
+#[derive(Default)]
+struct ItemTime {
+    location: String,
+    /// Indexed by `ItemPhase`.
+    phases: [Duration; ItemPhase::ALL.len()],
+}
+
+#[derive(Default)]
+struct CodegenUnitTime {
+    /// The items in the codegen unit, with their size estimates.
+    items: Vec<(u32, u64)>,
+    time: Duration,
+}
+
+#[derive(Default)]
+struct SummaryData {
+    /// Statistics of query events not yet attributed to a query.
+    invocations: FxHashMap<u32, ItemStats>,
+    queries: FxHashMap<&'static str, ItemStats>,
+    activities: FxHashMap<&'static str, ItemStats>,
+    /// Keyed by item name.
+    items: FxIndexMap<String, ItemTime>,
+    /// Keyed by codegen unit name.
+    codegen_units: FxIndexMap<String, CodegenUnitTime>,
+}
+
+#[derive(Default)]
+pub(super) struct ProfileSummary {
+    data: Mutex<SummaryData>,
+}
+
+/// The time spent in events nested in a running summary timer so far.
+#[derive(Clone, Copy, Default)]
+struct NestedTime {
+    all: Duration,
+    /// The part of `all` spent in query providers, including those nested in
+    /// other events.
+    queries: Duration,
+}
+
+thread_local! {
+    /// For every summary timer running on this thread, from outermost to
+    /// innermost, the time spent in events nested in it so far.
+    static NESTED_TIME: RefCell<Vec<NestedTime>> = RefCell::new(Vec::new());
+}
+
+pub(super) struct SummaryTimer<'a> {
+    summary: &'a ProfileSummary,
+    event: SummaryEvent,
+    query_invocation_id: Option<u32>,
+    start: Instant,
+    /// Position of this timer in `NESTED_TIME`.
+    depth: usize,
+}
+
+impl<'a> SummaryTimer<'a> {
+    pub(super) fn start(summary: &'a ProfileSummary, event: SummaryEvent) -> SummaryTimer<'a> {
+        let depth = NESTED_TIME.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(NestedTime::default());
+            stack.len() - 1
+        });
+        SummaryTimer { summary, event, query_invocation_id: None, start: Instant::now(), depth }
+    }
+
+    pub(super) fn set_query_invocation_id(&mut self, id: QueryInvocationId) {
+        self.query_invocation_id = Some(id.0);
+    }
+}
+
+impl Drop for SummaryTimer<'_> {
+    fn drop(&mut self) {
+        let total_time = self.start.elapsed();
+        let nested = NESTED_TIME.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let nested = stack.get(self.depth).copied().unwrap_or_default();
+            // Timers are expected to be dropped in LIFO order; if they are not,
+            // this discards the entries of timers that outlived their parent.
+            stack.truncate(self.depth);
+            if let Some(parent) = stack.last_mut() {
+                parent.all += if self.event.is_attribution() { nested.all } else { total_time };
+                parent.queries += if self.event == SummaryEvent::QueryProvider {
+                    total_time
+                } else {
+                    nested.queries
+                };
+            }
+            nested
+        });
+        let nested_time = if self.event.is_attribution() { nested.queries } else { nested.all };
+        let self_time = total_time.saturating_sub(nested_time);
+        self.summary.record_interval(self.event, self.query_invocation_id, self_time, total_time);
+    }
+}
+
+impl ProfileSummary {
+    fn record_interval(
+        &self,
+        event: SummaryEvent,
+        query_invocation_id: Option<u32>,
+        self_time: Duration,
+        total_time: Duration,
+    ) {
+        let mut data = self.data.lock();
+        let stats = match (event, query_invocation_id) {
+            (SummaryEvent::Item(phase, item), _) => {
+                data.items[item as usize].phases[phase as usize] += self_time;
+                return;
+            }
+            (SummaryEvent::CodegenUnit(cgu), _) => {
+                data.codegen_units[cgu as usize].time += self_time;
+                return;
+            }
+            (SummaryEvent::GenericActivity(label), _) => data.activities.entry(label).or_default(),
+            (_, Some(id)) => data.invocations.entry(id).or_default(),
+            // Query events that were not finished with an invocation id cannot
+            // be attributed to anything.
+            (_, None) => return,
+        };
+        match event {
+            SummaryEvent::GenericActivity(_) | SummaryEvent::QueryProvider => {
+                stats.count += 1;
+                stats.self_time += self_time;
+                stats.total_time += total_time;
+            }
+            SummaryEvent::QueryBlocked => stats.blocked_time += total_time,
+            SummaryEvent::IncrementalLoadResult => {
+                stats.incremental_loads += 1;
+                stats.incremental_load_time += total_time;
+            }
+            SummaryEvent::IncrementalResultHashing => {
+                stats.incremental_hashing_time += total_time;
+            }
+            SummaryEvent::Item(..) | SummaryEvent::CodegenUnit(_) => unreachable!(),
+        }
+    }
+
+    pub(super) fn record_cache_hit(&self, query_invocation_id: QueryInvocationId) {
+        self.data.lock().invocations.entry(query_invocation_id.0).or_default().cache_hits += 1;
+    }
+
+    /// Attributes the statistics recorded for the given query invocations to
+    /// the query `query_name`.
+    pub(super) fn attribute_query_invocations(
+        &self,
+        query_name: &'static str,
+        ids: impl Iterator<Item = QueryInvocationId>,
+    ) {
+        let mut data = self.data.lock();
+        let mut query_stats = ItemStats::default();
+        for id in ids {
+            if let Some(stats) = data.invocations.remove(&id.0) {
+                query_stats.merge(&stats);
+            }
+        }
+        if query_stats != ItemStats::default() {
+            data.queries.entry(query_name).or_default().merge(&query_stats);
+        }
+    }
+
+    /// Returns the index of the item `name`, adding it if necessary.
+    pub(super) fn intern_item(&self, name: String, location: String) -> u32 {
+        let mut data = self.data.lock();
+        let index = match data.items.get_index_of(&name) {
+            Some(index) => index,
+            None => data.items.insert_full(name, ItemTime { location, ..Default::default() }).0,
+        };
+        index as u32
+    }
+
+    /// Returns the index of the codegen unit `name`, adding it if necessary.
+    pub(super) fn intern_codegen_unit(&self, name: &str) -> u32 {
+        let mut data = self.data.lock();
+        let index = match data.codegen_units.get_index_of(name) {
+            Some(index) => index,
+            None => data.codegen_units.insert_full(name.to_owned(), Default::default()).0,
+        };
+        index as u32
+    }
+
+    /// Attributes the self time of the query invocation `id` to `item`.
+    pub(super) fn attribute_query_invocation_to_item(
+        &self,
+        id: QueryInvocationId,
+        phase: ItemPhase,
+        item: u32,
+    ) {
+        let mut data = self.data.lock();
+        if let Some(stats) = data.invocations.get(&id.0) {
+            let self_time = stats.self_time;
+            data.items[item as usize].phases[phase as usize] += self_time;
+        }
+    }
+
+    /// Records the items of a codegen unit, with their size estimates. The time
+    /// spent on the codegen unit is split between them proportionally to their
+    /// size.
+    pub(super) fn add_codegen_unit_items(&self, cgu: u32, items: Vec<(u32, u64)>) {
+        self.data.lock().codegen_units[cgu as usize].items.extend(items);
+    }
+
+    /// Renders the `top_n` items with the most time attributed to them.
+    pub(super) fn render_items(&self, top_n: usize) -> String {
+        let data = self.data.lock();
+
+        let mut phases: Vec<[Duration; ItemPhase::ALL.len()]> =
+            data.items.values().map(|item| item.phases).collect();
+        for cgu in data.codegen_units.values() {
+            let total_size: u64 = cgu.items.iter().map(|&(_, size)| size).sum();
+            if total_size == 0 {
+                continue;
+            }
+            for &(item, size) in &cgu.items {
+                let share = cgu.time.mul_f64(size as f64 / total_size as f64);
+                phases[item as usize][ItemPhase::Codegen as usize] += share;
+            }
+        }
+
+        let mut items: Vec<(&str, &str, Duration, [Duration; ItemPhase::ALL.len()])> = data
+            .items
+            .iter()
+            .zip(phases)
+            .map(|((name, item), phases)| {
+                (&name[..], &item.location[..], phases.iter().sum(), phases)
+            })
+            .filter(|&(_, _, total, _)| !total.is_zero())
+            .collect();
+        items.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+        items.truncate(top_n);
+
+        let mut header = vec!["Item", "Location", "Total"];
+        header.extend(ItemPhase::ALL.map(ItemPhase::description));
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .map(|(name, location, total, phases)| {
+                let mut row = vec![name.to_string(), location.to_string(), format!("{total:.2?}")];
+                row.extend(phases.iter().map(|time| format!("{time:.2?}")));
+                row
+            })
+            .collect();
+
+        let mut out = String::new();
+        write_table(&mut out, &header, &rows, 2);
+        out
+    }
+
+    /// Renders the summary, with items sorted by decreasing self time.
+    pub(super) fn render(&self, format: SelfProfileSummaryFormat) -> String {
+        let data = self.data.lock();
+
+        let mut unknown = ItemStats::default();
+        for stats in data.invocations.values() {
+            unknown.merge(stats);
+        }
+
+        let mut items: Vec<(&str, &str, ItemStats)> = data
+            .queries
+            .iter()
+            .map(|(&name, &stats)| ("query", name, stats))
+            .chain(data.activities.iter().map(|(&name, &stats)| ("activity", name, stats)))
+            .collect();
+        if unknown != ItemStats::default() {
+            items.push(("query", "<unknown>", unknown));
+        }
+        items.sort_by(|a, b| b.2.self_time.cmp(&a.2.self_time).then(a.1.cmp(b.1)));
+
+        let total_self_time: Duration = items.iter().map(|(.., stats)| stats.self_time).sum();
+        match format {
+            SelfProfileSummaryFormat::Table => render_table(&items, total_self_time),
+            SelfProfileSummaryFormat::Json => render_json(&items, total_self_time),
+        }
+    }
+}
+
+fn render_table(items: &[(&str, &str, ItemStats)], total_self_time: Duration) -> String {
+    let header = [
+        "Item",
+        "Self time",
+        "% of total",
+        "Time",
+        "Count",
+        "Cache hits",
+        "Blocked time",
+        "Incr. loads",
+        "Incr. load time",
+        "Incr. hashing time",
+    ];
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|(_, name, stats)| {
+            let percent = if total_self_time.is_zero() {
+                0.0
+            } else {
+                stats.self_time.as_secs_f64() * 100.0 / total_self_time.as_secs_f64()
+            };
+            vec![
+                name.to_string(),
+                format!("{:.2?}", stats.self_time),
+                format!("{percent:.2}"),
+                format!("{:.2?}", stats.total_time),
+                stats.count.to_string(),
+                stats.cache_hits.to_string(),
+                format!("{:.2?}", stats.blocked_time),
+                stats.incremental_loads.to_string(),
+                format!("{:.2?}", stats.incremental_load_time),
+                format!("{:.2?}", stats.incremental_hashing_time),
+            ]
+        })
+        .collect();
+
+    let mut out = String::new();
+    write_table(&mut out, &header, &rows, 1);
+    writeln!(out, "\nTotal self time: {:.2?}", total_self_time).unwrap();
+    out
+}
+
+/// Writes a table with the given header and rows to `out`. The first
+/// `left_aligned` columns are left-aligned, all others are right-aligned.
+fn write_table(out: &mut String, header: &[&str], rows: &[Vec<String>], left_aligned: usize) {
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let header: Vec<String> = header.iter().map(|cell| cell.to_string()).collect();
+    let separator: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    for cells in [&header, &separator].into_iter().chain(rows) {
+        for (i, (cell, &width)) in cells.iter().zip(&widths).enumerate() {
+            if i < left_aligned {
+                write!(out, "| {cell:<width$} ").unwrap();
+            } else {
+                write!(out, "| {cell:>width$} ").unwrap();
+            }
+        }
+        out.push_str("|\n");
+    }
+}
+
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    total_self_time_ns: u128,
+    items: Vec<JsonItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonItem<'a> {
+    kind: &'a str,
+    name: &'a str,
+    count: u64,
+    self_time_ns: u128,
+    total_time_ns: u128,
+    cache_hits: u64,
+    blocked_time_ns: u128,
+    incremental_loads: u64,
+    incremental_load_time_ns: u128,
+    incremental_hashing_time_ns: u128,
+}
+
+fn render_json(items: &[(&str, &str, ItemStats)], total_self_time: Duration) -> String {
+    let summary = JsonSummary {
+        total_self_time_ns: total_self_time.as_nanos(),
+        items: items
+            .iter()
+            .map(|&(kind, name, stats)| JsonItem {
+                kind,
+                name,
+                count: stats.count,
+                self_time_ns: stats.self_time.as_nanos(),
+                total_time_ns: stats.total_time.as_nanos(),
+                cache_hits: stats.cache_hits,
+                blocked_time_ns: stats.blocked_time.as_nanos(),
+                incremental_loads: stats.incremental_loads,
+                incremental_load_time_ns: stats.incremental_load_time.as_nanos(),
+                incremental_hashing_time_ns: stats.incremental_hashing_time.as_nanos(),
+            })
+            .collect(),
+    };
+    let mut out = serde_json::to_string(&summary).unwrap();
+    out.push('\n');
+    out
+}
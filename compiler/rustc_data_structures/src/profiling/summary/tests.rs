@@ -0,0 +1,130 @@
+use super::*;
+
+fn stats(summary: &ProfileSummary, name: &str) -> ItemStats {
+    let data = summary.data.lock();
+    data.queries.get(name).or_else(|| data.activities.get(name)).copied().unwrap()
+}
+
+#[test]
+fn nested_time_is_excluded_from_self_time() {
+    let summary = ProfileSummary::default();
+    {
+        let _outer = SummaryTimer::start(&summary, SummaryEvent::GenericActivity("outer"));
+        let _inner = SummaryTimer::start(&summary, SummaryEvent::GenericActivity("inner"));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let outer = stats(&summary, "outer");
+    let inner = stats(&summary, "inner");
+    assert_eq!(outer.count, 1);
+    assert_eq!(inner.count, 1);
+    assert!(inner.self_time >= Duration::from_millis(10));
+    assert_eq!(outer.self_time + inner.total_time, outer.total_time);
+}
+
+#[test]
+fn query_events_are_attributed_by_invocation_id() {
+    let summary = ProfileSummary::default();
+    for id in [1, 2, 3] {
+        let mut timer = SummaryTimer::start(&summary, SummaryEvent::QueryProvider);
+        timer.set_query_invocation_id(QueryInvocationId(id));
+    }
+    summary.record_cache_hit(QueryInvocationId(1));
+    summary.record_cache_hit(QueryInvocationId(2));
+
+    summary.attribute_query_invocations("type_of", [1, 2].into_iter().map(QueryInvocationId));
+
+    let type_of = stats(&summary, "type_of");
+    assert_eq!(type_of.count, 2);
+    assert_eq!(type_of.cache_hits, 2);
+
+    // Invocation 3 was never attributed to a query.
+    let json = summary.render(SelfProfileSummaryFormat::Json);
+    assert!(json.contains("\"name\":\"type_of\",\"count\":2,"));
+    assert!(json.contains("\"name\":\"<unknown>\",\"count\":1,"));
+}
+
+#[test]
+fn query_events_without_invocation_id_are_dropped() {
+    let summary = ProfileSummary::default();
+    drop(SummaryTimer::start(&summary, SummaryEvent::QueryProvider));
+    assert_eq!(
+        summary.render(SelfProfileSummaryFormat::Json),
+        "{\"total_self_time_ns\":0,\"items\":[]}\n"
+    );
+}
+
+#[test]
+fn table_has_a_row_per_item() {
+    let summary = ProfileSummary::default();
+    drop(SummaryTimer::start(&summary, SummaryEvent::GenericActivity("parse_crate")));
+    drop(SummaryTimer::start(&summary, SummaryEvent::GenericActivity("codegen_crate")));
+
+    let table = summary.render(SelfProfileSummaryFormat::Table);
+    let lines: Vec<&str> = table.lines().collect();
+    // Header, separator, two items, an empty line and the total.
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with("| Item "));
+    assert!(lines[1].starts_with("| ----"));
+    assert!(lines[2..4].iter().any(|line| line.starts_with("| parse_crate ")));
+    assert!(lines[2..4].iter().any(|line| line.starts_with("| codegen_crate ")));
+    assert!(lines[5].starts_with("Total self time: "));
+}
+
+fn item_phases(summary: &ProfileSummary, name: &str) -> [Duration; ItemPhase::ALL.len()] {
+    summary.data.lock().items[name].phases
+}
+
+#[test]
+fn item_time_excludes_nested_queries_only() {
+    let summary = ProfileSummary::default();
+    let item = summary.intern_item("foo".to_owned(), "foo.rs:1:1: 1:10".to_owned());
+    {
+        let _activity = SummaryTimer::start(&summary, SummaryEvent::GenericActivity("collect"));
+        let _item =
+            SummaryTimer::start(&summary, SummaryEvent::Item(ItemPhase::Monomorphization, item));
+        {
+            let _nested = SummaryTimer::start(&summary, SummaryEvent::GenericActivity("nested"));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let mut query = SummaryTimer::start(&summary, SummaryEvent::QueryProvider);
+        query.set_query_invocation_id(QueryInvocationId(0));
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let mono = item_phases(&summary, "foo")[ItemPhase::Monomorphization as usize];
+    let nested = stats(&summary, "nested");
+    let collect = stats(&summary, "collect");
+    let query = summary.data.lock().invocations[&0];
+    assert!(mono >= nested.total_time);
+    assert!(mono < collect.total_time);
+    // The item timer is transparent to the enclosing activity.
+    assert_eq!(collect.self_time + nested.total_time + query.total_time, collect.total_time);
+}
+
+#[test]
+fn codegen_unit_time_is_split_by_size() {
+    let summary = ProfileSummary::default();
+    let small = summary.intern_item("small".to_owned(), String::new());
+    let large = summary.intern_item("large".to_owned(), String::new());
+    let cgu = summary.intern_codegen_unit("cgu.0");
+    summary.add_codegen_unit_items(cgu, vec![(small, 1), (large, 3)]);
+    summary.data.lock().codegen_units[cgu as usize].time = Duration::from_millis(400);
+
+    let report = summary.render_items(1);
+    let lines: Vec<&str> = report.lines().collect();
+    // Header, separator and the top item.
+    assert_eq!(lines.len(), 3);
+    assert!(lines[2].starts_with("| large "));
+    assert!(lines[2].contains(" 300.00ms "));
+}
+
+#[test]
+fn json_is_escaped() {
+    let summary = ProfileSummary::default();
+    drop(SummaryTimer::start(&summary, SummaryEvent::GenericActivity("a \"quoted\\\" name")));
+
+    let json = summary.render(SelfProfileSummaryFormat::Json);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["items"][0]["name"], "a \"quoted\\\" name");
+}
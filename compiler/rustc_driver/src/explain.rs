@@ -0,0 +1,376 @@
+//! The explanations of the error codes printed by `--explain`, and the check of their
+//! erroneous examples done by `-Z check-explanations`.
+//!
+//! The erroneous examples are the code blocks tagged `compile_fail` followed by the error
+//! codes they trigger. The check compiles each of them the way rustdoc would compile it as a
+//! doctest, and reports the ones that trigger other errors than the ones they are annotated
+//! with, which happens when a diagnostic changes without its explanation being updated.
+
+use rustc_errors::registry::{InvalidErrorCode, Registry};
+use rustc_errors::{pluralize, ColorConfig};
+use rustc_lexer::TokenKind;
+use rustc_log::stdout_isatty;
+use rustc_session::config::ErrorOutputType;
+use rustc_session::{early_error, early_error_no_abort};
+
+use std::collections::BTreeSet;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+
+const BOLD: &str = "\x1b[1m";
+const KEYWORD: &str = "\x1b[1;35m";
+const LITERAL: &str = "\x1b[32m";
+const LIFETIME: &str = "\x1b[33m";
+const COMMENT: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// The keywords highlighted in the examples.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "union",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+pub(crate) fn handle_explain(
+    registry: &Registry,
+    code: &str,
+    format: Option<&str>,
+    color: ColorConfig,
+    output: ErrorOutputType,
+) {
+    let (code, description) = find_description(registry, code, output);
+    match format {
+        None | Some("text") => {
+            if stdout_isatty() {
+                show_content_with_pager(description, color);
+            } else {
+                print!("{}", format_explanation(description, color == ColorConfig::Always));
+            }
+        }
+        Some("json") => {
+            let explanation = serde_json::json!({
+                "code": code,
+                "explanation": format_explanation(description, false),
+            });
+            println!("{explanation}");
+        }
+        Some(format) => early_error(
+            output,
+            &format!("argument for `--format` must be text or json (instead was `{format}`)"),
+        ),
+    }
+}
+
+fn find_description(
+    registry: &Registry,
+    code: &str,
+    output: ErrorOutputType,
+) -> (String, &'static str) {
+    let upper_cased_code = code.to_ascii_uppercase();
+    let normalised = if upper_cased_code.starts_with('E') {
+        upper_cased_code
+    } else {
+        format!("E{0:0>4}", code)
+    };
+    match registry.try_find_description(&normalised) {
+        Ok(Some(description)) => (normalised, description),
+        Ok(None) => early_error(output, &format!("no extended information for {}", code)),
+        Err(InvalidErrorCode) => {
+            early_error(output, &format!("{} is not a valid error code", code))
+        }
+    }
+}
+
+/// Removes the tags of the code blocks of `description` and the lines of the examples that
+/// are hidden from the reader. If `highlight` is set, the headers and the Rust code blocks
+/// are highlighted with ANSI escape codes.
+fn format_explanation(description: &str, highlight: bool) -> String {
+    let mut text = String::new();
+    // The code of the code block being read, and whether it is Rust code.
+    let mut code_block: Option<(String, bool)> = None;
+    for line in description.lines() {
+        let indent_level = line.find(|c: char| !c.is_whitespace()).unwrap_or_else(|| line.len());
+        let dedented_line = &line[indent_level..];
+        if let Some(tags) = dedented_line.strip_prefix("```") {
+            match code_block.take() {
+                Some((code, true)) if highlight => text.push_str(&highlight_rust(&code)),
+                Some((code, _)) => text.push_str(&code),
+                None => code_block = Some((String::new(), is_rust_code_block(tags))),
+            }
+            text.push_str(&line[..(indent_level + 3)]);
+        } else if let Some((code, _)) = &mut code_block {
+            if !dedented_line.starts_with("# ") {
+                code.push_str(line);
+                code.push('\n');
+            }
+            continue;
+        } else if highlight && dedented_line.starts_with('#') {
+            text.push_str(&format!("{BOLD}{line}{RESET}"));
+        } else {
+            text.push_str(line);
+        }
+        text.push('\n');
+    }
+    if let Some((code, _)) = code_block {
+        text.push_str(&code);
+    }
+    text
+}
+
+/// Like rustdoc, consider that code blocks contain Rust code unless tagged otherwise.
+fn is_rust_code_block(tags: &str) -> bool {
+    let first_tag = tags.split(|c: char| c == ',' || c.is_whitespace()).next();
+    !matches!(first_tag, Some("text" | "plain" | "sh" | "console" | "toml" | "json"))
+}
+
+fn highlight_rust(code: &str) -> String {
+    let mut highlighted = String::with_capacity(code.len());
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(code) {
+        let text = &code[pos..pos + token.len as usize];
+        pos += token.len as usize;
+        let style = match token.kind {
+            TokenKind::LineComment { .. } | TokenKind::BlockComment { .. } => COMMENT,
+            TokenKind::Ident if KEYWORDS.contains(&text) => KEYWORD,
+            TokenKind::Literal { .. } => LITERAL,
+            TokenKind::Lifetime { .. } => LIFETIME,
+            _ => {
+                highlighted.push_str(text);
+                continue;
+            }
+        };
+        highlighted.push_str(style);
+        highlighted.push_str(text);
+        highlighted.push_str(RESET);
+    }
+    highlighted
+}
+
+fn show_content_with_pager(description: &str, color: ColorConfig) {
+    let pager_name = env::var_os("PAGER").unwrap_or_else(|| {
+        if cfg!(windows) { OsString::from("more.com") } else { OsString::from("less") }
+    });
+    // Other pagers may show the escape codes instead of the colors.
+    let is_less = Path::new(&pager_name).file_stem().map_or(false, |stem| stem == "less");
+    let highlight = match color {
+        ColorConfig::Always => true,
+        ColorConfig::Never => false,
+        ColorConfig::Auto => is_less,
+    };
+    let content = format_explanation(description, highlight);
+
+    let mut pager = Command::new(&pager_name);
+    if is_less && highlight {
+        pager.arg("-R");
+    }
+
+    let mut fallback_to_println = false;
+
+    match pager.stdin(Stdio::piped()).spawn() {
+        Ok(mut pager) => {
+            if let Some(pipe) = pager.stdin.as_mut() {
+                if pipe.write_all(content.as_bytes()).is_err() {
+                    fallback_to_println = true;
+                }
+            }
+
+            if pager.wait().is_err() {
+                fallback_to_println = true;
+            }
+        }
+        Err(_) => {
+            fallback_to_println = true;
+        }
+    }
+
+    // If pager fails for whatever reason, we should still print the content
+    // to standard output
+    if fallback_to_println {
+        print!("{}", content);
+    }
+}
+
+/// An example of an explanation tagged `compile_fail` and the error codes it triggers.
+struct ErroneousExample {
+    source: String,
+    error_codes: BTreeSet<String>,
+    edition: &'static str,
+}
+
+/// Compiles the erroneous examples of the explanation of `code`, or of every explanation if
+/// there is no `code`, and reports the ones that do not trigger exactly the error codes they
+/// are annotated with.
+pub(crate) fn check_explanations(registry: &Registry, code: Option<&str>, output: ErrorOutputType) {
+    let explanations = match code {
+        Some(code) => vec![find_description(registry, code, output)],
+        None => rustc_error_codes::DIAGNOSTICS
+            .iter()
+            .filter_map(|&(code, description)| Some((code.to_string(), description?)))
+            .collect(),
+    };
+
+    let rustc = env::current_exe().unwrap_or_else(|e| {
+        early_error(output, &format!("cannot find the path of the compiler: {e}"))
+    });
+    let out_dir = env::temp_dir().join(format!("rustc-check-explanations-{}", process::id()));
+    if let Err(e) = fs::create_dir_all(&out_dir) {
+        early_error(output, &format!("cannot create `{}`: {e}", out_dir.display()));
+    }
+
+    let mut checked = 0;
+    let mut failures = 0;
+    for (code, description) in &explanations {
+        for (index, example) in erroneous_examples(description).iter().enumerate() {
+            checked += 1;
+            let problem = match emitted_error_codes(&rustc, &out_dir, example) {
+                Ok(emitted) if emitted == example.error_codes => continue,
+                Ok(emitted) if emitted.is_empty() => "compiles without errors".to_string(),
+                Ok(emitted) => format!(
+                    "triggers {} instead of {}",
+                    list_codes(&emitted),
+                    list_codes(&example.error_codes)
+                ),
+                Err(error) => format!("could not be checked: {error}"),
+            };
+            failures += 1;
+            early_error_no_abort(
+                output,
+                &format!("erroneous example {} of {code} {problem}", index + 1),
+            );
+        }
+    }
+    let _ = fs::remove_dir_all(&out_dir);
+
+    if failures > 0 {
+        early_error(
+            output,
+            &format!(
+                "{failures} of the {checked} erroneous examples do not trigger exactly \
+                 their error codes"
+            ),
+        );
+    }
+    println!(
+        "checked {checked} erroneous example{} of {} error code{}",
+        pluralize!(checked),
+        explanations.len(),
+        pluralize!(explanations.len())
+    );
+}
+
+fn erroneous_examples(description: &str) -> Vec<ErroneousExample> {
+    let mut examples = Vec::new();
+    let mut lines = description.lines();
+    while let Some(line) = lines.next() {
+        let Some(tags) = line.trim_start().strip_prefix("```") else { continue };
+        let code: Vec<&str> =
+            lines.by_ref().take_while(|line| !line.trim_start().starts_with("```")).collect();
+
+        let tags: Vec<&str> = tags.split(',').map(str::trim).collect();
+        if !tags.contains(&"compile_fail") || tags.iter().any(|tag| tag.starts_with("ignore")) {
+            continue;
+        }
+        let error_codes: BTreeSet<String> =
+            tags.iter().filter(|tag| is_error_code(tag)).map(|tag| tag.to_string()).collect();
+        // Without error codes, the example is only expected to fail to compile.
+        if error_codes.is_empty() {
+            continue;
+        }
+        let edition = tags
+            .iter()
+            .find_map(|&tag| match tag {
+                "edition2015" => Some("2015"),
+                "edition2018" | "rust2018" => Some("2018"),
+                "edition2021" => Some("2021"),
+                _ => None,
+            })
+            .unwrap_or("2015");
+        examples.push(ErroneousExample { source: example_source(&code), error_codes, edition });
+    }
+    examples
+}
+
+fn is_error_code(tag: &str) -> bool {
+    tag.len() == 5 && tag.starts_with('E') && tag[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Builds the crate of an example like rustdoc does for doctests: the hidden lines are part
+/// of it, the leading crate attributes are kept at the top and the rest is wrapped in a
+/// `main` function unless it has one.
+fn example_source(lines: &[&str]) -> String {
+    let mut crate_attrs = String::new();
+    let mut body = String::new();
+    let mut in_crate_attrs = true;
+    for &line in lines {
+        let trimmed = line.trim();
+        let line = if trimmed == "#" { "" } else { trimmed.strip_prefix("# ").unwrap_or(line) };
+        let trimmed = line.trim();
+        if in_crate_attrs && trimmed.starts_with("#![") {
+            crate_attrs.push_str(line);
+            crate_attrs.push('\n');
+            continue;
+        }
+        if !trimmed.is_empty() && !trimmed.starts_with("//") {
+            in_crate_attrs = false;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+
+    let mut source = String::from("#![allow(unused)]\n");
+    source.push_str(&crate_attrs);
+    if body.contains("fn main") {
+        source.push_str(&body);
+    } else {
+        source.push_str("fn main() {\n");
+        source.push_str(&body);
+        source.push_str("}\n");
+    }
+    source
+}
+
+/// Compiles an example and returns the codes of the errors it triggers.
+fn emitted_error_codes(
+    rustc: &Path,
+    out_dir: &Path,
+    example: &ErroneousExample,
+) -> Result<BTreeSet<String>, String> {
+    let mut child = Command::new(rustc)
+        .args(["-", "--crate-name", "rust_out", "--error-format=json", "--emit=metadata"])
+        .args(["--edition", example.edition])
+        .arg("--out-dir")
+        .arg(out_dir)
+        // Examples of errors about unstable features use them.
+        .env("RUSTC_BOOTSTRAP", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("cannot run the compiler: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(example.source.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+
+    let mut codes = BTreeSet::new();
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        let Ok(diagnostic) = serde_json::from_str::<serde_json::Value>(line) else { continue };
+        if diagnostic["level"] != "error" {
+            continue;
+        }
+        // Lints denied by the example have their name as code.
+        if let Some(code) = diagnostic["code"]["code"].as_str().filter(|code| is_error_code(code)) {
+            codes.insert(code.to_string());
+        }
+    }
+    Ok(codes)
+}
+
+fn list_codes(codes: &BTreeSet<String>) -> String {
+    codes.iter().map(String::as_str).collect::<Vec<_>>().join(", ")
+}
@@ -21,13 +21,12 @@
 use rustc_codegen_ssa::{traits::CodegenBackend, CodegenErrors, CodegenResults};
 use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
 use rustc_data_structures::sync::SeqCst;
-use rustc_errors::registry::{InvalidErrorCode, Registry};
+use rustc_errors::registry::Registry;
 use rustc_errors::{ErrorGuaranteed, PResult};
 use rustc_feature::find_gated_cfg;
 use rustc_interface::util::{self, collect_crate_types, get_codegen_backend};
 use rustc_interface::{interface, Queries};
 use rustc_lint::LintStore;
-use rustc_log::stdout_isatty;
 use rustc_metadata::locator;
 use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
@@ -46,23 +45,24 @@
 use std::cmp::max;
 use std::default::Default;
 use std::env;
-use std::ffi::OsString;
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
 use std::panic::{self, catch_unwind};
 use std::path::PathBuf;
-use std::process::{self, Command, Stdio};
+use std::process;
 use std::str;
 use std::sync::LazyLock;
 use std::time::Instant;
 
 pub mod args;
+mod explain;
 pub mod pretty;
 mod session_diagnostics;
 
 use crate::session_diagnostics::{
     RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch, RLinkRustcVersionMismatch,
-    RLinkWrongFileType, RlinkNotAFile, RlinkUnableToRead,
+    RLinkWrongFileType, RlinkNotAFile, RlinkUnableToRead, TranslateCheckFailed,
+    TranslateCheckNoBundle, TranslateCheckProblem, TranslateCheckSummary,
 };
 
 /// Exit status code used for successful compilation and help output.
@@ -206,10 +206,27 @@ fn run_compiler(
 
     let sopts = config::build_session_options(&matches);
 
+    if sopts.unstable_opts.check_explanations {
+        let code = matches.opt_str("explain");
+        explain::check_explanations(&diagnostics_registry(), code.as_deref(), sopts.error_format);
+        return Ok(());
+    }
+
     if let Some(ref code) = matches.opt_str("explain") {
-        handle_explain(diagnostics_registry(), code, sopts.error_format);
+        let format = matches.opt_str("format");
+        let color = config::parse_color(&matches);
+        explain::handle_explain(
+            &diagnostics_registry(),
+            code,
+            format.as_deref(),
+            color,
+            sopts.error_format,
+        );
         return Ok(());
     }
+    if matches.opt_present("format") {
+        early_error(sopts.error_format, "`--format` can only be used with `--explain`");
+    }
 
     let cfg = interface::parse_cfgspecs(matches.opt_strs("cfg"));
     let check_cfg = interface::parse_check_cfg(matches.opt_strs("check-cfg"));
@@ -227,6 +244,7 @@ fn run_compiler(
         parse_sess_created: None,
         register_lints: None,
         override_queries: None,
+        register_mir_passes: None,
         make_codegen_backend,
         registry: diagnostics_registry(),
     };
@@ -299,7 +317,8 @@ fn run_compiler(
         .and_then(|| {
             list_metadata(sess, &*compiler.codegen_backend().metadata_loader(), compiler.input())
         })
-        .and_then(|| try_process_rlink(sess, compiler));
+        .and_then(|| try_process_rlink(sess, compiler))
+        .and_then(|| check_translation(sess));
 
         if should_stop == Compilation::Stop {
             return sess.compile_status();
@@ -403,6 +422,10 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.unstable_opts.print_type_sizes_json {
+                sess.code_stats.print_type_sizes_json();
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
@@ -489,78 +512,6 @@ pub fn and_then<F: FnOnce() -> Compilation>(self, next: F) -> Compilation {
     }
 }
 
-fn handle_explain(registry: Registry, code: &str, output: ErrorOutputType) {
-    let upper_cased_code = code.to_ascii_uppercase();
-    let normalised = if upper_cased_code.starts_with('E') {
-        upper_cased_code
-    } else {
-        format!("E{0:0>4}", code)
-    };
-    match registry.try_find_description(&normalised) {
-        Ok(Some(description)) => {
-            let mut is_in_code_block = false;
-            let mut text = String::new();
-            // Slice off the leading newline and print.
-            for line in description.lines() {
-                let indent_level =
-                    line.find(|c: char| !c.is_whitespace()).unwrap_or_else(|| line.len());
-                let dedented_line = &line[indent_level..];
-                if dedented_line.starts_with("```") {
-                    is_in_code_block = !is_in_code_block;
-                    text.push_str(&line[..(indent_level + 3)]);
-                } else if is_in_code_block && dedented_line.starts_with("# ") {
-                    continue;
-                } else {
-                    text.push_str(line);
-                }
-                text.push('\n');
-            }
-            if stdout_isatty() {
-                show_content_with_pager(&text);
-            } else {
-                print!("{}", text);
-            }
-        }
-        Ok(None) => {
-            early_error(output, &format!("no extended information for {}", code));
-        }
-        Err(InvalidErrorCode) => {
-            early_error(output, &format!("{} is not a valid error code", code));
-        }
-    }
-}
-
-fn show_content_with_pager(content: &str) {
-    let pager_name = env::var_os("PAGER").unwrap_or_else(|| {
-        if cfg!(windows) { OsString::from("more.com") } else { OsString::from("less") }
-    });
-
-    let mut fallback_to_println = false;
-
-    match Command::new(pager_name).stdin(Stdio::piped()).spawn() {
-        Ok(mut pager) => {
-            if let Some(pipe) = pager.stdin.as_mut() {
-                if pipe.write_all(content.as_bytes()).is_err() {
-                    fallback_to_println = true;
-                }
-            }
-
-            if pager.wait().is_err() {
-                fallback_to_println = true;
-            }
-        }
-        Err(_) => {
-            fallback_to_println = true;
-        }
-    }
-
-    // If pager fails for whatever reason, we should still print the content
-    // to standard output
-    if fallback_to_println {
-        print!("{}", content);
-    }
-}
-
 pub fn try_process_rlink(sess: &Session, compiler: &interface::Compiler) -> Compilation {
     if sess.opts.unstable_opts.link_only {
         if let Input::File(file) = compiler.input() {
@@ -604,6 +555,30 @@ pub fn try_process_rlink(sess: &Session, compiler: &interface::Compiler) -> Comp
     }
 }
 
+/// Checks the translation of the locale requested with `-Z translate-lang` against the
+/// built-in messages, for `-Z translate-check`, and reports each problem as a note.
+fn check_translation(sess: &Session) -> Compilation {
+    if !sess.opts.unstable_opts.translate_check {
+        return Compilation::Continue;
+    }
+    let (Some(locale), Some(bundle)) =
+        (&sess.opts.unstable_opts.translate_lang, &sess.translation_bundle)
+    else {
+        sess.emit_fatal(TranslateCheckNoBundle);
+    };
+
+    let (problems, checked) =
+        rustc_errors::check_translation(bundle, rustc_errors::DEFAULT_LOCALE_RESOURCES);
+    for problem in &problems {
+        sess.emit_note(TranslateCheckProblem { problem: problem.to_string() });
+    }
+    sess.emit_note(TranslateCheckSummary { checked, count: problems.len() });
+    if !problems.is_empty() {
+        sess.emit_err(TranslateCheckFailed { locale: locale.to_string(), count: problems.len() });
+    }
+    Compilation::Stop
+}
+
 pub fn list_metadata(
     sess: &Session,
     metadata_loader: &dyn MetadataLoader,
@@ -1179,9 +1154,10 @@ pub fn catch_fatal_errors<F: FnOnce() -> R, R>(f: F) -> Result<R, ErrorGuarantee
 }
 
 /// Variant of `catch_fatal_errors` for the `interface::Result` return type
-/// that also computes the exit code.
+/// that also computes the exit code, and writes the SARIF log of the session.
 pub fn catch_with_exit_code(f: impl FnOnce() -> interface::Result<()>) -> i32 {
     let result = catch_fatal_errors(f).and_then(|result| result);
+    rustc_errors::sarif::write_stderr_log();
     match result {
         Ok(()) => EXIT_SUCCESS,
         Err(_) => EXIT_FAILURE,
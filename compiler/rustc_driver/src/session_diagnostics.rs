@@ -38,3 +38,27 @@ pub(crate) struct UnprettyDumpFail {
     pub path: String,
     pub err: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(driver_translate_check_no_bundle)]
+pub(crate) struct TranslateCheckNoBundle;
+
+#[derive(Diagnostic)]
+#[diag(driver_translate_check_problem)]
+pub(crate) struct TranslateCheckProblem {
+    pub problem: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(driver_translate_check_summary)]
+pub(crate) struct TranslateCheckSummary {
+    pub checked: usize,
+    pub count: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(driver_translate_check_failed)]
+pub(crate) struct TranslateCheckFailed {
+    pub locale: String,
+    pub count: usize,
+}
@@ -165,6 +165,10 @@ codegen_ssa_link_script_write_failure = failed to write link script to {$path}:
 
 codegen_ssa_failed_to_write = failed to write {$path}: {$error}
 
+codegen_ssa_stack_sizes_read_failure = failed to read the stack sizes of `{$path}`: {$error}
+
+codegen_ssa_stack_sizes_report_unsupported = `-Z emit-stack-sizes-report` is only supported for targets using ELF object files
+
 codegen_ssa_unable_to_write_debugger_visualizer = Unable to write debugger visualizer file `{$path}`: {$error}
 
 codegen_ssa_rlib_archive_build_failure = failed to build archive from rlib: {$error}
@@ -11,3 +11,17 @@ driver_rlink_rustc_version_mismatch = .rlink file was produced by rustc version
 driver_rlink_no_a_file = rlink must be a file
 
 driver_unpretty_dump_fail = pretty-print failed to write `{$path}` due to error `{$err}`
+
+driver_translate_check_no_bundle = `-Z translate-check` requires the locale to check to be set with `-Z translate-lang`
+
+driver_translate_check_problem = {$problem}
+
+driver_translate_check_summary = checked {$checked} messages, found {$count ->
+        [one] 1 problem
+       *[other] {$count} problems
+    }
+
+driver_translate_check_failed = the translation for `{$locale}` has {$count ->
+        [one] 1 problem
+       *[other] {$count} problems
+    }
@@ -410,8 +410,12 @@ lint_node_source = `forbid` level set here
 
 lint_command_line_source = `forbid` lint level was set on command line
 
+lint_config_file_source = `forbid` lint level was set in the lint configuration file
+
 lint_malformed_attribute = malformed lint attribute input
 
+lint_print_lint_levels_invalid_position = `-Z print-lint-levels` expects a `FILE:LINE:COL` position in a source file of the crate, found `{$position}`
+
 lint_bad_attribute_argument = bad attribute argument
 
 lint_reason_must_be_string_literal = reason must be a string literal
@@ -425,6 +429,8 @@ lint_unsupported_group = `{$lint_group}` lint group is not supported with ´--fo
 
 lint_requested_level = requested on the command line with `{$level} {$lint_name}`
 
+lint_requested_level_config = requested in the lint configuration file with `{$lint_name} = "{$level}"`
+
 lint_check_name_unknown = unknown lint: `{$lint_name}`
     .help = did you mean: `{$suggestion}`
 
@@ -21,3 +21,18 @@ monomorphize_large_assignments =
     moving {$size} bytes
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
+
+monomorphize_reachable_panic = `{$function}` is marked `#[no_panic]` but can panic
+    .call_chain = the panic is reached through {$call_chain}
+    .others = {$others ->
+        [one] there is one other place
+        *[other] there are {$others} other places
+    } where a panic is reachable
+
+monomorphize_panic_call = the panic is raised by this call to `{$callee}`
+
+monomorphize_panic_assert = this check panics if it fails
+
+monomorphize_panic_unchecked_call = the body of the callee of this call is not checked, so it may panic
+
+monomorphize_panic_virtual_drop = this drop runs the destructor of a trait object, whose body is not checked
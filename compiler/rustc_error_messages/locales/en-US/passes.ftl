@@ -707,3 +707,6 @@ passes_ignored_derived_impls =
       [one] trait {$trait_list}, but this is
      *[other] traits {$trait_list}, but these are
     } intentionally ignored during dead code analysis
+
+passes_item_deps_write_error =
+    failed to write item dependencies to `{$path}`: {$error}
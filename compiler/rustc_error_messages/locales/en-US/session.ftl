@@ -45,6 +45,29 @@ session_split_debuginfo_unstable_platform = `-Csplit-debuginfo={$debuginfo}` is
 
 session_file_is_not_writeable = output file {$file} is not writeable -- check its permissions
 
+session_fix_bundle_write_error = failed to write fix bundle to `{$path}`: {$error}
+
+session_lint_baseline_write_error = failed to write lint baseline to `{$path}`: {$error}
+
+session_stale_lint_baseline_entries = {$count ->
+        [one] 1 entry
+       *[other] {$count} entries
+    } of the lint baseline `{$path}` no longer {$count ->
+        [one] matches
+       *[other] match
+    } any lint
+    .note = stale entries: {$entries}
+    .help = run with `-Z update-lint-baseline` to record the current lints
+
+session_missing_translations_summary =
+    {$count ->
+        [one] 1 diagnostic message has
+       *[other] {$count} diagnostic messages have
+    } no translation for `{$locale}` and {$count ->
+        [one] was
+       *[other] were
+    } emitted in `en-US`: {$ids}
+
 session_crate_name_does_not_match = `--crate-name` and `#[crate_name]` are required to match, but `{$s}` != `{$name}`
 
 session_crate_name_invalid = crate names cannot start with a `-`, but `{$s}` has a leading hyphen
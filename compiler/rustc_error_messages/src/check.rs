@@ -0,0 +1,164 @@
+//! Validation of translations against the built-in "en-US" messages, for `-Z translate-check`.
+
+use crate::FluentBundle;
+use fluent_bundle::FluentResource;
+use fluent_syntax::ast;
+use std::fmt;
+
+/// A problem with a translation, found by [`check_translation`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TranslationProblem {
+    /// The message, or its value, is not translated.
+    MissingMessage { message: String },
+    /// An attribute of the message, used by a subdiagnostic, is not translated.
+    MissingAttribute { message: String, attr: String },
+    /// The translation refers to a variable that the "en-US" message doesn't use, so the
+    /// diagnostic might not set it, which would make the translation fail when it is emitted.
+    UnknownVariable { message: String, attr: Option<String>, variable: String },
+}
+
+impl fmt::Display for TranslationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationProblem::MissingMessage { message } => {
+                write!(f, "missing message `{message}`")
+            }
+            TranslationProblem::MissingAttribute { message, attr } => {
+                write!(f, "missing attribute `{message}.{attr}`")
+            }
+            TranslationProblem::UnknownVariable { message, attr: None, variable } => {
+                write!(f, "unknown variable `${variable}` in `{message}`")
+            }
+            TranslationProblem::UnknownVariable { message, attr: Some(attr), variable } => {
+                write!(f, "unknown variable `${variable}` in `{message}.{attr}`")
+            }
+        }
+    }
+}
+
+/// Checks that `bundle` translates every message and attribute of the built-in "en-US"
+/// `resources`, which contain the slugs of all `#[diag]` and subdiagnostic attributes of the
+/// compiler, and that the translations only refer to variables that the diagnostics set.
+///
+/// Returns the problems that were found, sorted by message, and the number of messages that
+/// were checked.
+pub fn check_translation(
+    bundle: &FluentBundle,
+    resources: &[&'static str],
+) -> (Vec<TranslationProblem>, usize) {
+    let mut problems = vec![];
+    let mut checked = 0;
+    for resource in resources {
+        let resource = FluentResource::try_new(resource.to_string())
+            .expect("failed to parse fallback fluent resource");
+        for entry in resource.entries() {
+            let ast::Entry::Message(message) = entry else { continue };
+            checked += 1;
+            check_message(bundle, message, &mut problems);
+        }
+    }
+    problems.sort();
+    (problems, checked)
+}
+
+fn check_message(
+    bundle: &FluentBundle,
+    expected: &ast::Message<&str>,
+    problems: &mut Vec<TranslationProblem>,
+) {
+    let id = expected.id.name;
+    let Some(translated) = bundle.get_message(id) else {
+        problems.push(TranslationProblem::MissingMessage { message: id.to_owned() });
+        return;
+    };
+
+    // Diagnostics set the same variables for the message and for all of its attributes.
+    let mut variables = vec![];
+    for pattern in expected.value.iter().chain(expected.attributes.iter().map(|a| &a.value)) {
+        collect_variables(pattern, &mut variables);
+    }
+
+    match translated.value() {
+        Some(pattern) => check_variables(id, None, pattern, &variables, problems),
+        None if expected.value.is_some() => {
+            problems.push(TranslationProblem::MissingMessage { message: id.to_owned() })
+        }
+        None => {}
+    }
+    for attr in &expected.attributes {
+        let attr = attr.id.name;
+        match translated.get_attribute(attr) {
+            Some(translated) => {
+                check_variables(id, Some(attr), translated.value(), &variables, problems)
+            }
+            None => problems.push(TranslationProblem::MissingAttribute {
+                message: id.to_owned(),
+                attr: attr.to_owned(),
+            }),
+        }
+    }
+}
+
+fn check_variables(
+    message: &str,
+    attr: Option<&str>,
+    pattern: &ast::Pattern<&str>,
+    known_variables: &[&str],
+    problems: &mut Vec<TranslationProblem>,
+) {
+    let mut variables = vec![];
+    collect_variables(pattern, &mut variables);
+    for variable in variables {
+        if !known_variables.contains(&variable) {
+            problems.push(TranslationProblem::UnknownVariable {
+                message: message.to_owned(),
+                attr: attr.map(str::to_owned),
+                variable: variable.to_owned(),
+            });
+        }
+    }
+}
+
+fn collect_variables<'a>(pattern: &ast::Pattern<&'a str>, variables: &mut Vec<&'a str>) {
+    for element in &pattern.elements {
+        if let ast::PatternElement::Placeable { expression } = element {
+            collect_expression_variables(expression, variables);
+        }
+    }
+}
+
+fn collect_expression_variables<'a>(
+    expression: &ast::Expression<&'a str>,
+    variables: &mut Vec<&'a str>,
+) {
+    match expression {
+        ast::Expression::Select { selector, variants } => {
+            collect_inline_variables(selector, variables);
+            for variant in variants {
+                collect_variables(&variant.value, variables);
+            }
+        }
+        ast::Expression::Inline(expression) => collect_inline_variables(expression, variables),
+    }
+}
+
+fn collect_inline_variables<'a>(
+    expression: &ast::InlineExpression<&'a str>,
+    variables: &mut Vec<&'a str>,
+) {
+    match expression {
+        ast::InlineExpression::VariableReference { id } => variables.push(id.name),
+        ast::InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in &arguments.positional {
+                collect_inline_variables(argument, variables);
+            }
+            for argument in &arguments.named {
+                collect_inline_variables(&argument.value, variables);
+            }
+        }
+        ast::InlineExpression::Placeable { expression } => {
+            collect_expression_variables(expression, variables)
+        }
+        _ => {}
+    }
+}
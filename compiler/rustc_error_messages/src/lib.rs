@@ -8,6 +8,8 @@
 #[macro_use]
 extern crate tracing;
 
+mod check;
+
 use fluent_bundle::FluentResource;
 use fluent_syntax::parser::ParserError;
 use rustc_data_structures::sync::Lrc;
@@ -30,6 +32,7 @@
 #[cfg(not(parallel_compiler))]
 use intl_memoizer::IntlLangMemoizer;
 
+pub use check::{check_translation, TranslationProblem};
 pub use fluent_bundle::{self, FluentArgs, FluentError, FluentValue};
 
 pub use unic_langid::{langid, LanguageIdentifier};
@@ -156,13 +159,17 @@ fn from(mut errs: Vec<FluentError>) -> Self {
 /// Returns Fluent bundle with the user's locale resources from
 /// `$sysroot/share/locale/$requested_locale/*.ftl`.
 ///
+/// Messages that are missing for the requested locale are looked up in the locales of
+/// `fallback_locales`, in order, before the caller falls back to the built-in "en-US" messages.
+///
 /// If `-Z additional-ftl-path` was provided, load that resource and add it  to the bundle
 /// (overriding any conflicting messages).
 #[instrument(level = "trace")]
 pub fn fluent_bundle(
-    mut user_provided_sysroot: Option<PathBuf>,
-    mut sysroot_candidates: Vec<PathBuf>,
+    user_provided_sysroot: Option<PathBuf>,
+    sysroot_candidates: Vec<PathBuf>,
     requested_locale: Option<LanguageIdentifier>,
+    fallback_locales: &[LanguageIdentifier],
     additional_ftl_path: Option<&Path>,
     with_directionality_markers: bool,
 ) -> Result<Option<Lrc<FluentBundle>>, TranslationBundleError> {
@@ -171,13 +178,25 @@ pub fn fluent_bundle(
     }
 
     let fallback_locale = langid!("en-US");
-    let requested_fallback_locale = requested_locale.as_ref() == Some(&fallback_locale);
+
+    // The locales to load, from the most to the least preferred one. If the user requests the
+    // default locale then don't try to load anything, its messages are built into the compiler.
+    // That is also why a fallback chain ends at the default locale.
+    let mut locales = vec![];
+    if let Some(requested_locale) = requested_locale && requested_locale != fallback_locale {
+        locales.push(requested_locale);
+        for locale in fallback_locales.iter().take_while(|&locale| *locale != fallback_locale) {
+            if !locales.contains(locale) {
+                locales.push(locale.clone());
+            }
+        }
+    }
+    trace!(?locales);
 
     // If there is only `-Z additional-ftl-path`, assume locale is "en-US", otherwise use user
     // provided locale.
-    let locale = requested_locale.clone().unwrap_or(fallback_locale);
-    trace!(?locale);
-    let mut bundle = new_bundle(vec![locale]);
+    let mut bundle =
+        new_bundle(if locales.is_empty() { vec![fallback_locale] } else { locales.clone() });
 
     // Fluent diagnostics can insert directionality isolation markers around interpolated variables
     // indicating that there may be a shift from right-to-left to left-to-right text (or
@@ -186,13 +205,17 @@ pub fn fluent_bundle(
     // surrounding diagnostic messages are right-to-left, then these might be helpful).
     bundle.set_use_isolating(with_directionality_markers);
 
-    // If the user requests the default locale then don't try to load anything.
-    if !requested_fallback_locale && let Some(requested_locale) = requested_locale {
+    // Load the least preferred locale first, so that the messages of each locale override
+    // those of the locales that it falls back to.
+    let sysroots: Vec<_> = user_provided_sysroot.into_iter().chain(sysroot_candidates).collect();
+    for (i, locale) in locales.iter().enumerate().rev() {
+        let overriding = i + 1 < locales.len();
         let mut found_resources = false;
-        for sysroot in user_provided_sysroot.iter_mut().chain(sysroot_candidates.iter_mut()) {
+        for sysroot in &sysroots {
+            let mut sysroot = sysroot.clone();
             sysroot.push("share");
             sysroot.push("locale");
-            sysroot.push(requested_locale.to_string());
+            sysroot.push(locale.to_string());
             trace!(?sysroot);
 
             if !sysroot.exists() {
@@ -218,7 +241,11 @@ pub fn fluent_bundle(
                 let resource =
                     FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
                 trace!(?resource);
-                bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
+                if overriding {
+                    bundle.add_resource_overriding(resource);
+                } else {
+                    bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
+                }
                 found_resources = true;
             }
         }
@@ -7,7 +7,7 @@
 
 use crate::emitter::FileWithAnnotatedLines;
 use crate::snippet::Line;
-use crate::translation::{to_fluent_args, Translate};
+use crate::translation::{to_fluent_args, MissingTranslations, Translate};
 use crate::{
     CodeSuggestion, Diagnostic, DiagnosticId, DiagnosticMessage, Emitter, FluentBundle,
     LazyFallbackBundle, Level, MultiSpan, Style, SubDiagnostic,
@@ -24,6 +24,7 @@ pub struct AnnotateSnippetEmitterWriter {
     source_map: Option<Lrc<SourceMap>>,
     fluent_bundle: Option<Lrc<FluentBundle>>,
     fallback_bundle: LazyFallbackBundle,
+    missing_translations: Option<Lrc<MissingTranslations>>,
 
     /// If true, hides the longer explanation text
     short_message: bool,
@@ -41,6 +42,10 @@ fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
     fn fallback_fluent_bundle(&self) -> &FluentBundle {
         &**self.fallback_bundle
     }
+
+    fn missing_translations(&self) -> Option<&MissingTranslations> {
+        self.missing_translations.as_deref()
+    }
 }
 
 impl Emitter for AnnotateSnippetEmitterWriter {
@@ -111,6 +116,7 @@ pub fn new(
             source_map,
             fluent_bundle,
             fallback_bundle,
+            missing_translations: None,
             short_message,
             ui_testing: false,
             macro_backtrace,
@@ -125,6 +131,15 @@ pub fn ui_testing(mut self, ui_testing: bool) -> Self {
         self
     }
 
+    /// Records the messages that are missing from the primary bundle in `missing_translations`.
+    pub fn track_missing_translations(
+        mut self,
+        missing_translations: Option<Lrc<MissingTranslations>>,
+    ) -> Self {
+        self.missing_translations = missing_translations;
+        self
+    }
+
     fn emit_messages_default(
         &mut self,
         level: &Level,
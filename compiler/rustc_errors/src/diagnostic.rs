@@ -117,6 +117,18 @@ pub struct Diagnostic {
     /// otherwise hash is based on the all the fields
     pub is_lint: bool,
 
+    /// For lints emitted in an item, the def path of the item. This identifies the lint
+    /// in a lint baseline (see `-Z lint-baseline`).
+    pub lint_owner: Option<String>,
+
+    /// For lints emitted in an item, the def path of its module. This groups the lints of
+    /// an edition migration report (see `--edition-migration-report`).
+    pub lint_module: Option<String>,
+
+    /// Whether this is the diagnostic of a lint that is only counted in the edition
+    /// migration report (see `--edition-migration-report`).
+    pub is_edition_migration: bool,
+
     /// With `-Ztrack_diagnostics` enabled,
     /// we print where in rustc this error was emitted.
     pub emitted_at: DiagnosticLocation,
@@ -224,6 +236,9 @@ pub fn new_with_messages(level: Level, messages: Vec<(DiagnosticMessage, Style)>
             args: Default::default(),
             sort_span: DUMMY_SP,
             is_lint: false,
+            lint_owner: None,
+            lint_module: None,
+            is_edition_migration: false,
             emitted_at: DiagnosticLocation::caller(),
         }
     }
@@ -244,6 +259,9 @@ pub fn new_with_code<M: Into<DiagnosticMessage>>(
             args: Default::default(),
             sort_span: DUMMY_SP,
             is_lint: false,
+            lint_owner: None,
+            lint_module: None,
+            is_edition_migration: false,
             emitted_at: DiagnosticLocation::caller(),
         }
     }
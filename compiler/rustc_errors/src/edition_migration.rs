@@ -0,0 +1,136 @@
+//! The report of `--edition-migration-report`.
+//!
+//! With `--edition-migration-report=<edition>`, the migration lints of every edition after
+//! the one of the crate, up to `<edition>`, are forced on. Their diagnostics are not emitted
+//! but counted in the report, by lint and by module, telling apart the ones that have a
+//! machine-applicable suggestion, which `cargo fix` can apply, from the ones that need to be
+//! fixed manually. The module of a lint is the def path of the module of the node that it is
+//! emitted at, for early and late lints alike.
+//!
+//! The report of a compilation is a JSON object like:
+//!
+//! ```json
+//! {
+//!   "edition": "2021",
+//!   "total": 3,
+//!   "machine_applicable": 2,
+//!   "manual": 1,
+//!   "lints": [
+//!     {
+//!       "name": "array_into_iter",
+//!       "total": 3,
+//!       "machine_applicable": 2,
+//!       "manual": 1,
+//!       "modules": [
+//!         { "module": "crate", "machine_applicable": 1, "manual": 1 },
+//!         { "module": "crate::iter", "machine_applicable": 1, "manual": 0 }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+
+use rustc_span::edition::Edition;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Default)]
+struct Counts {
+    machine_applicable: usize,
+    manual: usize,
+}
+
+impl Counts {
+    fn add(&mut self, other: Counts) {
+        self.machine_applicable += other.machine_applicable;
+        self.manual += other.manual;
+    }
+}
+
+#[derive(Serialize)]
+struct ModuleReport<'a> {
+    module: &'a str,
+    machine_applicable: usize,
+    manual: usize,
+}
+
+#[derive(Serialize)]
+struct LintReport<'a> {
+    name: &'a str,
+    total: usize,
+    machine_applicable: usize,
+    manual: usize,
+    modules: Vec<ModuleReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    edition: String,
+    total: usize,
+    machine_applicable: usize,
+    manual: usize,
+    lints: Vec<LintReport<'a>>,
+}
+
+/// The diagnostics of the edition migration lints of a session.
+pub struct EditionMigrationReport {
+    edition: Edition,
+    /// The counts of the diagnostics of each lint in each module.
+    counts: BTreeMap<String, BTreeMap<String, Counts>>,
+}
+
+impl EditionMigrationReport {
+    pub fn new(edition: Edition) -> EditionMigrationReport {
+        EditionMigrationReport { edition, counts: BTreeMap::new() }
+    }
+
+    /// Records a diagnostic of `lint` emitted in `module`.
+    pub(crate) fn record(&mut self, lint: String, module: String, machine_applicable: bool) {
+        let counts = self.counts.entry(lint).or_default().entry(module).or_default();
+        if machine_applicable {
+            counts.machine_applicable += 1;
+        } else {
+            counts.manual += 1;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut total = Counts::default();
+        let lints = self
+            .counts
+            .iter()
+            .map(|(name, modules)| {
+                let mut lint_total = Counts::default();
+                let modules = modules
+                    .iter()
+                    .map(|(module, &counts)| {
+                        lint_total.add(counts);
+                        ModuleReport {
+                            module,
+                            machine_applicable: counts.machine_applicable,
+                            manual: counts.manual,
+                        }
+                    })
+                    .collect();
+                total.add(lint_total);
+                LintReport {
+                    name,
+                    total: lint_total.machine_applicable + lint_total.manual,
+                    machine_applicable: lint_total.machine_applicable,
+                    manual: lint_total.manual,
+                    modules,
+                }
+            })
+            .collect();
+
+        let report = Report {
+            edition: self.edition.to_string(),
+            total: total.machine_applicable + total.manual,
+            machine_applicable: total.machine_applicable,
+            manual: total.manual,
+            lints,
+        };
+        serde_json::to_string_pretty(&report).unwrap()
+    }
+}
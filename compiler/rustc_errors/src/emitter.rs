@@ -14,7 +14,7 @@
 
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
-use crate::translation::{to_fluent_args, Translate};
+use crate::translation::{to_fluent_args, MissingTranslations, Translate};
 use crate::{
     diagnostic::DiagnosticLocation, CodeSuggestion, Diagnostic, DiagnosticId, DiagnosticMessage,
     FluentBundle, Handler, LazyFallbackBundle, Level, MultiSpan, SubDiagnostic,
@@ -225,6 +225,11 @@ fn should_show_explain(&self) -> bool {
         true
     }
 
+    /// Checks if should show the summary of the number of errors and warnings emitted
+    fn should_show_error_count(&self) -> bool {
+        true
+    }
+
     /// Checks if we can use colors in the current output stream.
     fn supports_color(&self) -> bool {
         false
@@ -527,6 +532,10 @@ fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
     fn fallback_fluent_bundle(&self) -> &FluentBundle {
         &**self.fallback_bundle
     }
+
+    fn missing_translations(&self) -> Option<&MissingTranslations> {
+        self.missing_translations.as_deref()
+    }
 }
 
 impl Emitter for EmitterWriter {
@@ -644,6 +653,7 @@ pub struct EmitterWriter {
     sm: Option<Lrc<SourceMap>>,
     fluent_bundle: Option<Lrc<FluentBundle>>,
     fallback_bundle: LazyFallbackBundle,
+    missing_translations: Option<Lrc<MissingTranslations>>,
     short_message: bool,
     teach: bool,
     ui_testing: bool,
@@ -678,6 +688,7 @@ pub fn stderr(
             sm: source_map,
             fluent_bundle,
             fallback_bundle,
+            missing_translations: None,
             short_message,
             teach,
             ui_testing: false,
@@ -704,6 +715,7 @@ pub fn new(
             sm: source_map,
             fluent_bundle,
             fallback_bundle,
+            missing_translations: None,
             short_message,
             teach,
             ui_testing: false,
@@ -718,6 +730,14 @@ pub fn ui_testing(mut self, ui_testing: bool) -> Self {
         self
     }
 
+    pub fn track_missing_translations(
+        mut self,
+        missing_translations: Option<Lrc<MissingTranslations>>,
+    ) -> Self {
+        self.missing_translations = missing_translations;
+        self
+    }
+
     fn maybe_anonymized(&self, line_num: usize) -> Cow<'static, str> {
         if self.ui_testing {
             Cow::Borrowed(ANONYMIZED_LINE_NUM)
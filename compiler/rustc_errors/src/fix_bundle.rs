@@ -0,0 +1,258 @@
+//! Bundling of machine-applicable suggestions into a single patch.
+//!
+//! With `-Z emit-fix-bundle`, the `Handler` keeps every
+//! `Applicability::MachineApplicable` suggestion it emits. At the end of the
+//! session they are turned into one unified diff here, so that tools can apply
+//! all fixes at once instead of replaying individual suggestions.
+//!
+//! A suggestion is applied atomically: either all of its parts end up in the
+//! bundle, or none of them do. Suggestions are considered in emission order, and
+//! a suggestion whose edits overlap an edit accepted earlier is dropped. Edits
+//! that are exactly identical to an accepted one (as happens when the same
+//! suggestion is emitted from several diagnostics) are only applied once.
+//!
+//! Files are diffed in the normalized form held by the `SourceMap`, i.e.
+//! without a byte order mark and with `\n` line endings. They are named
+//! relative to the working directory, so that the bundle can be applied from
+//! there with `patch -p1`, and the suggestions for files outside of it are
+//! left out.
+
+use crate::CodeSuggestion;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Pos, SourceFile};
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::{Component, Path};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of unchanged lines printed around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// A single replacement, with byte offsets relative to the start of its file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Edit {
+    lo: usize,
+    hi: usize,
+    snippet: String,
+}
+
+impl Edit {
+    /// Whether applying both `self` and `other` is ambiguous. Two insertions at
+    /// the same position conflict because their relative order is unknown.
+    fn conflicts_with(&self, other: &Edit) -> bool {
+        (self.lo < other.hi && other.lo < self.hi)
+            || (self.lo == other.lo && (self.lo == self.hi || other.lo == other.hi))
+    }
+}
+
+struct FileEdits {
+    file: Lrc<SourceFile>,
+    edits: Vec<Edit>,
+}
+
+/// Renders the machine-applicable `suggestions` as a unified diff against the
+/// files in `sm`, relative to `working_dir`. Returns an empty string if no
+/// suggestion can be applied.
+pub fn render_fix_bundle(
+    sm: &SourceMap,
+    working_dir: &Path,
+    suggestions: &[CodeSuggestion],
+) -> String {
+    let mut files: FxIndexMap<String, FileEdits> = FxIndexMap::default();
+
+    'suggestions: for suggestion in suggestions {
+        let Some(new_edits) = resolve_suggestion(sm, working_dir, suggestion) else { continue };
+
+        let mut accepted: Vec<(String, Lrc<SourceFile>, Edit)> = vec![];
+        for (path, file, edit) in new_edits {
+            let existing = files.get(&path).map_or(&[][..], |f| &f.edits[..]);
+            if existing.contains(&edit) {
+                continue;
+            }
+            let pending = accepted.iter().filter(|(p, ..)| *p == path).map(|(.., e)| e);
+            if existing.iter().chain(pending).any(|e| e.conflicts_with(&edit)) {
+                continue 'suggestions;
+            }
+            accepted.push((path, file, edit));
+        }
+
+        for (path, file, edit) in accepted {
+            let file_edits = files.entry(path).or_insert_with(|| FileEdits { file, edits: vec![] });
+            file_edits.edits.push(edit);
+        }
+    }
+
+    files.sort_keys();
+    let mut out = String::new();
+    for (path, FileEdits { file, mut edits }) in files {
+        let src = file.src.as_ref().unwrap();
+        edits.sort_by_key(|e| (e.lo, e.hi));
+        render_file_diff(&mut out, &path, src, &edits);
+    }
+    out
+}
+
+/// Turns the single substitution of a machine-applicable suggestion into edits,
+/// or returns `None` if any of its parts cannot be written back to a local file
+/// in `working_dir`.
+fn resolve_suggestion(
+    sm: &SourceMap,
+    working_dir: &Path,
+    suggestion: &CodeSuggestion,
+) -> Option<Vec<(String, Lrc<SourceFile>, Edit)>> {
+    // Several substitutions are alternatives the user has to choose from.
+    if suggestion.applicability != Applicability::MachineApplicable
+        || suggestion.substitutions.len() != 1
+    {
+        return None;
+    }
+
+    suggestion.substitutions[0]
+        .parts
+        .iter()
+        .map(|part| {
+            if part.span.is_dummy() {
+                return None;
+            }
+            let file = sm.lookup_source_file(part.span.lo());
+            if part.span.hi() > file.end_pos || file.src.is_none() {
+                return None;
+            }
+            let FileName::Real(ref name) = file.name else { return None };
+            let path = relative_path(name.local_path()?, working_dir)?.display().to_string();
+            let edit = Edit {
+                lo: (part.span.lo() - file.start_pos).to_usize(),
+                hi: (part.span.hi() - file.start_pos).to_usize(),
+                snippet: part.snippet.clone(),
+            };
+            Some((path, file, edit))
+        })
+        .collect()
+}
+
+/// The path of `path` relative to `working_dir`, if it is in `working_dir`.
+fn relative_path<'a>(path: &'a Path, working_dir: &Path) -> Option<&'a Path> {
+    let path = if path.is_absolute() { path.strip_prefix(working_dir).ok()? } else { path };
+    if path.components().any(|component| component == Component::ParentDir) {
+        return None;
+    }
+    Some(path)
+}
+
+/// Byte ranges of the lines of `src`, each including its line terminator.
+fn line_ranges(src: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    for (i, _) in src.match_indices('\n') {
+        ranges.push(start..i + 1);
+        start = i + 1;
+    }
+    if start < src.len() {
+        ranges.push(start..src.len());
+    }
+    ranges
+}
+
+/// Index of the line containing byte `pos`. An offset at the very end of the
+/// file belongs to the last line.
+fn line_of(lines: &[Range<usize>], pos: usize) -> usize {
+    lines.partition_point(|line| line.end <= pos).min(lines.len().saturating_sub(1))
+}
+
+/// Appends the unified diff of applying the sorted, non-overlapping `edits` to
+/// `src` to `out`.
+fn render_file_diff(out: &mut String, path: &str, src: &str, edits: &[Edit]) {
+    let lines = line_ranges(src);
+
+    // Group the edits into hunks: edits whose context lines would touch or
+    // overlap are printed together.
+    let mut hunks: Vec<(Range<usize>, &[Edit])> = vec![];
+    let mut first = 0;
+    for (i, edit) in edits.iter().enumerate() {
+        let start = line_of(&lines, edit.lo);
+        let end = line_of(&lines, if edit.hi > edit.lo { edit.hi - 1 } else { edit.lo });
+        match hunks.last_mut() {
+            Some((range, hunk_edits)) if start <= range.end + 2 * CONTEXT_LINES => {
+                range.end = range.end.max(end);
+                *hunk_edits = &edits[first..=i];
+            }
+            _ => {
+                first = i;
+                hunks.push((start..end, &edits[i..=i]));
+            }
+        }
+    }
+
+    writeln!(out, "--- a/{path}").unwrap();
+    writeln!(out, "+++ b/{path}").unwrap();
+
+    // Difference between line numbers in the new and the old file, accumulated
+    // over the previous hunks.
+    let mut line_delta: isize = 0;
+    for (range, hunk_edits) in hunks {
+        let first_line = range.start.saturating_sub(CONTEXT_LINES);
+        let last_line = (range.end + CONTEXT_LINES).min(lines.len().saturating_sub(1));
+        let (old_lo, old_hi) =
+            if lines.is_empty() { (0, 0) } else { (lines[first_line].start, lines[last_line].end) };
+
+        let mut new_text = String::new();
+        let mut pos = old_lo;
+        for edit in hunk_edits {
+            new_text.push_str(&src[pos..edit.lo]);
+            new_text.push_str(&edit.snippet);
+            pos = edit.hi;
+        }
+        new_text.push_str(&src[pos..old_hi]);
+
+        let old_lines: Vec<&str> = src[old_lo..old_hi].split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new_text.split_inclusive('\n').collect();
+        let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+        let suffix = old_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let old_start = if old_lines.is_empty() { first_line } else { first_line + 1 };
+        let new_start = (old_start as isize + line_delta) as usize;
+        let new_start = if new_lines.is_empty() { new_start.saturating_sub(1) } else { new_start };
+        writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            old_start,
+            old_lines.len(),
+            new_start,
+            new_lines.len()
+        )
+        .unwrap();
+        line_delta += new_lines.len() as isize - old_lines.len() as isize;
+
+        for line in &old_lines[..prefix] {
+            push_diff_line(out, ' ', line);
+        }
+        for line in &old_lines[prefix..old_lines.len() - suffix] {
+            push_diff_line(out, '-', line);
+        }
+        for line in &new_lines[prefix..new_lines.len() - suffix] {
+            push_diff_line(out, '+', line);
+        }
+        for line in &old_lines[old_lines.len() - suffix..] {
+            push_diff_line(out, ' ', line);
+        }
+    }
+}
+
+fn push_diff_line(out: &mut String, marker: char, line: &str) {
+    out.push(marker);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
@@ -0,0 +1,197 @@
+use super::*;
+
+use crate::{Substitution, SubstitutionPart, SuggestionStyle};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, Span};
+
+use std::env;
+use std::path::PathBuf;
+
+struct TestFile {
+    file: Lrc<SourceFile>,
+}
+
+impl TestFile {
+    fn new(sm: &SourceMap, name: &str, src: &str) -> TestFile {
+        TestFile { file: sm.new_source_file(PathBuf::from(name).into(), src.to_owned()) }
+    }
+
+    /// A replacement of `lo..hi` (relative to the start of the file) by `snippet`.
+    fn part(&self, lo: u32, hi: u32, snippet: &str) -> SubstitutionPart {
+        let span = Span::with_root_ctxt(
+            self.file.start_pos + BytePos(lo),
+            self.file.start_pos + BytePos(hi),
+        );
+        SubstitutionPart { span, snippet: snippet.to_owned() }
+    }
+}
+
+fn suggestion(parts: Vec<SubstitutionPart>, applicability: Applicability) -> CodeSuggestion {
+    CodeSuggestion {
+        substitutions: vec![Substitution { parts }],
+        msg: "test".into(),
+        style: SuggestionStyle::ShowCode,
+        applicability,
+    }
+}
+
+fn machine_applicable(parts: Vec<SubstitutionPart>) -> CodeSuggestion {
+    suggestion(parts, Applicability::MachineApplicable)
+}
+
+fn with_source_map(f: impl FnOnce(&SourceMap)) {
+    rustc_span::create_default_session_globals_then(|| {
+        f(&SourceMap::new(FilePathMapping::empty()));
+    })
+}
+
+fn render(sm: &SourceMap, suggestions: &[CodeSuggestion]) -> String {
+    render_fix_bundle(sm, &env::current_dir().unwrap(), suggestions)
+}
+
+#[test]
+fn single_edit() {
+    with_source_map(|sm| {
+        let f = TestFile::new(sm, "main.rs", "fn main() {\n    let x = 1;\n}\n");
+        let bundle = render(sm, &[machine_applicable(vec![f.part(20, 21, "_x")])]);
+        assert_eq!(
+            bundle,
+            "--- a/main.rs\n\
+             +++ b/main.rs\n\
+             @@ -1,3 +1,3 @@\n \
+             fn main() {\n\
+             -    let x = 1;\n\
+             +    let _x = 1;\n \
+             }\n"
+        );
+    })
+}
+
+#[test]
+fn only_machine_applicable() {
+    with_source_map(|sm| {
+        let f = TestFile::new(sm, "main.rs", "let x = 1;\n");
+        let bundle = render(
+            sm,
+            &[
+                suggestion(vec![f.part(4, 5, "y")], Applicability::MaybeIncorrect),
+                suggestion(vec![f.part(4, 5, "y")], Applicability::HasPlaceholders),
+            ],
+        );
+        assert_eq!(bundle, "");
+    })
+}
+
+#[test]
+fn duplicates_applied_once() {
+    with_source_map(|sm| {
+        let f = TestFile::new(sm, "main.rs", "let x = 1;\n");
+        let bundle = render(
+            sm,
+            &[
+                machine_applicable(vec![f.part(4, 5, "_x")]),
+                machine_applicable(vec![f.part(4, 5, "_x")]),
+            ],
+        );
+        assert_eq!(
+            bundle,
+            "--- a/main.rs\n+++ b/main.rs\n@@ -1,1 +1,1 @@\n-let x = 1;\n+let _x = 1;\n"
+        );
+    })
+}
+
+#[test]
+fn overlapping_suggestion_dropped_atomically() {
+    with_source_map(|sm| {
+        let f = TestFile::new(sm, "main.rs", "let x = y;\n");
+        let bundle = render(
+            sm,
+            &[
+                machine_applicable(vec![f.part(4, 5, "a")]),
+                // Overlaps the first suggestion, so neither of its parts is applied.
+                machine_applicable(vec![f.part(8, 9, "b"), f.part(4, 5, "c")]),
+            ],
+        );
+        assert_eq!(
+            bundle,
+            "--- a/main.rs\n+++ b/main.rs\n@@ -1,1 +1,1 @@\n-let x = y;\n+let a = y;\n"
+        );
+    })
+}
+
+#[test]
+fn insertions_at_same_position_conflict() {
+    with_source_map(|sm| {
+        let f = TestFile::new(sm, "main.rs", "x\n");
+        let bundle = render(
+            sm,
+            &[
+                machine_applicable(vec![f.part(0, 0, "a")]),
+                machine_applicable(vec![f.part(0, 0, "b")]),
+            ],
+        );
+        assert_eq!(bundle, "--- a/main.rs\n+++ b/main.rs\n@@ -1,1 +1,1 @@\n-x\n+ax\n");
+    })
+}
+
+#[test]
+fn multiple_files_and_hunks() {
+    with_source_map(|sm| {
+        let src = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let main = TestFile::new(sm, "main.rs", src);
+        let lib = TestFile::new(sm, "lib.rs", "x");
+        let bundle = render(
+            sm,
+            &[
+                machine_applicable(vec![main.part(0, 2, "")]),
+                machine_applicable(vec![main.part(20, 21, "K"), lib.part(1, 1, "y")]),
+            ],
+        );
+        assert_eq!(
+            bundle,
+            "--- a/lib.rs\n\
+             +++ b/lib.rs\n\
+             @@ -1,1 +1,1 @@\n\
+             -x\n\
+             \\ No newline at end of file\n\
+             +xy\n\
+             \\ No newline at end of file\n\
+             --- a/main.rs\n\
+             +++ b/main.rs\n\
+             @@ -1,4 +1,3 @@\n\
+             -a\n \
+             b\n \
+             c\n \
+             d\n\
+             @@ -8,4 +7,4 @@\n \
+             h\n \
+             i\n \
+             j\n\
+             -k\n\
+             +K\n"
+        );
+    })
+}
+
+#[test]
+fn paths_relative_to_working_dir() {
+    with_source_map(|sm| {
+        let working_dir = env::current_dir().unwrap();
+        let inside = working_dir.join("src").join("main.rs");
+        let outside = working_dir.parent().unwrap().join("other.rs");
+        let main = TestFile::new(sm, inside.to_str().unwrap(), "x\n");
+        let other = TestFile::new(sm, outside.to_str().unwrap(), "x\n");
+        let parent = TestFile::new(sm, "../parent.rs", "x\n");
+        let bundle = render(
+            sm,
+            &[
+                machine_applicable(vec![main.part(0, 1, "y")]),
+                machine_applicable(vec![other.part(0, 1, "y")]),
+                machine_applicable(vec![parent.part(0, 1, "y")]),
+            ],
+        );
+        let path = PathBuf::from("src").join("main.rs");
+        let path = path.display();
+        assert_eq!(bundle, format!("--- a/{path}\n+++ b/{path}\n@@ -1,1 +1,1 @@\n-x\n+y\n"));
+    })
+}
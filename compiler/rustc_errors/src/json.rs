@@ -13,7 +13,7 @@
 
 use crate::emitter::{Emitter, HumanReadableErrorType};
 use crate::registry::Registry;
-use crate::translation::{to_fluent_args, Translate};
+use crate::translation::{to_fluent_args, MissingTranslations, Translate};
 use crate::DiagnosticId;
 use crate::{
     CodeSuggestion, FluentBundle, LazyFallbackBundle, MultiSpan, SpanLabel, SubDiagnostic,
@@ -40,6 +40,7 @@ pub struct JsonEmitter {
     sm: Lrc<SourceMap>,
     fluent_bundle: Option<Lrc<FluentBundle>>,
     fallback_bundle: LazyFallbackBundle,
+    missing_translations: Option<Lrc<MissingTranslations>>,
     pretty: bool,
     ui_testing: bool,
     json_rendered: HumanReadableErrorType,
@@ -66,6 +67,7 @@ pub fn stderr(
             sm: source_map,
             fluent_bundle,
             fallback_bundle,
+            missing_translations: None,
             pretty,
             ui_testing: false,
             json_rendered,
@@ -116,6 +118,7 @@ pub fn new(
             sm: source_map,
             fluent_bundle,
             fallback_bundle,
+            missing_translations: None,
             pretty,
             ui_testing: false,
             json_rendered,
@@ -128,6 +131,13 @@ pub fn new(
     pub fn ui_testing(self, ui_testing: bool) -> Self {
         Self { ui_testing, ..self }
     }
+
+    pub fn track_missing_translations(
+        self,
+        missing_translations: Option<Lrc<MissingTranslations>>,
+    ) -> Self {
+        Self { missing_translations, ..self }
+    }
 }
 
 impl Translate for JsonEmitter {
@@ -138,6 +148,10 @@ fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
     fn fallback_fluent_bundle(&self) -> &FluentBundle {
         &**self.fallback_bundle
     }
+
+    fn missing_translations(&self) -> Option<&MissingTranslations> {
+        self.missing_translations.as_deref()
+    }
 }
 
 impl Emitter for JsonEmitter {
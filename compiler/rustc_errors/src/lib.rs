@@ -23,16 +23,18 @@
 use rustc_lint_defs::LintExpectationId;
 use Level::*;
 
+use edition_migration::EditionMigrationReport;
 use emitter::{is_case_difference, Emitter, EmitterWriter};
+use lint_baseline::{BaselineEntry, LintBaseline};
 use registry::Registry;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
 pub use rustc_error_messages::{
-    fallback_fluent_bundle, fluent, fluent_bundle, DelayDm, DiagnosticMessage, FluentBundle,
-    LanguageIdentifier, LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagnosticMessage,
-    DEFAULT_LOCALE_RESOURCES,
+    check_translation, fallback_fluent_bundle, fluent, fluent_bundle, DelayDm, DiagnosticMessage,
+    FluentBundle, LanguageIdentifier, LazyFallbackBundle, MultiSpan, SpanLabel,
+    SubdiagnosticMessage, TranslationProblem, DEFAULT_LOCALE_RESOURCES,
 };
 pub use rustc_lint_defs::{pluralize, Applicability};
 use rustc_span::source_map::SourceMap;
@@ -52,10 +54,14 @@
 mod diagnostic;
 mod diagnostic_builder;
 mod diagnostic_impls;
+pub mod edition_migration;
 pub mod emitter;
+pub mod fix_bundle;
 pub mod json;
+pub mod lint_baseline;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 pub mod translation;
@@ -432,6 +438,18 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// Machine-applicable suggestions of all emitted diagnostics, only collected
+    /// when `HandlerFlags::collect_machine_applicable_suggestions` is set.
+    machine_applicable_suggestions: Vec<CodeSuggestion>,
+
+    /// The baseline that lint diagnostics are recorded in and suppressed by, if any.
+    /// (rustc: see `-Z lint-baseline`)
+    lint_baseline: Option<LintBaseline>,
+
+    /// The report that the diagnostics of the edition migration lints are recorded in
+    /// instead of being emitted, if any. (rustc: see `--edition-migration-report`)
+    edition_migration_report: Option<EditionMigrationReport>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -494,6 +512,9 @@ pub struct HandlerFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If true, machine-applicable suggestions of emitted diagnostics are kept.
+    /// (rustc: see `-Z emit-fix-bundle`)
+    pub collect_machine_applicable_suggestions: bool,
 }
 
 impl Drop for HandlerInner {
@@ -599,6 +620,9 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                machine_applicable_suggestions: Vec::new(),
+                lint_baseline: None,
+                edition_migration_report: None,
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
@@ -1056,6 +1080,37 @@ pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
 
+    pub fn take_machine_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        std::mem::take(&mut self.inner.borrow_mut().machine_applicable_suggestions)
+    }
+
+    /// Sets the baseline that the lint diagnostics emitted from now on are recorded in,
+    /// and suppressed by if they match one of its entries.
+    pub fn set_lint_baseline(&self, baseline: LintBaseline) {
+        self.inner.borrow_mut().lint_baseline = Some(baseline);
+    }
+
+    /// Returns the entries of the lint baseline that have not matched any lint diagnostic
+    /// emitted so far.
+    pub fn stale_lint_baseline_entries(&self) -> Vec<BaselineEntry> {
+        self.inner.borrow().lint_baseline.as_ref().map_or_else(Vec::new, |b| b.stale_entries())
+    }
+
+    pub fn take_lint_baseline(&self) -> Option<LintBaseline> {
+        self.inner.borrow_mut().lint_baseline.take()
+    }
+
+    /// Sets the report that the diagnostics of the edition migration lints emitted from now
+    /// on are recorded in, instead of being emitted.
+    pub fn set_edition_migration_report(&self, report: EditionMigrationReport) {
+        self.inner.borrow_mut().edition_migration_report = Some(report);
+    }
+
+    /// Renders the edition migration report of the diagnostics recorded so far, if any.
+    pub fn render_edition_migration_report(&self) -> Option<String> {
+        self.inner.borrow().edition_migration_report.as_ref().map(|report| report.render())
+    }
+
     pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
@@ -1282,6 +1337,14 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             return None;
         }
 
+        if self.recorded_for_edition_migration(diagnostic) {
+            return None;
+        }
+
+        if self.suppressed_by_lint_baseline(diagnostic) {
+            return None;
+        }
+
         if let Some(ref code) = diagnostic.code {
             self.emitted_diagnostic_codes.insert(code.clone());
         }
@@ -1313,6 +1376,16 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
             self.emitter.emit_diagnostic(&diagnostic);
+            if self.flags.collect_machine_applicable_suggestions {
+                self.machine_applicable_suggestions.extend(
+                    diagnostic
+                        .suggestions
+                        .iter()
+                        .flatten()
+                        .filter(|sugg| sugg.applicability == Applicability::MachineApplicable)
+                        .cloned(),
+                );
+            }
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
             } else if let Warning(_) = diagnostic.level {
@@ -1365,7 +1438,7 @@ fn print_error_count(&mut self, registry: &Registry) {
             1 => "aborting due to previous error".to_string(),
             count => format!("aborting due to {count} previous errors"),
         };
-        if self.treat_err_as_bug() {
+        if self.treat_err_as_bug() || !self.emitter.should_show_error_count() {
             return;
         }
 
@@ -1497,6 +1570,40 @@ fn emit_diag_at_span(&mut self, mut diag: Diagnostic, sp: impl Into<MultiSpan>)
         self.emit_diagnostic(diag.set_span(sp));
     }
 
+    /// Records a lint diagnostic in the lint baseline, and returns whether the baseline
+    /// suppresses it. Forced warnings are never recorded.
+    fn suppressed_by_lint_baseline(&mut self, diagnostic: &Diagnostic) -> bool {
+        let Some(baseline) = &mut self.lint_baseline else { return false };
+        let Some(DiagnosticId::Lint { name, is_force_warn: false, .. }) = &diagnostic.code else {
+            return false;
+        };
+
+        let sm = self.emitter.source_map();
+        let location =
+            diagnostic.lint_owner.clone().unwrap_or_else(|| primary_file(sm, diagnostic));
+        let args = crate::translation::to_fluent_args(diagnostic.args());
+        let message = self.emitter.translate_messages(&diagnostic.message, &args);
+        baseline.record(BaselineEntry::new(name.clone(), location, &message))
+    }
+
+    /// Records the diagnostic of an edition migration lint in the edition migration report,
+    /// and returns whether it was recorded, in which case it is not emitted.
+    fn recorded_for_edition_migration(&mut self, diagnostic: &Diagnostic) -> bool {
+        let Some(report) = &mut self.edition_migration_report else { return false };
+        let Some(DiagnosticId::Lint { name, .. }) = &diagnostic.code else { return false };
+        if !diagnostic.is_edition_migration {
+            return false;
+        }
+
+        let sm = self.emitter.source_map();
+        let module = diagnostic.lint_module.clone().unwrap_or_else(|| primary_file(sm, diagnostic));
+        let machine_applicable = diagnostic.suggestions.as_ref().map_or(false, |suggestions| {
+            suggestions.iter().any(|s| s.applicability == Applicability::MachineApplicable)
+        });
+        report.record(name.clone(), module, machine_applicable);
+        true
+    }
+
     #[track_caller]
     fn delay_span_bug(
         &mut self,
@@ -1631,6 +1738,17 @@ fn panic_if_treat_err_as_bug(&self) {
     }
 }
 
+/// The file of the primary span of `diagnostic`, which locates lints that are not emitted in
+/// an item.
+fn primary_file(sm: Option<&Lrc<SourceMap>>, diagnostic: &Diagnostic) -> String {
+    match (sm, diagnostic.span.primary_span()) {
+        (Some(sm), Some(span)) => {
+            sm.filename_for_diagnostics(&sm.span_to_filename(span)).to_string()
+        }
+        _ => String::new(),
+    }
+}
+
 struct DelayedDiagnostic {
     inner: Diagnostic,
     note: Backtrace,
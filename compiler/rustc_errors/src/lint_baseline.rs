@@ -0,0 +1,138 @@
+//! Baselines of lint diagnostics, for `-Z lint-baseline`.
+//!
+//! A baseline records every lint diagnostic emitted in a session, identified by the
+//! name of the lint, its location and a hash of its message. The location is the def
+//! path of the item the lint was emitted in, or the file of its primary span for lints
+//! emitted before there are items. Messages are normalized before hashing, so that a
+//! baseline does not go stale when only numbers mentioned in a message change.
+//!
+//! When the `Handler` is given a baseline read from a file, lint diagnostics matching
+//! one of its entries are suppressed. An entry that is recorded several times
+//! suppresses as many diagnostics. Entries that did not match any diagnostic are
+//! stale and can be reported with [`Handler::stale_lint_baseline_entries`].
+//!
+//! [`Handler::stale_lint_baseline_entries`]: crate::Handler::stale_lint_baseline_entries
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::hash::Hash;
+use std::path::Path;
+
+/// Version of the file format, bumped when the way entries are computed changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// A lint diagnostic, as recorded in a baseline.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub lint: String,
+    pub location: String,
+    pub message_hash: String,
+}
+
+impl BaselineEntry {
+    pub fn new(lint: String, location: String, message: &str) -> BaselineEntry {
+        let mut hasher = StableHasher::new();
+        normalize_message(message).hash(&mut hasher);
+        let hash: u64 = hasher.finish();
+        BaselineEntry { lint, location, message_hash: format!("{hash:016x}") }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+/// The baseline of a session.
+#[derive(Default)]
+pub struct LintBaseline {
+    /// The entries read from the baseline file, with the number of diagnostics each of
+    /// them can still suppress. `None` if a new baseline is being recorded.
+    recorded: Option<FxIndexMap<BaselineEntry, usize>>,
+    /// Every lint diagnostic of the session, whether it was suppressed or not.
+    emitted: Vec<BaselineEntry>,
+}
+
+impl LintBaseline {
+    /// Creates a baseline that records the lint diagnostics of the session without
+    /// suppressing any of them.
+    pub fn recording() -> LintBaseline {
+        LintBaseline::default()
+    }
+
+    /// Reads the baseline file at `path`.
+    pub fn load(path: &Path) -> Result<LintBaseline, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: BaselineFile = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        if file.version != FORMAT_VERSION {
+            return Err(format!(
+                "unsupported baseline version {}, expected {FORMAT_VERSION}",
+                file.version
+            ));
+        }
+
+        let mut recorded = FxIndexMap::default();
+        for entry in file.entries {
+            *recorded.entry(entry).or_insert(0) += 1;
+        }
+        Ok(LintBaseline { recorded: Some(recorded), emitted: Vec::new() })
+    }
+
+    /// Whether a new baseline is being recorded, rather than one read from a file.
+    pub fn is_recording(&self) -> bool {
+        self.recorded.is_none()
+    }
+
+    /// Records a lint diagnostic and returns whether it should be suppressed.
+    pub(crate) fn record(&mut self, entry: BaselineEntry) -> bool {
+        let suppress = match self.recorded.as_mut().and_then(|recorded| recorded.get_mut(&entry)) {
+            Some(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                true
+            }
+            _ => false,
+        };
+        self.emitted.push(entry);
+        suppress
+    }
+
+    /// Returns the entries of the baseline file that did not suppress any diagnostic,
+    /// once for each time they were left unused.
+    pub fn stale_entries(&self) -> Vec<BaselineEntry> {
+        let Some(recorded) = &self.recorded else { return Vec::new() };
+        recorded
+            .iter()
+            .flat_map(|(entry, &remaining)| std::iter::repeat(entry.clone()).take(remaining))
+            .collect()
+    }
+
+    /// Renders the lint diagnostics of the session as a baseline file.
+    pub fn render(&self) -> String {
+        let mut entries = self.emitted.clone();
+        entries.sort();
+        let file = BaselineFile { version: FORMAT_VERSION, entries };
+        serde_json::to_string_pretty(&file).unwrap()
+    }
+}
+
+/// Replaces every number in `message` by `N` and collapses runs of whitespace.
+fn normalize_message(message: &str) -> String {
+    let mut normalized = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            while chars.next_if(|c| c.is_ascii_digit()).is_some() {}
+            normalized.push('N');
+        } else if c.is_whitespace() {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            normalized.push(' ');
+        } else {
+            normalized.push(c);
+        }
+    }
+    normalized.trim().to_string()
+}
@@ -0,0 +1,528 @@
+//! A SARIF emitter for errors.
+//!
+//! [SARIF] (Static Analysis Results Interchange Format) is the format consumed by
+//! code-scanning services. Unlike the JSON emitter, which writes one object per
+//! diagnostic as soon as it is emitted, this emitter buffers every diagnostic of
+//! the compilation session and writes a single SARIF log, containing one run.
+//!
+//! A session may create several handlers: early errors and warnings are reported
+//! before the session exists, and rustdoc parses its options with a handler of
+//! its own. The emitters writing to stderr therefore don't write a log when they
+//! are dropped, but add their results to a process-wide log, which the driver
+//! writes once with [`write_stderr_log`]. The summary of the number of errors and
+//! warnings is not reported, as it has no location and no rule.
+//!
+//! Diagnostics are mapped onto SARIF as follows:
+//!
+//! * the error code or lint name becomes the result's `ruleId`, and a matching
+//!   `reportingDescriptor` is added to the tool's `rules`;
+//! * primary spans become `locations`, secondary spans and the spans of child
+//!   diagnostics become `relatedLocations`;
+//! * every substitution of every `CodeSuggestion` becomes one entry in `fixes`.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, MissingTranslations, Translate};
+use crate::{
+    CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, MultiSpan, SubDiagnostic,
+};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_span::Span;
+use std::io::{self, Write};
+use std::mem;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// The results of the emitters created with [`SarifEmitter::stderr`] that have
+/// been dropped, waiting for [`write_stderr_log`].
+static STDERR_LOG: Mutex<Option<PendingLog>> = Mutex::new(None);
+
+/// Writes the results of every emitter created with [`SarifEmitter::stderr`] as
+/// a single SARIF log. This is called by the driver once all the handlers of the
+/// session are dropped; it does nothing if no such emitter was created.
+pub fn write_stderr_log() {
+    let Some(log) = STDERR_LOG.lock().unwrap().take() else { return };
+    if let Err(e) = log.write(&mut io::BufWriter::new(io::stderr())) {
+        panic!("failed to print SARIF log: {:?}", e);
+    }
+}
+
+pub struct SarifEmitter {
+    /// Where to write the log when the emitter is dropped, or `None` to add the
+    /// results to the process-wide log written by [`write_stderr_log`].
+    dst: Option<Box<dyn Write + Send>>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    missing_translations: Option<Lrc<MissingTranslations>>,
+    log: PendingLog,
+}
+
+/// The rules and results of a log that has not been written yet.
+#[derive(Default)]
+struct PendingLog {
+    /// Rules referenced by the results, keyed by error code or lint name. The
+    /// index in this map is the `ruleIndex` of the results referring to it.
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    results: Vec<SarifResult>,
+}
+
+impl PendingLog {
+    /// Moves the rules and results of `other` into this log, renumbering the
+    /// rules its results refer to.
+    fn append(&mut self, other: PendingLog) {
+        let rule_indices: Vec<usize> = other
+            .rules
+            .into_iter()
+            .map(|(id, rule)| {
+                let entry = self.rules.entry(id);
+                let index = entry.index();
+                entry.or_insert(rule);
+                index
+            })
+            .collect();
+        self.results.extend(other.results.into_iter().map(|mut result| {
+            result.rule_index = result.rule_index.map(|index| rule_indices[index]);
+            result
+        }));
+    }
+
+    fn write(&self, dst: &mut dyn Write) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        writeln!(dst, "{}", serde_json::to_string(&log).unwrap())?;
+        dst.flush()
+    }
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: None,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            missing_translations: None,
+            log: PendingLog::default(),
+        }
+    }
+
+    pub fn basic(
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(file_path_mapping)),
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: Some(dst),
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            missing_translations: None,
+            log: PendingLog::default(),
+        }
+    }
+
+    pub fn track_missing_translations(
+        mut self,
+        missing_translations: Option<Lrc<MissingTranslations>>,
+    ) -> Self {
+        self.missing_translations = missing_translations;
+        self
+    }
+
+    /// Returns the index of the rule for `code`, registering it on first use.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let (id, help_uri) = match code {
+            DiagnosticId::Error(code) => {
+                (code.clone(), Some(format!("https://doc.rust-lang.org/error-index.html#{code}")))
+            }
+            DiagnosticId::Lint { name, .. } => (name.clone(), None),
+        };
+        let entry = self.log.rules.entry(id);
+        let index = entry.index();
+        let registry = self.registry.as_ref();
+        entry.or_insert_with_key(|id| {
+            let explanation =
+                registry.and_then(|registry| registry.try_find_description(id).ok().flatten());
+            ReportingDescriptor {
+                id: id.clone(),
+                full_description: explanation.map(|text| Message { text: text.to_owned() }),
+                help_uri,
+            }
+        });
+        index
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        let log = mem::take(&mut self.log);
+        match &mut self.dst {
+            Some(dst) => {
+                if let Err(e) = log.write(dst) {
+                    panic!("failed to print SARIF log: {:?}", e);
+                }
+            }
+            None => STDERR_LOG.lock().unwrap().get_or_insert_with(Default::default).append(log),
+        }
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &**self.fallback_bundle
+    }
+
+    fn missing_translations(&self) -> Option<&MissingTranslations> {
+        self.missing_translations.as_deref()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        let Some(level) = sarif_level(diag.level) else { return };
+        let args = to_fluent_args(diag.args());
+        let rule_index = diag.code.as_ref().map(|code| self.rule_index(code));
+
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in diag.span.span_labels() {
+            let message = span_label
+                .label
+                .as_ref()
+                .map(|label| Message { text: self.translate_message(label, &args).to_string() });
+            let Some(physical_location) = PhysicalLocation::from_span(span_label.span, self) else {
+                continue;
+            };
+            if span_label.is_primary {
+                locations.push(Location { id: None, physical_location, message });
+            } else {
+                related_locations.push(Location { id: None, physical_location, message });
+            }
+        }
+        for child in &diag.children {
+            self.push_child_locations(child, &args, &mut related_locations);
+        }
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|sugg| self.fixes_from_suggestion(sugg, &args))
+            .collect();
+
+        let result = SarifResult {
+            rule_id: rule_index.map(|index| self.log.rules.get_index(index).unwrap().0.clone()),
+            rule_index,
+            level,
+            message: Message { text: self.translate_messages(&diag.message, &args).to_string() },
+            locations,
+            related_locations,
+            fixes,
+        };
+        self.log.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+
+    fn should_show_error_count(&self) -> bool {
+        false
+    }
+}
+
+impl SarifEmitter {
+    /// Child diagnostics (notes and helps) have no SARIF counterpart; their spans
+    /// are attached to the result as related locations carrying the child message.
+    fn push_child_locations(
+        &self,
+        child: &SubDiagnostic,
+        args: &FluentArgs<'_>,
+        related_locations: &mut Vec<Location>,
+    ) {
+        if sarif_level(child.level).is_none() {
+            return;
+        }
+        let text = format!("{}: {}", child.level, self.translate_messages(&child.message, args));
+        let span: &MultiSpan = child.render_span.as_ref().unwrap_or(&child.span);
+        for span in span.primary_spans() {
+            let Some(physical_location) = PhysicalLocation::from_span(*span, self) else {
+                continue;
+            };
+            related_locations.push(Location {
+                id: None,
+                physical_location,
+                message: Some(Message { text: text.clone() }),
+            });
+        }
+    }
+
+    fn fixes_from_suggestion(&self, sugg: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description = self.translate_message(&sugg.msg, args).to_string();
+        sugg.substitutions
+            .iter()
+            .filter_map(|substitution| {
+                // Group the replacements by file, keeping the order in which files
+                // first appear in the substitution.
+                let mut changes: FxIndexMap<String, Vec<Replacement>> = FxIndexMap::default();
+                for part in &substitution.parts {
+                    let (uri, deleted_region) = self.region_from_span(part.span)?;
+                    changes.entry(uri).or_default().push(Replacement {
+                        deleted_region,
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    });
+                }
+                Some(Fix {
+                    description: Message { text: description.clone() },
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: sugg.applicability },
+                })
+            })
+            .collect()
+    }
+
+    fn region_from_span(&self, span: Span) -> Option<(String, Region)> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_offset = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let uri = self.sm.filename_for_diagnostics(&start.file.name).to_string().replace('\\', "/");
+        let region = Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset,
+            byte_length: byte_end - byte_offset,
+        };
+        Some((uri, region))
+    }
+}
+
+/// Maps a diagnostic level onto a SARIF `level`, or `None` for levels that are
+/// never reported.
+fn sarif_level(level: Level) -> Option<&'static str> {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => Some("error"),
+        Level::Warning(_) => Some("warning"),
+        Level::Note | Level::OnceNote | Level::Help | Level::FailureNote => Some("note"),
+        Level::Allow | Level::Expect(_) => None,
+    }
+}
+
+// The following data types are provided just for serialisation. Field names
+// follow the SARIF 2.1.0 schema.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    /// Columns are counted in characters, as in the human-readable output.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code or lint name.
+    id: String,
+    /// The long-form explanation of an error code, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    /// Only set for related locations, which must be uniquely identified within
+    /// a result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+impl PhysicalLocation {
+    fn from_span(span: Span, se: &SarifEmitter) -> Option<PhysicalLocation> {
+        let (uri, region) = se.region_from_span(span)?;
+        Some(PhysicalLocation { artifact_location: ArtifactLocation { uri }, region })
+    }
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
+
+/// SARIF has no notion of how reliable a fix is, so this is recorded in the
+/// fix's property bag.
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+use crate::Handler;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` with a handler backed by a `SarifEmitter` over `code`, and returns
+/// the log written when the handler is dropped.
+fn with_sarif_handler(code: &str, f: impl FnOnce(&Handler)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            None,
+            sm,
+            None,
+            fallback_bundle,
+        );
+
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        f(&handler);
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn single_log_for_session() {
+    let log = with_sarif_handler("fn main() {}\n", |handler| {
+        handler.span_err(span(3, 7), "first");
+        handler.span_warn(span(0, 2), "second");
+    });
+
+    assert_eq!(log["version"], "2.1.0");
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let results = runs[0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["message"]["text"], "first");
+    assert_eq!(results[1]["level"], "warning");
+    assert_eq!(results[1]["message"]["text"], "second");
+}
+
+#[test]
+fn primary_and_secondary_spans() {
+    let log = with_sarif_handler("let x = y;\n", |handler| {
+        let mut msp = MultiSpan::from_span(span(8, 9));
+        msp.push_span_label(span(4, 5), "declared here");
+        handler.struct_span_err(msp, "bad").emit();
+    });
+
+    let result = &log["runs"][0]["results"][0];
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.rs");
+    assert_eq!(location["region"]["startLine"], 1);
+    assert_eq!(location["region"]["startColumn"], 9);
+    assert_eq!(location["region"]["endColumn"], 10);
+    assert_eq!(location["region"]["byteOffset"], 8);
+    assert_eq!(location["region"]["byteLength"], 1);
+
+    let related = &result["relatedLocations"][0];
+    assert_eq!(related["id"], 0);
+    assert_eq!(related["message"]["text"], "declared here");
+    assert_eq!(related["physicalLocation"]["region"]["startColumn"], 5);
+}
+
+#[test]
+fn codes_become_rules() {
+    let log = with_sarif_handler("fn main() {}\n", |handler| {
+        let code = DiagnosticId::Error("E0001".to_owned());
+        handler.struct_span_err_with_code(span(0, 2), "a", code.clone()).emit();
+        handler.struct_span_err_with_code(span(3, 7), "b", code).emit();
+        handler.span_err(span(3, 7), "no code");
+    });
+
+    let run = &log["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0]["id"], "E0001");
+    assert_eq!(run["results"][0]["ruleId"], "E0001");
+    assert_eq!(run["results"][0]["ruleIndex"], 0);
+    assert_eq!(run["results"][1]["ruleIndex"], 0);
+    assert!(run["results"][2].get("ruleId").is_none());
+}
+
+#[test]
+fn suggestions_become_fixes() {
+    let log = with_sarif_handler("let x = y;\n", |handler| {
+        handler
+            .struct_span_err(span(8, 9), "cannot find `y`")
+            .span_suggestion(span(8, 9), "use `x`", "x", Applicability::MachineApplicable)
+            .emit();
+    });
+
+    let fix = &log["runs"][0]["results"][0]["fixes"][0];
+    assert_eq!(fix["description"]["text"], "use `x`");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let change = &fix["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "test.rs");
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 8);
+    assert_eq!(replacement["insertedContent"]["text"], "x");
+}
+
+#[test]
+fn no_error_count_summary() {
+    let log = with_sarif_handler("fn main() {}\n", |handler| {
+        handler.span_err(span(3, 7), "first");
+        handler.span_warn(span(0, 2), "second");
+        handler.print_error_count(&Registry::new(&[]));
+    });
+
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn appended_logs_share_rules() {
+    let pending_log = |codes: &[&str]| {
+        rustc_span::create_default_session_globals_then(|| {
+            let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+            let fallback_bundle = crate::fallback_fluent_bundle(
+                rustc_error_messages::DEFAULT_LOCALE_RESOURCES,
+                false,
+            );
+            let mut se = SarifEmitter::new(Box::new(io::sink()), None, sm, None, fallback_bundle);
+            for code in codes {
+                let mut diag = crate::Diagnostic::new(Level::Error { lint: false }, "a");
+                diag.code(DiagnosticId::Error(code.to_string()));
+                se.emit_diagnostic(&diag);
+            }
+            mem::take(&mut se.log)
+        })
+    };
+
+    let mut log = pending_log(&["E0001"]);
+    log.append(pending_log(&["E0002", "E0001"]));
+
+    assert_eq!(log.rules.keys().collect::<Vec<_>>(), ["E0001", "E0002"]);
+    let rule_indices: Vec<_> = log.results.iter().map(|result| result.rule_index).collect();
+    assert_eq!(rule_indices, [Some(0), Some(1), Some(0)]);
+}
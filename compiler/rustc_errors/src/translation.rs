@@ -1,6 +1,7 @@
 use crate::snippet::Style;
 use crate::{DiagnosticArg, DiagnosticMessage, FluentBundle};
-use rustc_data_structures::sync::Lrc;
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_data_structures::sync::{Lock, Lrc};
 use rustc_error_messages::{
     fluent_bundle::resolver::errors::{ReferenceKind, ResolverError},
     FluentArgs, FluentError,
@@ -28,6 +29,33 @@ pub fn to_fluent_args<'iter, 'arg: 'iter>(
     args
 }
 
+/// The messages that could not be translated with the primary bundle, and that were
+/// translated with the fallback bundle instead.
+///
+/// Shared between the emitters and the session, which reports them at the end of the session.
+#[derive(Default)]
+pub struct MissingTranslations {
+    ids: Lock<FxIndexSet<String>>,
+}
+
+impl MissingTranslations {
+    fn record(&self, identifier: &str, attr: Option<&str>) {
+        let id = match attr {
+            Some(attr) => format!("{identifier}.{attr}"),
+            None => identifier.to_owned(),
+        };
+        self.ids.lock().insert(id);
+    }
+
+    /// Returns the identifiers of the missing messages, as `message` or `message.attribute`,
+    /// sorted.
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<_> = self.ids.lock().iter().cloned().collect();
+        ids.sort();
+        ids
+    }
+}
+
 pub trait Translate {
     /// Return `FluentBundle` with localized diagnostics for the locale requested by the user. If no
     /// language was requested by the user then this will be `None` and `fallback_fluent_bundle`
@@ -39,6 +67,12 @@ pub trait Translate {
     /// unavailable for the requested locale.
     fn fallback_fluent_bundle(&self) -> &FluentBundle;
 
+    /// Return the tracker of messages missing from `fluent_bundle`, if the user asked for a
+    /// summary of them.
+    fn missing_translations(&self) -> Option<&MissingTranslations> {
+        None
+    }
+
     /// Convert `DiagnosticMessage`s to a string, performing translation if necessary.
     fn translate_messages(
         &self,
@@ -102,7 +136,14 @@ fn translate_message<'a>(
             // ..otherwise, for end users, an error about this wouldn't be useful or actionable, so
             // just hide it and try with the fallback bundle.
             .filter(|(_, errs)| errs.is_empty())
-            .or_else(|| translate_with_bundle(self.fallback_fluent_bundle()))
+            .or_else(|| {
+                if self.fluent_bundle().is_some()
+                    && let Some(missing_translations) = self.missing_translations()
+                {
+                    missing_translations.record(identifier, attr.as_deref());
+                }
+                translate_with_bundle(self.fallback_fluent_bundle())
+            })
             .map(|(translated, errs)| {
                 // Always bail out for errors with the fallback bundle.
 
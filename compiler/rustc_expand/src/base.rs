@@ -954,6 +954,7 @@ fn pre_expansion_lint(
         node_id: NodeId,
         attrs: &[Attribute],
         items: &[P<Item>],
+        module_path: &[Symbol],
         name: &str,
     );
 }
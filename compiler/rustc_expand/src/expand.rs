@@ -1116,12 +1116,19 @@ fn wrap_flat_map_node_noop_flat_map(
                     );
 
                 if let Some(lint_store) = ecx.lint_store {
+                    // The path of the module without the name of the crate.
+                    let module_path: Vec<_> = ecx.current_expansion.module.mod_path[1..]
+                        .iter()
+                        .chain(iter::once(&ident))
+                        .map(|ident| ident.name)
+                        .collect();
                     lint_store.pre_expansion_lint(
                         ecx.sess,
                         ecx.resolver.registered_tools(),
                         ecx.current_expansion.lint_node_id,
                         &attrs,
                         &items,
+                        &module_path,
                         ident.name.as_str(),
                     );
                 }
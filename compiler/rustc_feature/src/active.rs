@@ -160,6 +160,9 @@ pub fn set(&self, features: &mut Features, span: Span) {
     (active, intrinsics, "1.0.0", None, None),
     /// Allows using `#[lang = ".."]` attribute for linking items to special compiler logic.
     (active, lang_items, "1.0.0", None, None),
+    /// Allows the `#[no_panic]` attribute, checking that a function cannot panic once
+    /// monomorphized.
+    (active, no_panic, "1.67.0", None, None),
     /// Allows using `#[omit_gdb_pretty_printer_section]`.
     (active, omit_gdb_pretty_printer_section, "1.5.0", None, None),
     /// Allows using `#[prelude_import]` on glob `use` items.
@@ -45,7 +45,7 @@
 use rustc_session::errors::ExprParenthesesNeeded;
 use rustc_session::parse::feature_err;
 use rustc_span::hygiene::DesugaringKind;
-use rustc_span::lev_distance::find_best_match_for_name;
+use rustc_span::lev_distance::{find_best_match_for_name, lev_distance};
 use rustc_span::source_map::{Span, Spanned};
 use rustc_span::symbol::{kw, sym, Ident, Symbol};
 use rustc_target::spec::abi::Abi::RustIntrinsic;
@@ -353,7 +353,7 @@ fn check_expr_kind(
             ExprKind::Struct(qpath, fields, ref base_expr) => {
                 self.check_expr_struct(expr, expected, qpath, fields, base_expr)
             }
-            ExprKind::Field(base, field) => self.check_field(expr, &base, field),
+            ExprKind::Field(base, field) => self.check_field(expr, &base, field, expected),
             ExprKind::Index(base, idx) => self.check_expr_index(base, idx, expr),
             ExprKind::Yield(value, ref src) => self.check_expr_yield(value, expr, src),
             hir::ExprKind::Err => tcx.ty_error(),
@@ -1244,6 +1244,7 @@ fn check_method_call(
                         SelfSource::MethodCall(rcvr),
                         error,
                         Some((rcvr, args)),
+                        expected,
                     ) {
                         err.emit();
                     }
@@ -2155,6 +2156,7 @@ fn check_field(
         expr: &'tcx hir::Expr<'tcx>,
         base: &'tcx hir::Expr<'tcx>,
         field: Ident,
+        expected: Expectation<'tcx>,
     ) -> Ty<'tcx> {
         debug!("check_field(expr: {:?}, base: {:?}, field: {:?})", expr, base, field);
         let base_ty = self.check_expr(base);
@@ -2221,7 +2223,7 @@ fn check_field(
         } else if self.method_exists(field, base_ty, expr.hir_id, true) {
             self.ban_take_value_of_method(expr, base_ty, field);
         } else if !base_ty.is_primitive_ty() {
-            self.ban_nonexisting_field(field, base, expr, base_ty);
+            self.ban_nonexisting_field(field, base, expr, base_ty, expected);
         } else {
             let field_name = field.to_string();
             let mut err = type_error_struct!(
@@ -2342,6 +2344,7 @@ fn ban_nonexisting_field(
         base: &'tcx hir::Expr<'tcx>,
         expr: &'tcx hir::Expr<'tcx>,
         base_ty: Ty<'tcx>,
+        expected: Expectation<'tcx>,
     ) {
         debug!(
             "ban_nonexisting_field: field={:?}, base={:?}, expr={:?}, base_ty={:?}",
@@ -2357,7 +2360,9 @@ fn ban_nonexisting_field(
                 self.suggest_first_deref_field(&mut err, expr, base, ident);
             }
             ty::Adt(def, _) if !def.is_enum() => {
-                self.suggest_fields_on_recordish(&mut err, def, ident, expr.span);
+                self.suggest_fields_on_recordish(
+                    &mut err, base, base_ty, def, ident, expr, expected,
+                );
             }
             ty::Param(param_ty) => {
                 self.point_at_param_definition(&mut err, param_ty);
@@ -2519,32 +2524,119 @@ fn point_at_param_definition(&self, err: &mut Diagnostic, param: ty::ParamTy) {
     fn suggest_fields_on_recordish(
         &self,
         err: &mut Diagnostic,
+        base: &hir::Expr<'_>,
+        base_ty: Ty<'tcx>,
         def: ty::AdtDef<'tcx>,
         field: Ident,
-        access_span: Span,
+        expr: &hir::Expr<'_>,
+        expected: Expectation<'tcx>,
     ) {
-        if let Some(suggested_field_name) =
-            self.suggest_field_name(def.non_enum_variant(), field.name, vec![], access_span)
-        {
-            err.span_suggestion(
-                field.span,
-                "a field with a similar name exists",
-                suggested_field_name,
-                Applicability::MaybeIncorrect,
-            );
-        } else {
-            err.span_label(field.span, "unknown field");
-            let struct_variant_def = def.non_enum_variant();
-            let field_names = self.available_field_names(struct_variant_def, access_span);
-            if !field_names.is_empty() {
-                err.note(&format!(
-                    "available fields are: {}",
-                    self.name_series_display(field_names),
-                ));
+        let mod_id = self.tcx.parent_module(base.hir_id).to_def_id();
+        // Prefer the fields that have the type expected where the field is used, even if
+        // they are nested in other fields or their name is not the closest one.
+        let typed_path = expected
+            .only_has_type(self)
+            .filter(|ty| !ty.is_ty_var() && !ty.references_error())
+            .and_then(|expected| {
+                self.find_similar_field_path(expr.span, base_ty, field, mod_id, |field_ty| {
+                    self.can_coerce(field_ty, expected)
+                })
+            });
+        let path = typed_path
+            .or_else(|| {
+                self.suggest_field_name(def.non_enum_variant(), field.name, vec![], expr.span)
+                    .map(|name| vec![Ident::with_dummy_span(name)])
+            })
+            .or_else(|| self.find_similar_field_path(expr.span, base_ty, field, mod_id, |_| true));
+
+        match path.as_deref() {
+            Some([suggested_field]) => {
+                err.span_suggestion(
+                    field.span,
+                    "a field with a similar name exists",
+                    suggested_field.name,
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            Some(path) => {
+                let path = path
+                    .iter()
+                    .map(|id| id.name.to_ident_string())
+                    .collect::<Vec<String>>()
+                    .join(".");
+                err.span_suggestion(
+                    field.span,
+                    "one of the expressions' fields has a field with a similar name",
+                    path,
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            None => {
+                err.span_label(field.span, "unknown field");
+                let struct_variant_def = def.non_enum_variant();
+                let field_names = self.available_field_names(struct_variant_def, expr.span);
+                if !field_names.is_empty() {
+                    err.note(&format!(
+                        "available fields are: {}",
+                        self.name_series_display(field_names),
+                    ));
+                }
             }
         }
     }
 
+    /// Looks for the fields with a name similar to `field` whose type satisfies `ty_matches`,
+    /// among the fields of `base_ty` and of its deref targets, and the fields nested in them
+    /// as deep as `check_for_nested_field_satisfying` goes. Returns the path to the one with
+    /// the closest name among the least nested ones.
+    fn find_similar_field_path(
+        &self,
+        span: Span,
+        base_ty: Ty<'tcx>,
+        field: Ident,
+        mod_id: DefId,
+        ty_matches: impl Fn(Ty<'tcx>) -> bool,
+    ) -> Option<Vec<Ident>> {
+        let (fields, substs) =
+            self.get_field_candidates_considering_privacy(span, base_ty, mod_id)?;
+        // Unlike `find_best_match_for_name`, don't allow any typo in very short names, as they
+        // would be similar to too many of the nested fields.
+        let max_dist = field.as_str().len() / 3;
+        let is_similar = |candidate_field: &ty::FieldDef, field_ty: Ty<'tcx>| {
+            candidate_field.name != field.name
+                && lev_distance(candidate_field.name.as_str(), field.as_str(), max_dist).is_some()
+                && !matches!(
+                    self.tcx.eval_stability(candidate_field.did, None, span, None),
+                    stability::EvalResult::Deny { .. }
+                )
+                && ty_matches(field_ty)
+        };
+        let paths: Vec<Vec<Ident>> = fields
+            .filter_map(|candidate_field| {
+                self.check_for_nested_field_satisfying(
+                    span,
+                    &is_similar,
+                    candidate_field,
+                    substs,
+                    vec![],
+                    mod_id,
+                )
+            })
+            .collect();
+
+        let depth = paths.iter().map(|path| path.len()).min()?;
+        let names: Vec<Symbol> = paths
+            .iter()
+            .filter(|path| path.len() == depth)
+            .filter_map(|path| path.last())
+            .map(|id| id.name)
+            .collect();
+        let best_name = find_best_match_for_name(&names, field.name, None)?;
+        paths
+            .into_iter()
+            .find(|path| path.len() == depth && path.last().map(|id| id.name) == Some(best_name))
+    }
+
     fn maybe_suggest_array_indexing(
         &self,
         err: &mut Diagnostic,
@@ -895,6 +895,7 @@ pub fn resolve_ty_and_res_fully_qualified_call(
                         SelfSource::QPath(qself),
                         error,
                         None,
+                        Expectation::NoExpectation,
                     ) {
                         e.emit();
                     }
@@ -71,10 +71,18 @@ pub struct NoMatchData<'tcx> {
     pub unsatisfied_predicates:
         Vec<(ty::Predicate<'tcx>, Option<ty::Predicate<'tcx>>, Option<ObligationCause<'tcx>>)>,
     pub out_of_scope_traits: Vec<DefId>,
-    pub lev_candidate: Option<ty::AssocItem>,
+    pub lev_candidates: Vec<LevCandidate>,
     pub mode: probe::Mode,
 }
 
+/// An item applicable to the receiver whose name is close to the one that was not found.
+#[derive(Clone, Copy, Debug)]
+pub struct LevCandidate {
+    pub item: ty::AssocItem,
+    /// The trait of the item, if it is only applicable once this trait is imported.
+    pub trait_to_import: Option<DefId>,
+}
+
 // A pared down enum describing just the places from which a method
 // candidate can arise. Used for error reporting only.
 #[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
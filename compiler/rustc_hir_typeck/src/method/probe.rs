@@ -1,5 +1,6 @@
 use super::suggest;
 use super::CandidateSource;
+use super::LevCandidate;
 use super::MethodError;
 use super::NoMatchData;
 
@@ -24,9 +25,7 @@
 use rustc_session::lint;
 use rustc_span::def_id::DefId;
 use rustc_span::def_id::LocalDefId;
-use rustc_span::lev_distance::{
-    find_best_match_for_name_with_substrings, lev_distance_with_substrings,
-};
+use rustc_span::lev_distance::lev_distance_with_substrings;
 use rustc_span::symbol::sym;
 use rustc_span::{symbol::Ident, Span, Symbol, DUMMY_SP};
 use rustc_trait_selection::autoderef::{self, Autoderef};
@@ -428,7 +427,7 @@ fn probe_op<OP, R>(
                     static_candidates: Vec::new(),
                     unsatisfied_predicates: Vec::new(),
                     out_of_scope_traits: Vec::new(),
-                    lev_candidate: None,
+                    lev_candidates: Vec::new(),
                     mode,
                 }));
             }
@@ -1038,13 +1037,13 @@ fn pick(mut self) -> PickResult<'tcx> {
         if let Some((kind, def_id)) = private_candidate {
             return Err(MethodError::PrivateMatch(kind, def_id, out_of_scope_traits));
         }
-        let lev_candidate = self.probe_for_lev_candidate()?;
+        let lev_candidates = self.probe_for_lev_candidates()?;
 
         Err(MethodError::NoMatch(NoMatchData {
             static_candidates,
             unsatisfied_predicates,
             out_of_scope_traits,
-            lev_candidate,
+            lev_candidates,
             mode: self.mode,
         }))
     }
@@ -1685,10 +1684,11 @@ fn collapse_candidates_to_trait_pick(
         })
     }
 
-    /// Similarly to `probe_for_return_type`, this method attempts to find the best matching
-    /// candidate method where the method name may have been misspelled. Similarly to other
-    /// Levenshtein based suggestions, we provide at most one such suggestion.
-    fn probe_for_lev_candidate(&mut self) -> Result<Option<ty::AssocItem>, MethodError<'tcx>> {
+    /// Similarly to `probe_for_return_type`, this method attempts to find the candidate methods
+    /// whose name may have been misspelled. The inherent methods of every autoderef step are
+    /// considered, as well as the methods of every trait, including those that are not in scope
+    /// and would have to be imported. Choosing which one to suggest is left to the caller.
+    fn probe_for_lev_candidates(&mut self) -> Result<Vec<LevCandidate>, MethodError<'tcx>> {
         debug!("probing for method names similar to {:?}", self.method_name);
 
         let steps = self.steps.clone();
@@ -1705,36 +1705,37 @@ fn probe_for_lev_candidate(&mut self) -> Result<Option<ty::AssocItem>, MethodErr
             );
             pcx.allow_similar_names = true;
             pcx.assemble_inherent_candidates();
+            pcx.assemble_extension_candidates_for_all_traits();
 
             let method_names = pcx.candidate_method_names(|_| true);
             pcx.allow_similar_names = false;
-            let applicable_close_candidates: Vec<ty::AssocItem> = method_names
+            let applicable_close_candidates: Vec<LevCandidate> = method_names
                 .iter()
                 .filter_map(|&method_name| {
                     pcx.reset();
                     pcx.method_name = Some(method_name);
                     pcx.assemble_inherent_candidates();
-                    pcx.pick_core().and_then(|pick| pick.ok()).map(|pick| pick.item)
+                    pcx.assemble_extension_candidates_for_traits_in_scope(self.scope_expr_id);
+                    if let Some(Ok(pick)) = pcx.pick_core() {
+                        return Some(LevCandidate { item: pick.item, trait_to_import: None });
+                    }
+
+                    // Like in `pick`, look at all traits once the ones in scope did not match.
+                    // Unstable traits are not suggested, as they are unlikely to be usable.
+                    pcx.reset();
+                    pcx.assemble_extension_candidates_for_all_traits();
+                    let pick = pcx.pick_core()?.ok()?;
+                    let trait_def_id = pick.item.container_id(self.tcx);
+                    match self.tcx.lookup_stability(trait_def_id) {
+                        Some(stability) if !stability.level.is_stable() => None,
+                        _ => Some(LevCandidate {
+                            item: pick.item,
+                            trait_to_import: Some(trait_def_id),
+                        }),
+                    }
                 })
                 .collect();
-
-            if applicable_close_candidates.is_empty() {
-                Ok(None)
-            } else {
-                let best_name = {
-                    let names = applicable_close_candidates
-                        .iter()
-                        .map(|cand| cand.name)
-                        .collect::<Vec<Symbol>>();
-                    find_best_match_for_name_with_substrings(
-                        &names,
-                        self.method_name.unwrap().name,
-                        None,
-                    )
-                }
-                .unwrap();
-                Ok(applicable_close_candidates.into_iter().find(|method| method.name == best_name))
-            }
+            Ok(applicable_close_candidates)
         })
     }
 
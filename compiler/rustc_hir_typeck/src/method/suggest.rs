@@ -2,7 +2,7 @@
 //! found or is otherwise invalid.
 
 use crate::errors;
-use crate::FnCtxt;
+use crate::{Expectation, FnCtxt};
 use rustc_ast::ast::Mutability;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::{
@@ -15,6 +15,7 @@
 use rustc_hir::lang_items::LangItem;
 use rustc_hir::{ExprKind, Node, QPath};
 use rustc_infer::infer::{
+    self,
     type_variable::{TypeVariableOrigin, TypeVariableOriginKind},
     RegionVariableOrigin,
 };
@@ -39,7 +40,7 @@
 use std::iter;
 
 use super::probe::{AutorefOrPtrAdjustment, IsSuggestion, Mode, ProbeScope};
-use super::{CandidateSource, MethodError, NoMatchData};
+use super::{CandidateSource, LevCandidate, MethodError, NoMatchData};
 
 impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
     fn is_fn_ty(&self, ty: Ty<'tcx>, span: Span) -> bool {
@@ -104,6 +105,7 @@ pub fn report_method_error(
         source: SelfSource<'tcx>,
         error: MethodError<'tcx>,
         args: Option<(&'tcx hir::Expr<'tcx>, &'tcx [hir::Expr<'tcx>])>,
+        expected: Expectation<'tcx>,
     ) -> Option<DiagnosticBuilder<'_, ErrorGuaranteed>> {
         // Avoid suggestions when we don't know what's going on.
         if rcvr_ty.references_error() {
@@ -257,11 +259,23 @@ pub fn report_method_error(
                 mut static_candidates,
                 unsatisfied_predicates,
                 out_of_scope_traits,
-                lev_candidate,
+                lev_candidates,
                 mode,
             }) => {
                 let tcx = self.tcx;
 
+                // When a trait that is not in scope has an item of this very name, importing it
+                // is more likely to be what is needed than a trait item with a similar name.
+                let lev_candidates = if out_of_scope_traits.is_empty() {
+                    lev_candidates
+                } else {
+                    lev_candidates
+                        .into_iter()
+                        .filter(|candidate| tcx.trait_of_item(candidate.item.def_id).is_none())
+                        .collect()
+                };
+                let lev_candidate = self.choose_lev_candidate(lev_candidates, item_name, expected);
+
                 let rcvr_ty = self.resolve_vars_if_possible(rcvr_ty);
                 let ty_str = self.ty_to_string(rcvr_ty);
                 let is_method = mode == Mode::MethodCall;
@@ -969,7 +983,9 @@ trait bound{s}",
                     if fallback_span {
                         err.span_label(span, msg);
                     }
-                } else if let Some(lev_candidate) = lev_candidate {
+                } else if let Some(LevCandidate { item: lev_candidate, trait_to_import }) =
+                    lev_candidate
+                {
                     // Don't emit a suggestion if we found an actual method
                     // that had unsatisfied trait bounds
                     if unsatisfied_predicates.is_empty() {
@@ -997,6 +1013,14 @@ trait bound{s}",
                                 Applicability::MaybeIncorrect,
                             );
                         }
+                        if let Some(trait_did) = trait_to_import {
+                            let msg = format!(
+                                "the trait defining `{}` is implemented but not in scope; \
+                                 perhaps add a `use` for it:",
+                                lev_candidate.name,
+                            );
+                            self.suggest_use_candidates(&mut err, msg, vec![trait_did]);
+                        }
                     }
                 }
 
@@ -1971,6 +1995,69 @@ fn suggest_await_before_method(
         }
     }
 
+    /// Chooses which of the applicable items with a name close to `item_name` to suggest.
+    ///
+    /// The items whose type is compatible with the one expected where `item_name` is used are
+    /// preferred, then inherent items over trait items, and the items of traits in scope over
+    /// the ones that would have to be imported. The closest name is chosen among the rest.
+    fn choose_lev_candidate(
+        &self,
+        mut candidates: Vec<LevCandidate>,
+        item_name: Ident,
+        expected: Expectation<'tcx>,
+    ) -> Option<LevCandidate> {
+        if let Some(expected) = expected.only_has_type(self)
+            && !expected.is_ty_var()
+            && !expected.references_error()
+        {
+            let matching: Vec<_> = candidates
+                .iter()
+                .filter(|candidate| {
+                    self.item_can_have_type(&candidate.item, expected, item_name.span)
+                })
+                .copied()
+                .collect();
+            if !matching.is_empty() {
+                candidates = matching;
+            }
+        }
+
+        let rank = |candidate: &LevCandidate| {
+            if candidate.trait_to_import.is_some() {
+                2
+            } else if self.tcx.trait_of_item(candidate.item.def_id).is_some() {
+                1
+            } else {
+                0
+            }
+        };
+        let best_rank = candidates.iter().map(rank).min()?;
+        candidates.retain(|candidate| rank(candidate) == best_rank);
+
+        let names: Vec<Symbol> = candidates.iter().map(|candidate| candidate.item.name).collect();
+        let best_name =
+            lev_distance::find_best_match_for_name_with_substrings(&names, item_name.name, None)?;
+        candidates.into_iter().find(|candidate| candidate.item.name == best_name)
+    }
+
+    /// Whether the value of `item`, or the value returned by `item` if it is a function, can
+    /// be coerced to `expected`. The receiver and the arguments of a call are not checked, as
+    /// they are not known to be meant for this item.
+    fn item_can_have_type(&self, item: &ty::AssocItem, expected: Ty<'tcx>, span: Span) -> bool {
+        self.probe(|_| {
+            let substs = self.fresh_substs_for_item(span, item.def_id);
+            let ty = match item.kind {
+                ty::AssocKind::Fn => {
+                    let sig = self.tcx.bound_fn_sig(item.def_id).subst(self.tcx, substs);
+                    self.replace_bound_vars_with_fresh_vars(span, infer::FnCall, sig).output()
+                }
+                ty::AssocKind::Const => self.tcx.bound_type_of(item.def_id).subst(self.tcx, substs),
+                ty::AssocKind::Type => return false,
+            };
+            self.can_coerce(ty, expected)
+        })
+    }
+
     fn suggest_use_candidates(&self, err: &mut Diagnostic, msg: String, candidates: Vec<DefId>) {
         let parent_map = self.tcx.visible_parent_map(());
 
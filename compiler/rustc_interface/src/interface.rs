@@ -10,6 +10,7 @@
 use rustc_errors::registry::Registry;
 use rustc_errors::{ErrorGuaranteed, Handler};
 use rustc_lint::LintStore;
+use rustc_middle::mir::ExtraMirPasses;
 use rustc_middle::ty;
 use rustc_parse::maybe_new_parser_from_source_str;
 use rustc_query_impl::QueryCtxt;
@@ -43,6 +44,8 @@ pub struct Compiler {
     pub(crate) register_lints: Option<Box<dyn Fn(&Session, &mut LintStore) + Send + Sync>>,
     pub(crate) override_queries:
         Option<fn(&Session, &mut ty::query::Providers, &mut ty::query::ExternProviders)>,
+    pub(crate) register_mir_passes:
+        Option<Box<dyn Fn(&Session, &mut ExtraMirPasses) + Send + Sync>>,
 }
 
 impl Compiler {
@@ -270,6 +273,14 @@ pub struct Config {
     pub override_queries:
         Option<fn(&Session, &mut ty::query::Providers, &mut ty::query::ExternProviders)>,
 
+    /// This is a callback from the driver that is called when the global context is created,
+    /// to register MIR passes that run at the given points of the MIR pipeline.
+    ///
+    /// The passes are run like the passes of the compiler: they are skipped when their
+    /// `is_enabled` returns `false`, can be forced on or off with `-Z mir-enable-passes`, and
+    /// the MIR before and after them is dumped by `-Z dump-mir`.
+    pub register_mir_passes: Option<Box<dyn Fn(&Session, &mut ExtraMirPasses) + Send + Sync>>,
+
     /// This is a callback from the driver that is called to create a codegen backend.
     pub make_codegen_backend:
         Option<Box<dyn FnOnce(&config::Options) -> Box<dyn CodegenBackend> + Send>>,
@@ -316,6 +327,7 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                 temps_dir,
                 register_lints: config.register_lints,
                 override_queries: config.override_queries,
+                register_mir_passes: config.register_mir_passes,
             };
 
             rustc_span::with_source_map(compiler.sess.parse_sess.clone_source_map(), move || {
@@ -327,6 +339,9 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                     f(&compiler)
                 };
 
+                compiler.sess.print_self_profile_summary();
+                compiler.sess.print_time_by_item();
+
                 let prof = compiler.sess.prof.clone();
                 prof.generic_activity("drop_compiler").run(move || drop(compiler));
                 r
@@ -1,5 +1,5 @@
 use crate::errors::{
-    CantEmitMIR, EmojiIdentifier, ErrorWritingDependencies, FerrisIdentifier,
+    CantEmitMIR, EmojiIdentifier, ErrorWritingDependencies, FailedWritingFile, FerrisIdentifier,
     GeneratedFileConflictsWithDirectory, InputFileWouldBeOverWritten, MixedBinCrate,
     MixedProcMacroCrate, OutDirError, ProcMacroDocWithoutArg, TempsDirError,
 };
@@ -20,11 +20,12 @@
 use rustc_metadata::creader::CStore;
 use rustc_middle::arena::Arena;
 use rustc_middle::dep_graph::DepGraph;
+use rustc_middle::mir::ExtraMirPasses;
 use rustc_middle::ty::query::{ExternProviders, Providers};
 use rustc_middle::ty::{self, GlobalCtxt, RegisteredTools, TyCtxt};
 use rustc_mir_build as mir_build;
 use rustc_parse::{parse_crate_from_file, parse_crate_from_source_str, validate_attr};
-use rustc_passes::{self, hir_stats, layout_test};
+use rustc_passes::{self, hir_stats, item_deps, layout_test};
 use rustc_plugin_impl as plugin;
 use rustc_query_impl::{OnDiskCache, Queries as TcxQueries};
 use rustc_resolve::{Resolver, ResolverArenas};
@@ -256,9 +257,11 @@ fn pre_expansion_lint(
         node_id: ast::NodeId,
         attrs: &[ast::Attribute],
         items: &[rustc_ast::ptr::P<ast::Item>],
+        module_path: &[Symbol],
         name: &str,
     ) {
-        pre_expansion_lint(sess, self.0, registered_tools, (node_id, attrs, items), name);
+        let check_node = (node_id, attrs, items, module_path);
+        pre_expansion_lint(sess, self.0, registered_tools, check_node, name);
     }
 }
 
@@ -804,11 +807,17 @@ pub fn create_global_ctxt<'tcx>(
         TcxQueries::new(local_providers, extern_providers, query_result_on_disk_cache)
     });
 
+    let mut extra_mir_passes = ExtraMirPasses::default();
+    if let Some(callback) = &compiler.register_mir_passes {
+        callback(sess, &mut extra_mir_passes);
+    }
+
     let gcx = sess.time("setup_global_ctxt", || {
         global_ctxt.get_or_init(move || {
             TyCtxt::create_global_ctxt(
                 sess,
                 lint_store,
+                extra_mir_passes,
                 arena,
                 hir_arena,
                 resolver_outputs,
@@ -832,6 +841,9 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
     rustc_passes::hir_id_validator::check_crate(tcx);
 
     let sess = tcx.sess;
+    if sess.opts.unstable_opts.print_lint_levels.is_some() {
+        rustc_lint::print_lint_levels(tcx);
+    }
     let mut entry_point = None;
 
     sess.time("misc_checking_1", || {
@@ -844,6 +856,7 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
                 });
 
                 CStore::from_tcx(tcx).report_unused_deps(tcx);
+                CStore::from_tcx(tcx).write_extern_usage_report(tcx);
             },
             {
                 tcx.hir().par_for_each_module(|module| {
@@ -960,9 +973,27 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         sess.time("check_lint_expectations", || tcx.check_expectations(None));
     });
 
+    if sess.opts.unstable_opts.dump_item_deps.is_some() {
+        sess.time("dump_item_deps", || item_deps::dump_item_deps(tcx));
+    }
+
+    if sess.opts.edition_migration_report.is_some() {
+        write_edition_migration_report(tcx);
+    }
+
     Ok(())
 }
 
+/// Writes the edition migration report of `--edition-migration-report` next to the other
+/// outputs of the crate, once all the lints have been run.
+fn write_edition_migration_report(tcx: TyCtxt<'_>) {
+    let Some(report) = tcx.sess.diagnostic().render_edition_migration_report() else { return };
+    let path = tcx.output_filenames(()).with_extension("edition-migration.json");
+    if let Err(error) = fs::write(&path, report) {
+        tcx.sess.emit_err(FailedWritingFile { path: &path, error });
+    }
+}
+
 /// Runs the codegen backend, after which the AST and analysis can
 /// be discarded.
 pub fn start_codegen<'tcx>(
@@ -81,6 +81,7 @@ pub fn create_session(
         sopts.maybe_sysroot.clone(),
         sysroot_candidates().to_vec(),
         sopts.unstable_opts.translate_lang.clone(),
+        &sopts.unstable_opts.translate_fallback,
         sopts.unstable_opts.translate_additional_ftl.as_deref(),
         sopts.unstable_opts.translate_directionality_markers,
     ) {
@@ -335,24 +335,47 @@ pub fn check_lint_name_cmdline(
         level: Level,
         registered_tools: &RegisteredTools,
     ) {
-        let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
+        let (_, lint_name_only) = parse_lint_and_tool_name(lint_name);
         if lint_name_only == crate::WARNINGS.name_lower() && matches!(level, Level::ForceWarn(_)) {
             sess.emit_err(UnsupportedGroup { lint_group: crate::WARNINGS.name_lower() });
             return;
         }
+        self.check_requested_lint_name(sess, lint_name, registered_tools, |lint_name| {
+            RequestedLevel::CommandLine { level, lint_name }
+        });
+    }
+
+    /// Checks the validity of lint names from the lint configuration file.
+    pub fn check_lint_name_config(
+        &self,
+        sess: &Session,
+        lint_name: &str,
+        level: Level,
+        registered_tools: &RegisteredTools,
+    ) {
+        self.check_requested_lint_name(sess, lint_name, registered_tools, |lint_name| {
+            RequestedLevel::ConfigFile { level: level.as_str().to_string(), lint_name }
+        });
+    }
+
+    fn check_requested_lint_name(
+        &self,
+        sess: &Session,
+        lint_name: &str,
+        registered_tools: &RegisteredTools,
+        requested: impl Fn(String) -> RequestedLevel,
+    ) {
+        let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
         let lint_name = lint_name.to_string();
         match self.check_lint_name(lint_name_only, tool_name, registered_tools) {
             CheckLintNameResult::Warning(msg, _) => {
-                sess.emit_warning(CheckNameWarning {
-                    msg,
-                    sub: RequestedLevel { level, lint_name },
-                });
+                sess.emit_warning(CheckNameWarning { msg, sub: requested(lint_name) });
             }
             CheckLintNameResult::NoLint(suggestion) => {
                 sess.emit_err(CheckNameUnknown {
                     lint_name: lint_name.clone(),
                     suggestion,
-                    sub: RequestedLevel { level, lint_name },
+                    sub: requested(lint_name),
                 });
             }
             CheckLintNameResult::Tool(result) => {
@@ -360,14 +383,14 @@ pub fn check_lint_name_cmdline(
                     sess.emit_warning(CheckNameDeprecated {
                         lint_name: lint_name.clone(),
                         new_name,
-                        sub: RequestedLevel { level, lint_name },
+                        sub: requested(lint_name),
                     });
                 }
             }
             CheckLintNameResult::NoTool => {
                 sess.emit_err(CheckNameUnknownTool {
                     tool_name: tool_name.unwrap(),
-                    sub: RequestedLevel { level, lint_name },
+                    sub: requested(lint_name),
                 });
             }
             _ => {}
@@ -560,6 +583,10 @@ pub struct LateContext<'tcx> {
 pub struct EarlyContext<'a> {
     pub builder: LintLevelsBuilder<'a, crate::levels::TopDown>,
     pub buffered: LintBuffer,
+    /// The names of the items that the node being checked is in. The first `module_depth`
+    /// of them make the def path of its module.
+    pub(crate) item_path: Vec<Symbol>,
+    pub(crate) module_depth: usize,
 }
 
 pub trait LintPassObject: Sized {}
@@ -988,8 +1015,21 @@ pub(crate) fn new(
                 registered_tools,
             ),
             buffered,
+            item_path: Vec::new(),
+            module_depth: 0,
         }
     }
+
+    /// The def path of the module of the node being checked, which groups the lints emitted
+    /// at this node in an edition migration report, like `TyCtxt::lint_module` does for late
+    /// lints. Only computed with `--edition-migration-report`.
+    fn lint_module(&self) -> Option<String> {
+        self.sess().opts.edition_migration_report?;
+        let path = &self.item_path[..self.module_depth];
+        let names: Vec<_> =
+            iter::once("crate").chain(path.iter().map(|name| name.as_str())).collect();
+        Some(names.join("::"))
+    }
 }
 
 impl<'tcx> LintContext for LateContext<'tcx> {
@@ -1047,7 +1087,11 @@ fn lookup<S: Into<MultiSpan>>(
             &'b mut DiagnosticBuilder<'a, ()>,
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
-        self.builder.struct_lint(lint, span.map(|s| s.into()), msg, decorate)
+        let module = self.lint_module();
+        self.builder.struct_lint(lint, span.map(|s| s.into()), msg, |diag| {
+            diag.lint_module = module;
+            decorate(diag)
+        })
     }
 
     fn get_lint_level(&self, lint: &'static Lint) -> Level {
@@ -22,7 +22,7 @@
 use rustc_middle::ty::RegisteredTools;
 use rustc_session::lint::{BufferedEarlyLint, LintBuffer, LintPass};
 use rustc_session::Session;
-use rustc_span::symbol::Ident;
+use rustc_span::symbol::{kw, Ident, Symbol};
 use rustc_span::Span;
 
 use std::slice;
@@ -56,10 +56,25 @@ fn check_id(&mut self, id: ast::NodeId) {
     fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f: F)
     where
         F: FnOnce(&mut Self),
+    {
+        self.with_module_lint_attrs(id, attrs, None, f)
+    }
+
+    /// Like `with_lint_attrs`, but also merges the lints specified by the lint configuration
+    /// file for the source file of a module, if `module_span` is the inner span of the crate
+    /// root or of a module loaded from its own file.
+    fn with_module_lint_attrs<F>(
+        &mut self,
+        id: ast::NodeId,
+        attrs: &'a [ast::Attribute],
+        module_span: Option<Span>,
+        f: F,
+    ) where
+        F: FnOnce(&mut Self),
     {
         let is_crate_node = id == ast::CRATE_NODE_ID;
         debug!(?id);
-        let push = self.context.builder.push(attrs, is_crate_node, None);
+        let push = self.context.builder.push(attrs, is_crate_node, None, module_span);
 
         self.check_id(id);
         debug!("early context: enter_attrs({:?})", attrs);
@@ -69,6 +84,26 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         run_early_pass!(self, exit_lint_attrs, attrs);
         self.context.builder.pop(push);
     }
+
+    /// Walks the contents of an item named `name` with `f`, in the module of that name if
+    /// `is_module`. Unnamed items, like impls, are not part of the module paths.
+    fn with_item_path<F>(&mut self, name: Symbol, is_module: bool, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        if name == kw::Empty {
+            return f(self);
+        }
+
+        let module_depth = self.context.module_depth;
+        self.context.item_path.push(name);
+        if is_module {
+            self.context.module_depth = self.context.item_path.len();
+        }
+        f(self);
+        self.context.item_path.pop();
+        self.context.module_depth = module_depth;
+    }
 }
 
 impl<'a, T: EarlyLintPass> ast_visit::Visitor<'a> for EarlyContextAndPass<'a, T> {
@@ -80,9 +115,16 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
+        let module_span = match &it.kind {
+            ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, ast::Inline::No, spans)) => {
+                Some(spans.inner_span)
+            }
+            _ => None,
+        };
+        self.with_module_lint_attrs(it.id, &it.attrs, module_span, |cx| {
             run_early_pass!(cx, check_item, it);
-            ast_visit::walk_item(cx, it);
+            let is_module = matches!(it.kind, ast::ItemKind::Mod(..));
+            cx.with_item_path(it.ident.name, is_module, |cx| ast_visit::walk_item(cx, it));
             run_early_pass!(cx, check_item_post, it);
         })
     }
@@ -248,11 +290,15 @@ fn visit_assoc_item(&mut self, item: &'a ast::AssocItem, ctxt: ast_visit::AssocC
         self.with_lint_attrs(item.id, &item.attrs, |cx| match ctxt {
             ast_visit::AssocCtxt::Trait => {
                 run_early_pass!(cx, check_trait_item, item);
-                ast_visit::walk_assoc_item(cx, item, ctxt);
+                cx.with_item_path(item.ident.name, false, |cx| {
+                    ast_visit::walk_assoc_item(cx, item, ctxt)
+                });
             }
             ast_visit::AssocCtxt::Impl => {
                 run_early_pass!(cx, check_impl_item, item);
-                ast_visit::walk_assoc_item(cx, item, ctxt);
+                cx.with_item_path(item.ident.name, false, |cx| {
+                    ast_visit::walk_assoc_item(cx, item, ctxt)
+                });
             }
         });
     }
@@ -321,6 +367,15 @@ impl EarlyLintPass for EarlyLintPassObjects<'_> {
 /// This trait generalizes over those nodes.
 pub trait EarlyCheckNode<'a>: Copy {
     fn id(self) -> ast::NodeId;
+    /// The inner span of the crate root, to find the levels that the lint configuration file
+    /// sets for its source file.
+    fn module_span(self) -> Option<Span> {
+        None
+    }
+    /// The names of the modules from the crate root to the node, if it is a module.
+    fn module_path(self) -> &'a [Symbol] {
+        &[]
+    }
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b;
@@ -333,6 +388,9 @@ impl<'a> EarlyCheckNode<'a> for &'a ast::Crate {
     fn id(self) -> ast::NodeId {
         ast::CRATE_NODE_ID
     }
+    fn module_span(self) -> Option<Span> {
+        Some(self.spans.inner_span)
+    }
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b,
@@ -349,10 +407,15 @@ fn check<'b>(self, cx: &mut EarlyContextAndPass<'b, impl EarlyLintPass>)
     }
 }
 
-impl<'a> EarlyCheckNode<'a> for (ast::NodeId, &'a [ast::Attribute], &'a [P<ast::Item>]) {
+impl<'a> EarlyCheckNode<'a>
+    for (ast::NodeId, &'a [ast::Attribute], &'a [P<ast::Item>], &'a [Symbol])
+{
     fn id(self) -> ast::NodeId {
         self.0
     }
+    fn module_path(self) -> &'a [Symbol] {
+        self.3
+    }
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b,
@@ -387,8 +450,15 @@ fn early_lint_node<'a>(
         ),
         pass,
     };
-
-    cx.with_lint_attrs(check_node.id(), check_node.attrs(), |cx| check_node.check(cx));
+    cx.context.item_path = check_node.module_path().to_vec();
+    cx.context.module_depth = cx.context.item_path.len();
+
+    cx.with_module_lint_attrs(
+        check_node.id(),
+        check_node.attrs(),
+        check_node.module_span(),
+        |cx| check_node.check(cx),
+    );
     cx.context.buffered
 }
 
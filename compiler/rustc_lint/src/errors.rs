@@ -23,6 +23,7 @@ pub enum OverruledAttributeSub {
     DefaultSource { id: String },
     NodeSource { span: Span, reason: Option<Symbol> },
     CommandLineSource,
+    ConfigFileSource,
 }
 
 impl AddToDiagnostic for OverruledAttributeSub {
@@ -44,6 +45,9 @@ fn add_to_diagnostic_with<F>(self, diag: &mut Diagnostic, _: F)
             OverruledAttributeSub::CommandLineSource => {
                 diag.note(fluent::lint_command_line_source);
             }
+            OverruledAttributeSub::ConfigFileSource => {
+                diag.note(fluent::lint_config_file_source);
+            }
         }
     }
 }
@@ -67,6 +71,12 @@ pub enum MalformedAttributeSub {
     ReasonMustComeLast(#[primary_span] Span),
 }
 
+#[derive(Diagnostic)]
+#[diag(lint_print_lint_levels_invalid_position)]
+pub struct PrintLintLevelsInvalidPosition<'a> {
+    pub position: &'a str,
+}
+
 #[derive(Diagnostic)]
 #[diag(lint_unknown_tool_in_scoped_lint, code = "E0710")]
 pub struct UnknownToolInScopedLint {
@@ -89,10 +99,11 @@ pub struct BuiltinEllpisisInclusiveRangePatterns {
 }
 
 #[derive(Subdiagnostic)]
-#[note(lint_requested_level)]
-pub struct RequestedLevel {
-    pub level: Level,
-    pub lint_name: String,
+pub enum RequestedLevel {
+    #[note(lint_requested_level)]
+    CommandLine { level: Level, lint_name: String },
+    #[note(lint_requested_level_config)]
+    ConfigFile { level: String, lint_name: String },
 }
 
 #[derive(Diagnostic)]
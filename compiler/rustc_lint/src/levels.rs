@@ -3,6 +3,7 @@
 use rustc_ast as ast;
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, DiagnosticMessage, MultiSpan};
 use rustc_hir as hir;
 use rustc_hir::intravisit::{self, Visitor};
@@ -22,11 +23,12 @@
 use rustc_session::parse::{add_feature_diagnostics, feature_err};
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::{BytePos, FileName, Span, DUMMY_SP};
+use std::path::Path;
 
 use crate::errors::{
     MalformedAttribute, MalformedAttributeSub, OverruledAttribute, OverruledAttributeSub,
-    UnknownToolInScopedLint,
+    PrintLintLevelsInvalidPosition, UnknownToolInScopedLint,
 };
 
 /// Collection of lint levels for the whole crate.
@@ -155,6 +157,12 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
         levels.add_command_line();
     }
 
+    // The levels that the lint configuration file sets for the source file of a module go
+    // before the ones of its attributes, which take precedence over them.
+    if let Some(span) = module_file_span(tcx, owner) {
+        levels.add_config_overrides(span);
+    }
+
     match attrs.map.range(..) {
         // There is only something to do if there are attributes at all.
         [] => {}
@@ -185,6 +193,26 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
     specs
 }
 
+/// Returns the inner span of `owner` if it is the crate root or a module that is loaded from
+/// its own source file, and the lint configuration file sets levels for some source files.
+fn module_file_span(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> Option<Span> {
+    let config = tcx.sess.opts.lint_config.as_ref()?;
+    if config.overrides.is_empty() {
+        return None;
+    }
+    match tcx.hir().owner(owner) {
+        hir::OwnerNode::Crate(module) => Some(module.spans.inner_span),
+        hir::OwnerNode::Item(&hir::Item { kind: hir::ItemKind::Mod(module), span, .. }) => {
+            let inner_span = module.spans.inner_span;
+            let source_map = tcx.sess.source_map();
+            let item_file = source_map.lookup_source_file(span.lo());
+            let module_file = source_map.lookup_source_file(inner_span.lo());
+            (!Lrc::ptr_eq(&item_file, &module_file)).then_some(inner_span)
+        }
+        _ => None,
+    }
+}
+
 pub struct TopDown {
     sets: LintLevelSets,
     cur: LintStackIndex,
@@ -470,17 +498,25 @@ fn process_command_line(&mut self) {
     /// * Lint attributes are validated, e.g., a `#[forbid]` can't be switched to
     ///   `#[allow]`
     ///
+    /// For the crate root and modules loaded from their own source file, `module_span` is
+    /// their inner span, and the levels that the lint configuration file sets for that file
+    /// are added before the ones of the attributes.
+    ///
     /// Don't forget to call `pop`!
     pub(crate) fn push(
         &mut self,
         attrs: &[ast::Attribute],
         is_crate_node: bool,
         source_hir_id: Option<HirId>,
+        module_span: Option<Span>,
     ) -> BuilderPush {
         let prev = self.provider.cur;
         self.provider.cur =
             self.provider.sets.list.push(LintSet { specs: FxHashMap::default(), parent: prev });
 
+        if let Some(module_span) = module_span {
+            self.add_config_overrides(module_span);
+        }
         self.add(attrs, is_crate_node, source_hir_id);
 
         if self.provider.current_specs().is_empty() {
@@ -523,6 +559,12 @@ fn insert(&mut self, id: LintId, lvl: LevelAndSource) {
     }
 
     fn add_command_line(&mut self) {
+        // The levels of the lint configuration file come first, the flags override them.
+        let sess = self.sess;
+        if let Some(config) = &sess.opts.lint_config {
+            self.add_config_levels(&config.lints, None);
+        }
+
         for &(ref lint_name, level) in &self.sess.opts.lint_opts {
             self.store.check_lint_name_cmdline(self.sess, &lint_name, level, self.registered_tools);
             let orig_level = level;
@@ -546,6 +588,75 @@ fn add_command_line(&mut self) {
                 }
             }
         }
+
+        // The migration lints counted in the report of `--edition-migration-report` are run
+        // whatever their level, their diagnostics are only recorded in the report.
+        let store = self.store;
+        for &lint in store.get_lints() {
+            if sess.is_edition_migration_lint(lint) {
+                let level = Level::ForceWarn(None);
+                let src = LintLevelSource::CommandLine(Symbol::intern(&lint.name_lower()), level);
+                self.insert(LintId::of(lint), (level, src));
+            }
+        }
+    }
+
+    /// Adds the levels of the `[[override]]` sections of the lint configuration file that
+    /// match the source file of `span`, the inner span of the crate root or of a module that
+    /// is loaded from its own file.
+    fn add_config_overrides(&mut self, span: Span) {
+        let sess = self.sess;
+        let Some(config) = &sess.opts.lint_config else { return };
+        if config.overrides.is_empty() {
+            return;
+        }
+        let FileName::Real(file) = sess.source_map().span_to_filename(span) else { return };
+        let Some(file) = file.local_path() else { return };
+        let working_dir = sess.opts.working_dir.local_path_if_available();
+        for (lint_override, path) in config.overrides_for(file, working_dir) {
+            self.add_config_levels(&lint_override.lints, Some(path));
+        }
+    }
+
+    /// Adds levels from the lint configuration file, which apply to the whole crate if `path`
+    /// is `None`, or to the source files under `path`. The levels of lint groups are added
+    /// first, so that the levels of single lints take precedence over them.
+    fn add_config_levels(&mut self, levels: &'s [(String, Level)], path: Option<&str>) {
+        let sess = self.sess;
+        let store = self.store;
+        let Some(config) = &sess.opts.lint_config else { return };
+        let path = path.map(Symbol::intern);
+
+        let mut levels: Vec<_> = levels.iter().collect();
+        levels.sort_by_key(|(name, _)| {
+            !(config.groups.contains_key(name) || store.is_lint_group(Symbol::intern(name)))
+        });
+        for &(ref name, level) in levels {
+            let lint_names = match config.groups.get(name) {
+                Some(lint_names) => &lint_names[..],
+                None => std::slice::from_ref(name),
+            };
+            let src = LintLevelSource::ConfigFile { name: Symbol::intern(name), path };
+            for lint_name in lint_names {
+                store.check_lint_name_config(sess, lint_name, level, self.registered_tools);
+                let Ok(ids) = store.find_lints(lint_name) else {
+                    // errors handled in check_lint_name_config above
+                    continue
+                };
+                for id in ids {
+                    // Like the flags, the configuration file can't override `forbid` and
+                    // `force-warn`.
+                    if let (Level::ForceWarn(_) | Level::Forbid, _) =
+                        self.provider.get_lint_level(id.lint, sess)
+                    {
+                        continue;
+                    }
+                    if self.check_gated_lint(id, DUMMY_SP) {
+                        self.insert(id, (level, src));
+                    }
+                }
+            }
+        }
     }
 
     /// Attempts to insert the `id` to `level_src` map entry. If unsuccessful
@@ -575,6 +686,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                     LintLevelSource::Default => false,
                     LintLevelSource::Node { name, .. } => self.store.is_lint_group(name),
                     LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                    LintLevelSource::ConfigFile { name, .. } => self.store.is_lint_group(name),
                 };
                 debug!(
                     "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -602,6 +714,9 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                         LintLevelSource::CommandLine(_, _) => {
                             diag.note("`forbid` lint level was set on command line");
                         }
+                        LintLevelSource::ConfigFile { .. } => {
+                            diag.note("`forbid` lint level was set in the lint configuration file");
+                        }
                     }
                 };
                 if !fcw_warning {
@@ -620,6 +735,9 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                             LintLevelSource::CommandLine(_, _) => {
                                 OverruledAttributeSub::CommandLineSource
                             }
+                            LintLevelSource::ConfigFile { .. } => {
+                                OverruledAttributeSub::ConfigFileSource
+                            }
                         },
                     });
                 } else {
@@ -1088,6 +1206,94 @@ pub(crate) fn struct_lint(
     }
 }
 
+/// Prints the level of every lint at the source position given with `-Z print-lint-levels`,
+/// and how it was set, to stdout.
+pub fn print_lint_levels(tcx: TyCtxt<'_>) {
+    let Some(position) = &tcx.sess.opts.unstable_opts.print_lint_levels else { return };
+    let Some(pos) = parse_source_position(tcx.sess, position) else {
+        tcx.sess.emit_err(PrintLintLevelsInvalidPosition { position });
+        return;
+    };
+    let mut finder = NodeAtPosition { tcx, pos, found: None };
+    tcx.hir().walk_toplevel_module(&mut finder);
+    let hir_id = finder.found.map_or(hir::CRATE_HIR_ID, |(hir_id, _)| hir_id);
+
+    let source_map = tcx.sess.source_map();
+    let mut lints = unerased_lint_store(tcx).get_lints().to_vec();
+    lints.sort_by_key(|lint| lint.name_lower());
+    for lint in lints {
+        let (level, src) = tcx.lint_level_at_node(lint, hir_id);
+        let source = match src {
+            LintLevelSource::Default => "default".to_string(),
+            LintLevelSource::Node { name, span, .. } => {
+                format!("`{name}` attribute at {}", source_map.span_to_embeddable_string(span))
+            }
+            LintLevelSource::CommandLine(name, level) => {
+                format!("`{name}` set to `{}` on the command line", level.as_str())
+            }
+            LintLevelSource::ConfigFile { name, path: None } => {
+                format!("`{name}` in the lint configuration file")
+            }
+            LintLevelSource::ConfigFile { name, path: Some(path) } => {
+                format!("`{name}` for `{path}` in the lint configuration file")
+            }
+        };
+        println!("{}: {} ({source})", lint.name_lower(), level.as_str());
+    }
+}
+
+/// Parses a `FILE:LINE:COL` position, with a 1-based line and column, in one of the source
+/// files of the crate.
+fn parse_source_position(sess: &Session, position: &str) -> Option<BytePos> {
+    let mut parts = position.rsplitn(3, ':');
+    let col: usize = parts.next()?.parse().ok()?;
+    let line: usize = parts.next()?.parse().ok()?;
+    let path = Path::new(parts.next()?);
+
+    let working_dir = sess.opts.working_dir.local_path_if_available();
+    let path = working_dir.join(path);
+    let files = sess.source_map().files();
+    let file = files.iter().find(|file| match &file.name {
+        FileName::Real(name) => name.local_path().map_or(false, |p| working_dir.join(p) == path),
+        _ => false,
+    })?;
+    if line == 0 || col == 0 || line > file.count_lines() {
+        return None;
+    }
+    let text = file.get_line(line - 1)?;
+    let offset = match text.char_indices().nth(col - 1) {
+        Some((offset, _)) => offset,
+        None if text.chars().count() == col - 1 => text.len(),
+        None => return None,
+    };
+    Some(file.line_bounds(line - 1).start + BytePos(offset as u32))
+}
+
+/// Finds the innermost HIR node whose span contains a position.
+struct NodeAtPosition<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    pos: BytePos,
+    found: Option<(HirId, Span)>,
+}
+
+impl<'tcx> Visitor<'tcx> for NodeAtPosition<'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_id(&mut self, hir_id: HirId) {
+        let Some(span) = self.tcx.hir().opt_span(hir_id) else { return };
+        if span.from_expansion() || span.lo() > self.pos || self.pos >= span.hi() {
+            return;
+        }
+        if self.found.map_or(true, |(_, found)| found.contains(span)) {
+            self.found = Some((hir_id, span));
+        }
+    }
+}
+
 pub(crate) fn provide(providers: &mut Providers) {
     *providers = Providers { shallow_lint_levels_on, lint_expectations, ..*providers };
 }
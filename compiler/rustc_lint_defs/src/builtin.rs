@@ -3271,6 +3271,7 @@
         FFI_UNWIND_CALLS,
         REPR_TRANSPARENT_EXTERNAL_PRIVATE_FIELDS,
         NAMED_ARGUMENTS_USED_POSITIONALLY,
+        REACHABLE_PANICS,
     ]
 }
 
@@ -4003,3 +4004,42 @@
     Warn,
     "named arguments in format used positionally"
 }
+
+declare_lint! {
+    /// The `reachable_panics` lint detects functions marked `#[no_panic]` from which a panic
+    /// can be reached once they are monomorphized.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![feature(no_panic)]
+    ///
+    /// #[no_panic]
+    /// fn first(v: &[u32]) -> u32 {
+    ///     v[0]
+    /// }
+    ///
+    /// fn main() {
+    ///     first(&[1]);
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// A function marked `#[no_panic]` must not call the panic machinery of the standard
+    /// library, contain runtime checks such as bounds or overflow checks, call code whose body
+    /// the compiler doesn't see, like foreign functions, function pointers, trait object methods
+    /// and functions codegened by other crates, or drop trait objects, and neither may any
+    /// function it calls. This holds whatever the panic strategy, as the panics of these
+    /// functions can't be ruled out even if they abort rather than unwind. Since the check runs on
+    /// the monomorphized code of the crate, it only happens when the crate is built rather
+    /// than just checked, and it also covers the functions that are inlined from other
+    /// crates. Code that is optimized away later, like a bounds check on an index known to be
+    /// in bounds, is still reported.
+    pub REACHABLE_PANICS,
+    Deny,
+    "detects panics reachable from `#[no_panic]` functions",
+    @feature_gate = sym::no_panic;
+}
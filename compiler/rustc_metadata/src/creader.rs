@@ -2,16 +2,17 @@
 
 use crate::errors::{
     AllocFuncRequired, ConflictingAllocErrorHandler, ConflictingGlobalAlloc, CrateNotPanicRuntime,
-    GlobalAllocRequired, MissingAllocErrorHandler, NoMultipleAllocErrorHandler,
+    FailedWriteError, GlobalAllocRequired, MissingAllocErrorHandler, NoMultipleAllocErrorHandler,
     NoMultipleGlobalAlloc, NoPanicStrategy, NoTransitiveNeedsDep, NotProfilerRuntime,
     ProfilerBuiltinsNeedsCore,
 };
+use crate::extern_usage::{self, ExternUse};
 use crate::locator::{CrateError, CrateLocator, CratePaths};
 use crate::rmeta::{CrateDep, CrateMetadata, CrateNumMap, CrateRoot, MetadataBlob};
 
 use rustc_ast::expand::allocator::AllocatorKind;
 use rustc_ast::{self as ast, *};
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
 use rustc_expand::base::SyntaxExtension;
@@ -57,6 +58,10 @@ pub struct CStore {
 
     /// Unused externs of the crate
     unused_externs: Vec<Symbol>,
+
+    /// The uses of each crate passed with `--extern`, only recorded with
+    /// `-Z extern-usage-report`.
+    extern_uses: FxIndexMap<Symbol, Vec<ExternUse>>,
 }
 
 impl std::fmt::Debug for CStore {
@@ -239,6 +244,17 @@ pub fn report_unused_deps(&self, tcx: TyCtxt<'_>) {
             );
         }
     }
+
+    /// Writes how each crate passed with `--extern` is used to the path given to
+    /// `-Z extern-usage-report`.
+    pub fn write_extern_usage_report(&self, tcx: TyCtxt<'_>) {
+        let Some(path) = &tcx.sess.opts.unstable_opts.extern_usage_report else { return };
+
+        let report = extern_usage::render_report(tcx, &self.extern_uses);
+        if let Err(err) = std::fs::write(path, report) {
+            tcx.sess.emit_err(FailedWriteError { filename: path.clone(), err });
+        }
+    }
 }
 
 impl<'a> CrateLoader<'a> {
@@ -267,6 +283,7 @@ pub fn new(
                 has_alloc_error_handler: false,
                 stable_crate_ids,
                 unused_externs: Vec::new(),
+                extern_uses: Default::default(),
             },
             used_extern_options: Default::default(),
         }
@@ -1012,6 +1029,7 @@ pub fn process_extern_crate(
                 };
 
                 let cnum = self.resolve_crate(name, item.span, dep_kind)?;
+                self.record_extern_use(name, ExternUse::ExternCrate(def_id));
 
                 let path_len = definitions.def_path(def_id).data.len();
                 self.update_extern_crate(
@@ -1031,6 +1049,7 @@ pub fn process_extern_crate(
 
     pub fn process_path_extern(&mut self, name: Symbol, span: Span) -> Option<CrateNum> {
         let cnum = self.resolve_crate(name, span, CrateDepKind::Explicit)?;
+        self.record_extern_use(name, ExternUse::Path(span));
 
         self.update_extern_crate(
             cnum,
@@ -1049,6 +1068,53 @@ pub fn process_path_extern(&mut self, name: Symbol, span: Span) -> Option<CrateN
     pub fn maybe_process_path_extern(&mut self, name: Symbol) -> Option<CrateNum> {
         self.maybe_resolve_crate(name, CrateDepKind::Explicit, None).ok()
     }
+
+    /// Notes that the path whose first segment is at `span`, which was just processed with
+    /// `process_path_extern` or `process_extern_crate_use`, is the path of a macro.
+    pub fn process_macro_path_extern(&mut self, span: Span) {
+        // The first segment may name an `extern crate` item renaming the crate, so the use
+        // is looked up by its span rather than by the name of the crate.
+        for uses in self.cstore.extern_uses.values_mut() {
+            if let Some(last_use) = uses.last_mut()
+                && let ExternUse::Path(path_span) = *last_use
+                // The extern prelude is looked up with the root context.
+                && path_span == span.with_ctxt(path_span.ctxt())
+            {
+                *last_use = ExternUse::Macro(span);
+            }
+        }
+    }
+
+    /// Notes that the crate loaded by the `extern_crate` item is used by the path whose first
+    /// segment is at `span`.
+    pub fn process_extern_crate_use(&mut self, extern_crate: LocalDefId, span: Span) {
+        if self.sess.opts.unstable_opts.extern_usage_report.is_none() {
+            return;
+        }
+        let uses = self.cstore.extern_uses.values_mut().find(|uses| {
+            uses.iter().any(|u| matches!(u, ExternUse::ExternCrate(id) if *id == extern_crate))
+        });
+        if let Some(uses) = uses {
+            uses.push(ExternUse::Path(span));
+        }
+    }
+
+    /// Notes that macros were imported at `span` by the `#[macro_use]` attribute of the
+    /// `extern_crate` item.
+    pub fn process_macro_use_extern(&mut self, extern_crate: LocalDefId, span: Span) {
+        let uses = self.cstore.extern_uses.values_mut().find(|uses| {
+            uses.iter().any(|u| matches!(u, ExternUse::ExternCrate(id) if *id == extern_crate))
+        });
+        if let Some(uses) = uses {
+            uses.push(ExternUse::Macro(span));
+        }
+    }
+
+    fn record_extern_use(&mut self, name: Symbol, extern_use: ExternUse) {
+        if self.sess.opts.unstable_opts.extern_usage_report.is_some() {
+            self.cstore.extern_uses.entry(name).or_default().push(extern_use);
+        }
+    }
 }
 
 fn global_allocator_spans(sess: &Session, krate: &ast::Crate) -> Vec<Span> {
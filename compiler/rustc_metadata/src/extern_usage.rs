@@ -0,0 +1,207 @@
+//! The report of how each crate passed with `--extern` is used, written with
+//! `-Z extern-usage-report`.
+//!
+//! The `unused_crate_dependencies` lint only knows whether a crate was loaded at all. For
+//! this report, the crate loader records every use of an `--extern` crate made while
+//! resolving names (see [`ExternUse`]), which tells apart the crates that are only loaded by
+//! `extern crate` items that are themselves unused, the crates that only provide macros and
+//! the crates that are only used in code that only exists with `--test`, like `#[cfg(test)]`
+//! code.
+//!
+//! The report of a compilation is a JSON object like:
+//!
+//! ```json
+//! {
+//!   "crate_name": "foo",
+//!   "crate_types": ["lib"],
+//!   "test": true,
+//!   "externs": [
+//!     { "name": "bar", "usage": "test-only" },
+//!     { "name": "baz", "usage": "unused" }
+//!   ]
+//! }
+//! ```
+//!
+//! A single compilation cannot tell whether a dependency of a package can be removed, since
+//! the other targets of the package may use it. Build tools are expected to merge the reports
+//! of all the targets instead: a dependency can be removed if every target reports it as
+//! `unused` or `extern-crate-only`, and is a development dependency if it is only `used` or
+//! `macros-only` in test targets or is `test-only`.
+
+use rustc_ast::MetaItem;
+use rustc_attr::{eval_condition, Condition};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir::def_id::{LocalDefId, LOCAL_CRATE};
+use rustc_hir::{HirId, ItemKind};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::ExternLocation;
+use rustc_span::symbol::{kw, sym, Symbol};
+use rustc_span::Span;
+use serde::Serialize;
+
+/// A use of a crate passed with `--extern`, recorded by the crate loader.
+#[derive(Clone, Copy, Debug)]
+pub enum ExternUse {
+    /// The crate is loaded by this `extern crate` item, which may or may not be used itself.
+    ExternCrate(LocalDefId),
+    /// A path outside of a macro invocation starts with the name of the crate, or with the name
+    /// of an `extern crate` item loading it.
+    Path(Span),
+    /// A macro of the crate is invoked, by a path starting with the name of the crate or
+    /// through a `#[macro_use]` import.
+    Macro(Span),
+}
+
+/// How a crate passed with `--extern` is used by the crate being compiled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ExternUsage {
+    /// The crate is never loaded.
+    Unused,
+    /// The crate is only loaded by `extern crate` items that are not used.
+    ExternCrateOnly,
+    /// Only macros of the crate are used.
+    MacrosOnly,
+    /// The crate is only used in code whose `#[cfg]` only holds with `--test`.
+    TestOnly,
+    Used,
+}
+
+#[derive(Serialize)]
+struct ExternReport {
+    name: String,
+    usage: ExternUsage,
+}
+
+#[derive(Serialize)]
+struct UsageReport {
+    crate_name: String,
+    crate_types: Vec<String>,
+    test: bool,
+    externs: Vec<ExternReport>,
+}
+
+/// Renders the report of the crates passed with `--extern`, given the uses recorded for each
+/// of them.
+pub(crate) fn render_report(
+    tcx: TyCtxt<'_>,
+    extern_uses: &FxIndexMap<Symbol, Vec<ExternUse>>,
+) -> String {
+    let cfg_test_spans = cfg_test_spans(tcx);
+    let externs = tcx
+        .sess
+        .opts
+        .externs
+        .iter()
+        // Like `unused_crate_dependencies`, ignore pathless `--extern foo` sysroot references.
+        .filter(|(_, entry)| {
+            !matches!(entry.location, ExternLocation::FoundInLibrarySearchDirectories)
+        })
+        .map(|(name, _)| {
+            let usage = match extern_uses.get(&Symbol::intern(name)) {
+                Some(uses) if !uses.is_empty() => classify(tcx, uses, &cfg_test_spans),
+                _ => ExternUsage::Unused,
+            };
+            ExternReport { name: name.clone(), usage }
+        })
+        .collect();
+
+    let report = UsageReport {
+        crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        crate_types: tcx.sess.crate_types().iter().map(|ty| ty.to_string()).collect(),
+        test: tcx.sess.opts.test,
+        externs,
+    };
+    serde_json::to_string(&report).unwrap()
+}
+
+fn classify(tcx: TyCtxt<'_>, uses: &[ExternUse], cfg_test_spans: &[Span]) -> ExternUsage {
+    let in_cfg_test = |span: Span| {
+        let span = span.source_callsite();
+        cfg_test_spans.iter().any(|test_span| test_span.contains(span))
+    };
+
+    let mut used = false;
+    let mut used_outside_macros = false;
+    let mut used_outside_tests = false;
+    for &extern_use in uses {
+        let (span, is_macro) = match extern_use {
+            // `extern crate foo as _;` is written to link a crate that is otherwise unused, and
+            // a public `extern crate` item may be used by the crates depending on this one.
+            ExternUse::ExternCrate(def_id)
+                if tcx.item_name(def_id.to_def_id()) == kw::Underscore
+                    || tcx.visibility(def_id.to_def_id()).is_public() =>
+            {
+                (tcx.def_span(def_id), false)
+            }
+            // The uses of other `extern crate` items are recorded where they occur.
+            ExternUse::ExternCrate(_) => continue,
+            ExternUse::Path(span) => (span, false),
+            ExternUse::Macro(span) => (span, true),
+        };
+        used = true;
+        used_outside_macros |= !is_macro;
+        used_outside_tests |= !in_cfg_test(span);
+    }
+
+    if !used {
+        ExternUsage::ExternCrateOnly
+    } else if !used_outside_tests {
+        ExternUsage::TestOnly
+    } else if !used_outside_macros {
+        ExternUsage::MacrosOnly
+    } else {
+        ExternUsage::Used
+    }
+}
+
+/// Returns the spans of the items whose `#[cfg]` only holds when compiling with `--test`, like
+/// `#[cfg(test)]` or `#[cfg(all(test, unix))]`.
+fn cfg_test_spans(tcx: TyCtxt<'_>) -> Vec<Span> {
+    if !tcx.sess.opts.test {
+        return Vec::new();
+    }
+
+    // Evaluates the predicate of a `#[cfg]` with the configuration of the session, with `test`
+    // enabled or not.
+    let parse_sess = &tcx.sess.parse_sess;
+    let holds = |cfg: &MetaItem, test: bool| {
+        eval_condition(cfg, parse_sess, None, &mut |cond: Condition| {
+            if cond.name == sym::test && cond.value.is_none() {
+                test
+            } else {
+                parse_sess.config.contains(&(cond.name, cond.value))
+            }
+        })
+    };
+    let is_cfg_test = |hir_id: HirId| {
+        tcx.hir().attrs(hir_id).iter().any(|attr| {
+            attr.has_name(sym::cfg)
+                && attr.meta_item_list().map_or(false, |list| match &list[..] {
+                    [cfg] => {
+                        cfg.meta_item().map_or(false, |cfg| holds(cfg, true) && !holds(cfg, false))
+                    }
+                    _ => false,
+                })
+        })
+    };
+
+    let hir = tcx.hir();
+    let items = tcx.hir_crate_items(());
+    let mut spans = Vec::new();
+    for item in items.items() {
+        let item = hir.item(item);
+        if is_cfg_test(item.hir_id()) {
+            spans.push(item.span);
+            // The span of an out-of-line module is the one of its declaration.
+            if let ItemKind::Mod(module) = item.kind {
+                spans.push(module.spans.inner_span);
+            }
+        }
+    }
+    let others = items.trait_items().map(|item| item.hir_id());
+    let others = others.chain(items.impl_items().map(|item| item.hir_id()));
+    let others = others.chain(items.foreign_items().map(|item| item.hir_id()));
+    spans.extend(others.filter(|&hir_id| is_cfg_test(hir_id)).map(|id| hir.span_with_body(id)));
+    spans
+}
@@ -32,6 +32,7 @@
 pub use rmeta::{provide, provide_extern};
 
 mod dependency_format;
+mod extern_usage;
 mod foreign_modules;
 mod native_libs;
 mod rmeta;
@@ -34,6 +34,10 @@ pub enum LintLevelSource {
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by the lint configuration file given with `-Z lint-config`,
+    /// for the whole crate, or for the source files under `path` if it is set.
+    ConfigFile { name: Symbol, path: Option<Symbol> },
 }
 
 impl LintLevelSource {
@@ -42,6 +46,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node { name, .. } => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::ConfigFile { name, .. } => name,
         }
     }
 
@@ -50,6 +55,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node { span, .. } => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::ConfigFile { .. } => DUMMY_SP,
         }
     }
 }
@@ -258,6 +264,24 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::ConfigFile { name: lint_config_name, path } => {
+            let scope = match path {
+                Some(path) => format!("for the files under `{path}`"),
+                None => "for the crate".to_string(),
+            };
+            if lint_config_name.as_str() == name {
+                err.note_once(&format!(
+                    "`{name} = \"{}\"` set in the lint configuration file {scope}",
+                    level.as_str()
+                ));
+            } else {
+                err.note_once(&format!(
+                    "`{name} = \"{level}\"` implied by `{lint_config_name} = \"{level}\"` \
+                    in the lint configuration file {scope}",
+                    level = level.as_str()
+                ));
+            }
+        }
         LintLevelSource::Node { name: lint_attr_name, span, reason, .. } => {
             if let Some(rationale) = reason {
                 err.note(rationale.as_str());
@@ -427,6 +451,7 @@ fn struct_lint_level_impl(
         let name = lint.name_lower();
         let is_force_warn = matches!(level, Level::ForceWarn(_));
         err.code(DiagnosticId::Lint { name, has_future_breakage, is_force_warn });
+        err.is_edition_migration = sess.is_edition_migration_lint(lint);
 
         if let Some(future_incompatible) = future_incompatible {
             let explanation = match future_incompatible.reason {
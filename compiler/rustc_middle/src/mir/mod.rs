@@ -27,6 +27,7 @@
 pub use rustc_ast::Mutability;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::graph::dominators::Dominators;
+use rustc_data_structures::sync;
 use rustc_index::bit_set::BitMatrix;
 use rustc_index::vec::{Idx, IndexVec};
 use rustc_serialize::{Decodable, Encodable};
@@ -121,6 +122,47 @@ fn is_mir_dump_enabled(&self) -> bool {
     }
 }
 
+/// The points of the MIR pipeline at which a driver can run passes of its own, registered with
+/// the `register_mir_passes` callback of `rustc_interface::Config`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MirPassPoint {
+    /// After the body has been borrow checked, before it is lowered to runtime MIR. The MIR at
+    /// this point is also the MIR evaluated by CTFE. The passes must keep the MIR valid in the
+    /// `Analysis(Initial)` phase.
+    AfterBorrowck,
+    /// At the start of the optimizations, right before the callees are inlined.
+    BeforeInlining,
+    /// Right after the callees are inlined.
+    AfterInlining,
+    /// At the end of the optimizations, on the MIR given to codegen.
+    PreCodegen,
+}
+
+/// The passes registered by a driver, with the points of the pipeline at which they run.
+#[derive(Default)]
+pub struct ExtraMirPasses {
+    passes: Vec<(MirPassPoint, Box<dyn for<'tcx> MirPass<'tcx> + sync::Send + sync::Sync>)>,
+}
+
+impl ExtraMirPasses {
+    /// Runs `pass` at `point`, after the passes registered at that point before.
+    pub fn register(
+        &mut self,
+        point: MirPassPoint,
+        pass: Box<dyn for<'tcx> MirPass<'tcx> + sync::Send + sync::Sync>,
+    ) {
+        self.passes.push((point, pass));
+    }
+
+    /// Returns the passes registered at `point`, in the order they run in.
+    pub fn at<'tcx>(&self, point: MirPassPoint) -> impl Iterator<Item = &dyn MirPass<'tcx>> + '_ {
+        self.passes
+            .iter()
+            .filter(move |(p, _)| *p == point)
+            .map(|(_, pass)| &**pass as &dyn MirPass<'tcx>)
+    }
+}
+
 impl MirPhase {
     /// Gets the index of the current MirPhase within the set of all `MirPhase`s.
     ///
@@ -3,7 +3,7 @@
 use rustc_attr::InlineAttr;
 use rustc_data_structures::base_n;
 use rustc_data_structures::fingerprint::Fingerprint;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_hir::ItemId;
@@ -418,6 +418,25 @@ fn hash_stable(&self, hcx: &mut StableHashingContext<'a>, hasher: &mut StableHas
     }
 }
 
+/// The calls made by the functions codegened in the current crate, computed by the
+/// `mono_call_graph` query for `-Z emit-stack-sizes-report`.
+///
+/// Functions are identified by their symbol names, which is how the backend knows them.
+#[derive(Clone, Debug, Default)]
+pub struct MonoCallGraph {
+    pub functions: FxIndexMap<String, MonoFunction>,
+    /// The names of the functions of the graph and of the functions they call, for display.
+    pub names: FxHashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MonoFunction {
+    /// The symbol names of the functions called directly, including drop glue.
+    pub callees: Vec<String>,
+    /// Whether the function calls a function pointer or a method of a trait object.
+    pub has_indirect_calls: bool,
+}
+
 pub struct CodegenUnitNameBuilder<'tcx> {
     tcx: TyCtxt<'tcx>,
     cache: FxHashMap<CrateNum, String>,
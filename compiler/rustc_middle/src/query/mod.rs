@@ -1826,6 +1826,14 @@
         desc { "collect_and_partition_mono_items" }
     }
 
+    /// The calls made by the functions in the codegen units, for `-Z emit-stack-sizes-report`.
+    query mono_call_graph(_: ()) -> MonoCallGraph {
+        arena_cache
+        eval_always
+        no_hash
+        desc { "computing the call graph of the codegened functions" }
+    }
+
     query is_codegened_item(def_id: DefId) -> bool {
         desc { |tcx| "determining whether `{}` needs codegen", tcx.def_path_str(def_id) }
     }
@@ -10,7 +10,8 @@
 use crate::middle::stability;
 use crate::mir::interpret::{self, Allocation, ConstAllocation};
 use crate::mir::{
-    Body, BorrowCheckResult, Field, Local, Place, PlaceElem, ProjectionKind, Promoted,
+    Body, BorrowCheckResult, ExtraMirPasses, Field, Local, Place, PlaceElem, ProjectionKind,
+    Promoted,
 };
 use crate::thir::Thir;
 use crate::traits;
@@ -1041,6 +1042,9 @@ pub struct GlobalCtxt<'tcx> {
     /// we can upcast to `Any` for some additional type safety.
     pub lint_store: Lrc<dyn Any + sync::Sync + sync::Send>,
 
+    /// The MIR passes registered by the driver.
+    pub extra_mir_passes: ExtraMirPasses,
+
     pub dep_graph: DepGraph,
 
     pub prof: SelfProfilerRef,
@@ -1218,6 +1222,7 @@ pub fn lift<T: Lift<'tcx>>(self, value: T) -> Option<T::Lifted> {
     pub fn create_global_ctxt(
         s: &'tcx Session,
         lint_store: Lrc<dyn Any + sync::Send + sync::Sync>,
+        extra_mir_passes: ExtraMirPasses,
         arena: &'tcx WorkerLocal<Arena<'tcx>>,
         hir_arena: &'tcx WorkerLocal<hir::Arena<'tcx>>,
         resolver_outputs: ResolverOutputs,
@@ -1252,6 +1257,7 @@ pub fn create_global_ctxt(
         GlobalCtxt {
             sess: s,
             lint_store,
+            extra_mir_passes,
             arena,
             hir_arena,
             interners,
@@ -1461,6 +1467,13 @@ pub fn def_path_debug_str(self, def_id: DefId) -> String {
         )
     }
 
+    /// The name and location of `def_id`, as reported by `-Z time-by-item`.
+    pub fn time_by_item_key(self, def_id: DefId) -> (String, String) {
+        let name = ty::print::with_no_trimmed_paths!(self.def_path_str(def_id));
+        let location = self.sess.source_map().span_to_embeddable_string(self.def_span(def_id));
+        (name, location)
+    }
+
     /// Create a new definition within the incr. comp. engine.
     pub fn create_def(self, parent: LocalDefId, data: hir::definitions::DefPathData) -> LocalDefId {
         // This function modifies `self.definitions` using a side-effect.
@@ -2827,7 +2840,11 @@ pub fn emit_spanned_lint(
     ) {
         let msg = decorator.msg();
         let (level, src) = self.lint_level_at_node(lint, hir_id);
+        let owner = self.lint_baseline_owner(hir_id);
+        let module = self.lint_module(hir_id);
         struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg, |diag| {
+            diag.lint_owner = owner;
+            diag.lint_module = module;
             decorator.decorate_lint(diag)
         })
     }
@@ -2849,7 +2866,13 @@ pub fn struct_span_lint_hir(
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
         let (level, src) = self.lint_level_at_node(lint, hir_id);
-        struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg, decorate);
+        let owner = self.lint_baseline_owner(hir_id);
+        let module = self.lint_module(hir_id);
+        struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg, |diag| {
+            diag.lint_owner = owner;
+            diag.lint_module = module;
+            decorate(diag)
+        });
     }
 
     /// Emit a lint from a lint struct (some type that implements `DecorateLint`, typically
@@ -2879,7 +2902,29 @@ pub fn struct_lint_node(
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
         let (level, src) = self.lint_level_at_node(lint, id);
-        struct_lint_level(self.sess, lint, level, src, None, msg, decorate);
+        let owner = self.lint_baseline_owner(id);
+        let module = self.lint_module(id);
+        struct_lint_level(self.sess, lint, level, src, None, msg, |diag| {
+            diag.lint_owner = owner;
+            diag.lint_module = module;
+            decorate(diag)
+        });
+    }
+
+    /// The def path of the item that owns `hir_id`, which identifies the lints emitted at
+    /// `hir_id` in a lint baseline. Only computed with `-Z lint-baseline`.
+    fn lint_baseline_owner(self, hir_id: HirId) -> Option<String> {
+        self.sess.opts.unstable_opts.lint_baseline.as_ref()?;
+        Some(ty::print::with_no_trimmed_paths!(self.def_path_str(hir_id.owner.to_def_id())))
+    }
+
+    /// The def path of the module of `hir_id`, which groups the lints emitted at `hir_id` in
+    /// an edition migration report. Only computed with `--edition-migration-report`.
+    fn lint_module(self, hir_id: HirId) -> Option<String> {
+        self.sess.opts.edition_migration_report?;
+        let module = self.parent_module(hir_id).to_def_id();
+        let path = ty::print::with_no_trimmed_paths!(self.def_path_str(module));
+        Some(if path.is_empty() { "crate".to_string() } else { format!("crate::{path}") })
     }
 
     pub fn in_scope_traits(self, id: HirId) -> Option<&'tcx [TraitCandidate]> {
@@ -0,0 +1,121 @@
+//! A pass that propagates the copies between SSA locals.
+//!
+//! When an SSA local is assigned a copy, or a move, of another SSA local, both hold the same
+//! value wherever they are used (see the `ssa` module). The uses of all the locals of a copy
+//! class are replaced by uses of the head of the class, e.g.:
+//!
+//! ```text
+//! _2 = _1;
+//! _3 = move _2;
+//! _4 = Add(_3, const 1_i32);     // becomes `_4 = Add(_1, const 1_i32);`
+//! ```
+//!
+//! The assignments to the replaced locals are then unused, and are removed. As the head of a
+//! class is now used where the other locals of the class were, the storage statements of all
+//! the locals of the class are removed.
+//!
+//! A local cannot be used after it is moved, so the moves of the locals of a class are kept only
+//! if the whole class is only ever moved from: otherwise the head is read again after a move of
+//! it, and the moves are turned into copies. Only values of `Copy` types can be copied, so these
+//! copies are well-formed.
+
+use crate::ssa::SsaLocals;
+use crate::MirPass;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext};
+use rustc_middle::mir::*;
+use rustc_middle::ty::TyCtxt;
+
+pub struct CopyProp;
+
+impl<'tcx> MirPass<'tcx> for CopyProp {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        let dominators = body.basic_blocks.dominators();
+        let ssa = SsaLocals::new(body, &dominators);
+        let copy_classes = ssa.copy_classes();
+        if copy_classes.iter_enumerated().all(|(local, &head)| local == head) {
+            return;
+        }
+
+        let fully_moved = fully_moved_locals(&ssa, body);
+        let mut storage_to_remove = BitSet::new_empty(body.local_decls.len());
+        for (local, &head) in copy_classes.iter_enumerated() {
+            if local != head {
+                debug!(?local, ?head, "replacing copy");
+                storage_to_remove.insert(local);
+                storage_to_remove.insert(head);
+            }
+        }
+
+        Replacer { tcx, copy_classes, fully_moved, storage_to_remove }
+            .visit_body_preserves_cfg(body);
+        crate::simplify::remove_unused_definitions(body);
+    }
+}
+
+/// Returns the locals whose copy class is only ever moved from by the assignments of the class,
+/// so that the moves can stay moves once the locals are replaced by the head of the class.
+fn fully_moved_locals(ssa: &SsaLocals, body: &Body<'_>) -> BitSet<Local> {
+    let mut fully_moved = BitSet::new_filled(body.local_decls.len());
+    for (_, rvalue) in ssa.assignments(body) {
+        let (Rvalue::Use(Operand::Copy(place)) | Rvalue::CopyForDeref(place)) = *rvalue else {
+            continue;
+        };
+        if let Some(rhs) = place.as_local()
+            && ssa.is_ssa(rhs)
+        {
+            fully_moved.remove(rhs);
+        }
+    }
+    ssa.meet_copy_equivalence(&mut fully_moved);
+    fully_moved
+}
+
+/// Replaces the locals of each copy class by the head of the class.
+struct Replacer<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    copy_classes: &'a IndexVec<Local, Local>,
+    fully_moved: BitSet<Local>,
+    storage_to_remove: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.copy_classes[*local];
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if self.storage_to_remove.contains(local) =>
+            {
+                statement.make_nop();
+            }
+            // The assignments to the replaced locals are left alone, they are removed once unused.
+            StatementKind::Assign(box (place, ref mut rvalue)) if place.as_local().is_some() => {
+                self.visit_rvalue(rvalue, location);
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect()
+            && !self.fully_moved.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+        self.super_operand(operand, location);
+    }
+}
@@ -0,0 +1,233 @@
+//! Global value numbering.
+//!
+//! This pass finds the rvalues that compute the same value as an earlier rvalue, and replaces
+//! them with a copy of the local the earlier result was assigned to, e.g.:
+//!
+//! ```text
+//! _3 = Len(_1);
+//! _4 = Lt(_2, _3);
+//! // ...
+//! _7 = Len(_1);     // becomes `_7 = _3;`
+//! _8 = Lt(_6, _7);
+//! ```
+//!
+//! Only the values of SSA locals are numbered: locals that are assigned once, by an assignment
+//! that dominates all of their uses, and that are never borrowed. Such a local holds the same
+//! value everywhere it is used, so two rvalues that apply the same operation to the same SSA
+//! locals and constants compute the same value. The value of a local that is a copy of another
+//! SSA local is the value of the copied local.
+//!
+//! The rvalues that are numbered are the ones that only depend on their operands: binary and
+//! unary operations, casts, `Len` and `Discriminant` of SSA locals, and the size and alignment
+//! of types. `Len` can also read through a reference held by an SSA local, since the length of a
+//! slice is part of the metadata of the reference.
+//!
+//! An rvalue is only replaced by a copy of a local whose assignment dominates it. As the copies
+//! read the reused locals later than the original code did, the storage statements of these
+//! locals are removed, and their moves are turned into copies, which is allowed because the
+//! values this pass numbers have `Copy` types.
+//!
+//! The pass runs from mir-opt-level 1, so that debug builds also benefit from it.
+
+use crate::ssa::SsaLocals;
+use crate::MirPass;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+
+pub struct GVN;
+
+impl<'tcx> MirPass<'tcx> for GVN {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 1
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let dominators = body.basic_blocks.dominators();
+        let ssa = SsaLocals::new(body, &dominators);
+
+        let mut numbering = ValueNumbering {
+            tcx,
+            param_env,
+            body,
+            ssa: &ssa,
+            dominators: &dominators,
+            values: body.local_decls.indices().collect(),
+            exprs: FxHashMap::default(),
+        };
+        let replacements = numbering.find_redundant_rvalues();
+        if replacements.is_empty() {
+            return;
+        }
+
+        let mut reused = BitSet::new_empty(body.local_decls.len());
+        let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+        for (location, local) in replacements {
+            debug!(?location, ?local, "replacing redundant rvalue");
+            let statement = &mut basic_blocks[location.block].statements[location.statement_index];
+            let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind else {
+                bug!("redundant rvalue is not assigned at {:?}", location);
+            };
+            *rvalue = Rvalue::Use(Operand::Copy(local.into()));
+            reused.insert(local);
+        }
+        ReusedLocals { tcx, reused }.visit_body_preserves_cfg(body);
+    }
+}
+
+/// An operand of a numbered rvalue.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum ValueOperand<'tcx> {
+    /// The value of an SSA local, identified by the first local of its chain of copies.
+    Local(Local),
+    Constant(ConstantKind<'tcx>),
+}
+
+/// The value computed by a numbered rvalue.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Expr<'tcx> {
+    BinaryOp(BinOp, ValueOperand<'tcx>, ValueOperand<'tcx>),
+    CheckedBinaryOp(BinOp, ValueOperand<'tcx>, ValueOperand<'tcx>),
+    UnaryOp(UnOp, ValueOperand<'tcx>),
+    Cast(CastKind, ValueOperand<'tcx>, Ty<'tcx>),
+    NullaryOp(NullOp, Ty<'tcx>),
+    /// The length of an array or slice local, or of the one behind a reference if the flag is
+    /// set.
+    Len(Local, bool),
+    Discriminant(Local),
+}
+
+struct ValueNumbering<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    ssa: &'a SsaLocals,
+    dominators: &'a Dominators<BasicBlock>,
+    /// For each SSA local, the first local of the chain of copies it was assigned from.
+    values: IndexVec<Local, Local>,
+    /// The SSA locals that each value was assigned to by a numbered rvalue, with the location
+    /// of the assignment.
+    exprs: FxHashMap<Expr<'tcx>, Vec<(Local, Location)>>,
+}
+
+impl<'tcx> ValueNumbering<'_, 'tcx> {
+    /// Returns the assignments of numbered rvalues that compute the value of an earlier one,
+    /// with the local they can be replaced by a copy of.
+    fn find_redundant_rvalues(&mut self) -> Vec<(Location, Local)> {
+        let body = self.body;
+        let mut replacements = Vec::new();
+        // Blocks are visited after their dominators, so an assignment is always numbered
+        // before the rvalues it dominates.
+        for (block, data) in traversal::reverse_postorder(body) {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let StatementKind::Assign(box (place, rvalue)) = &statement.kind else { continue };
+                let Some(local) = place.as_local() else { continue };
+                if !self.ssa.is_ssa(local) {
+                    continue;
+                }
+
+                if let Rvalue::Use(Operand::Copy(source) | Operand::Move(source)) = rvalue
+                    && let Some(value) = source.as_local().and_then(|source| self.value(source))
+                {
+                    self.values[local] = value;
+                    continue;
+                }
+                let Some(expr) = self.expr(rvalue) else { continue };
+                let ty = body.local_decls[local].ty;
+                if !ty.is_copy_modulo_regions(self.tcx, self.param_env) {
+                    continue;
+                }
+
+                let location = Location { block, statement_index };
+                let dominators = self.dominators;
+                let holders = self.exprs.entry(expr).or_default();
+                let holder = holders.iter().find(|&&(holder, assignment)| {
+                    body.local_decls[holder].ty == ty && assignment.dominates(location, dominators)
+                });
+                if let Some(&(holder, _)) = holder {
+                    self.values[local] = self.values[holder];
+                    replacements.push((location, holder));
+                } else {
+                    holders.push((local, location));
+                }
+            }
+        }
+        replacements
+    }
+
+    fn value(&self, local: Local) -> Option<Local> {
+        self.ssa.is_ssa(local).then(|| self.values[local])
+    }
+
+    fn operand(&self, operand: &Operand<'tcx>) -> Option<ValueOperand<'tcx>> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                Some(ValueOperand::Local(self.value(place.as_local()?)?))
+            }
+            Operand::Constant(constant) => Some(ValueOperand::Constant(constant.literal)),
+        }
+    }
+
+    fn expr(&self, rvalue: &Rvalue<'tcx>) -> Option<Expr<'tcx>> {
+        let expr = match *rvalue {
+            Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) => {
+                Expr::BinaryOp(op, self.operand(lhs)?, self.operand(rhs)?)
+            }
+            Rvalue::CheckedBinaryOp(op, box (ref lhs, ref rhs)) => {
+                Expr::CheckedBinaryOp(op, self.operand(lhs)?, self.operand(rhs)?)
+            }
+            Rvalue::UnaryOp(op, ref operand) => Expr::UnaryOp(op, self.operand(operand)?),
+            Rvalue::Cast(kind, ref operand, ty) => Expr::Cast(kind, self.operand(operand)?, ty),
+            Rvalue::NullaryOp(op, ty) => Expr::NullaryOp(op, ty),
+            Rvalue::Len(place) => match place.as_ref() {
+                PlaceRef { local, projection: [] } => Expr::Len(self.value(local)?, false),
+                PlaceRef { local, projection: [PlaceElem::Deref] } => {
+                    Expr::Len(self.value(local)?, true)
+                }
+                _ => return None,
+            },
+            Rvalue::Discriminant(place) => Expr::Discriminant(self.value(place.as_local()?)?),
+            _ => return None,
+        };
+        Some(expr)
+    }
+}
+
+/// Keeps the locals that replace redundant rvalues alive in the whole body, and turns their
+/// moves into copies.
+struct ReusedLocals<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    reused: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ReusedLocals<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        if let StatementKind::StorageLive(local) | StatementKind::StorageDead(local) =
+            statement.kind
+            && self.reused.contains(local)
+        {
+            statement.make_nop();
+            return;
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand
+            && self.reused.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+        self.super_operand(operand, location);
+    }
+}
@@ -0,0 +1,285 @@
+//! A pass that threads the edges into a `SwitchInt` along which the switched value is known.
+//!
+//! The `?` operator, `matches!` and nested `if let` chains often assign a constant, or set the
+//! discriminant of an enum, in the arms of a first switch, only to switch on it again a few
+//! blocks later:
+//!
+//! ```text
+//! bb1: {                                  bb3: {
+//!     discriminant(_2) = 1;                   discriminant(_2) = 0;
+//!     goto -> bb4;                            goto -> bb4;
+//! }                                       }
+//!
+//! bb4: {
+//!     _8 = discriminant(_2);
+//!     switchInt(move _8) -> [0_isize: bb7, 1_isize: bb5, otherwise: bb6];
+//! }
+//! ```
+//!
+//! A forward dataflow analysis computes the values that the locals hold at each point of the
+//! body: the constants assigned to integer, `bool` and `char` locals, and the discriminants of
+//! enum locals. When the switched value is known at the end of a predecessor that jumps to the
+//! switch with a `goto`, the edge is threaded: the statements of the switch block are duplicated
+//! into a new block that jumps to the target of the value, and the predecessor jumps to the new
+//! block instead. Above, `bb1` ends up jumping to `bb5` and `bb3` to `bb7`. When the value is
+//! known in the switch block whichever predecessor it is reached from, the switch is replaced by
+//! a `goto`.
+//!
+//! Only the locals that are never borrowed are tracked, so that their values can only change
+//! through assignments to them. As threading an edge duplicates the statements of the switch
+//! block, only small blocks are duplicated, within a budget of statements for the whole body.
+//! The blocks that are no longer reachable are removed by a later `SimplifyCfg`.
+
+use crate::MirPass;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_mir_dataflow::fmt::DebugWithContext;
+use rustc_mir_dataflow::impls::borrowed_locals;
+use rustc_mir_dataflow::lattice::FlatSet;
+use rustc_mir_dataflow::{Analysis, AnalysisDomain, CallReturnPlaces};
+use rustc_target::abi::VariantIdx;
+
+/// The largest number of statements that a switch block can have to be duplicated.
+const MAX_DUPLICATED_STATEMENTS: usize = 8;
+/// The number of statements that can be duplicated in a body.
+const DUPLICATION_BUDGET: usize = 64;
+
+pub struct JumpThreading;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(skip_all level = "debug")]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let analysis = KnownValues::new(tcx, param_env, body);
+        if analysis.tracked.is_empty() {
+            return;
+        }
+        let mut cursor =
+            analysis.into_engine(tcx, body).iterate_to_fixpoint().into_results_cursor(body);
+
+        // The switches that always jump to the same target, and the edges to thread, as the
+        // predecessor, the switch block and the target.
+        let mut known_switches = Vec::new();
+        let mut threaded_edges = Vec::new();
+        let mut budget = DUPLICATION_BUDGET;
+        let predecessors = body.basic_blocks.predecessors();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            if data.is_cleanup {
+                continue;
+            }
+            let TerminatorKind::SwitchInt { ref discr, ref targets } = data.terminator().kind else {
+                continue;
+            };
+            let Some(discr) = discr.place().and_then(|discr| discr.as_local()) else { continue };
+
+            cursor.seek_before_primary_effect(body.terminator_loc(block));
+            match cursor.get()[discr] {
+                FlatSet::Elem(value) => {
+                    known_switches.push((block, targets.target_for_value(value)));
+                    continue;
+                }
+                // The block is unreachable.
+                FlatSet::Bottom => continue,
+                FlatSet::Top => {}
+            }
+
+            let cost = data.statements.len();
+            if cost > MAX_DUPLICATED_STATEMENTS {
+                continue;
+            }
+            for &predecessor in &predecessors[block] {
+                if cost > budget {
+                    break;
+                }
+                if !matches!(body[predecessor].terminator().kind, TerminatorKind::Goto { .. }) {
+                    continue;
+                }
+                cursor.seek_to_block_end(predecessor);
+                let mut state = cursor.get().clone();
+                for (statement_index, statement) in data.statements.iter().enumerate() {
+                    let location = Location { block, statement_index };
+                    cursor.analysis().apply_statement_effect(&mut state, statement, location);
+                }
+                if let FlatSet::Elem(value) = state[discr] {
+                    threaded_edges.push((predecessor, block, targets.target_for_value(value)));
+                    budget -= cost;
+                }
+            }
+        }
+        drop(cursor);
+
+        for (block, target) in known_switches {
+            debug!(?block, ?target, "replacing known switch");
+            body.basic_blocks_mut()[block].terminator_mut().kind = TerminatorKind::Goto { target };
+        }
+        for (predecessor, block, target) in threaded_edges {
+            debug!(?predecessor, ?block, ?target, "threading edge");
+            let data = &body.basic_blocks[block];
+            let threaded = BasicBlockData {
+                statements: data.statements.clone(),
+                terminator: Some(Terminator {
+                    source_info: data.terminator().source_info,
+                    kind: TerminatorKind::Goto { target },
+                }),
+                is_cleanup: data.is_cleanup,
+            };
+            let basic_blocks = body.basic_blocks_mut();
+            let threaded = basic_blocks.push(threaded);
+            basic_blocks[predecessor].terminator_mut().kind =
+                TerminatorKind::Goto { target: threaded };
+        }
+    }
+}
+
+/// The values known to be held by the tracked locals: the bits of integer, `bool` and `char`
+/// locals, and the discriminants of enum locals. Untracked locals are always `Top`.
+struct KnownValues<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    tracked: BitSet<Local>,
+}
+
+impl<'a, 'tcx> KnownValues<'a, 'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, body: &'a Body<'tcx>) -> Self {
+        let borrowed = borrowed_locals(body);
+        let mut tracked = BitSet::new_empty(body.local_decls.len());
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            if !borrowed.contains(local) && (decl.ty.is_enum() || has_known_bits(decl.ty)) {
+                tracked.insert(local);
+            }
+        }
+        KnownValues { tcx, param_env, body, tracked }
+    }
+
+    fn discriminant(&self, adt: ty::AdtDef<'tcx>, variant_index: VariantIdx) -> FlatSet<u128> {
+        if adt.is_enum() {
+            FlatSet::Elem(adt.discriminant_for_variant(self.tcx, variant_index).val)
+        } else {
+            FlatSet::Top
+        }
+    }
+
+    /// Records that `value` is written to `place`.
+    fn write(
+        &self,
+        state: &mut IndexVec<Local, FlatSet<u128>>,
+        place: Place<'tcx>,
+        value: FlatSet<u128>,
+    ) {
+        // A tracked local is never borrowed, so a write through a pointer cannot change it.
+        if place.is_indirect() || !self.tracked.contains(place.local) {
+            return;
+        }
+        state[place.local] = if place.projection.is_empty() { value } else { FlatSet::Top };
+    }
+
+    fn eval_rvalue(
+        &self,
+        rvalue: &Rvalue<'tcx>,
+        state: &IndexVec<Local, FlatSet<u128>>,
+    ) -> FlatSet<u128> {
+        match *rvalue {
+            Rvalue::Use(Operand::Constant(ref constant)) if has_known_bits(constant.ty()) => {
+                constant
+                    .literal
+                    .try_eval_bits(self.tcx, self.param_env, constant.ty())
+                    .map_or(FlatSet::Top, FlatSet::Elem)
+            }
+            Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => match place.as_local() {
+                Some(local) => state[local],
+                None => FlatSet::Top,
+            },
+            Rvalue::Discriminant(place) => match place.as_local() {
+                Some(local) if self.body.local_decls[local].ty.is_enum() => state[local],
+                _ => FlatSet::Top,
+            },
+            Rvalue::Aggregate(box AggregateKind::Adt(def_id, variant_index, ..), _) => {
+                self.discriminant(self.tcx.adt_def(def_id), variant_index)
+            }
+            _ => FlatSet::Top,
+        }
+    }
+}
+
+/// Whether the values of a type are tracked as the bits that a `SwitchInt` compares.
+fn has_known_bits(ty: Ty<'_>) -> bool {
+    ty.is_integral() || ty.is_bool() || ty.is_char()
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for KnownValues<'_, 'tcx> {
+    type Domain = IndexVec<Local, FlatSet<u128>>;
+
+    const NAME: &'static str = "KnownValues";
+
+    fn bottom_value(&self, body: &Body<'tcx>) -> Self::Domain {
+        IndexVec::from_elem(FlatSet::Bottom, &body.local_decls)
+    }
+
+    fn initialize_start_block(&self, _body: &Body<'tcx>, state: &mut Self::Domain) {
+        for value in state.iter_mut() {
+            *value = FlatSet::Top;
+        }
+    }
+}
+
+impl<'tcx> Analysis<'tcx> for KnownValues<'_, 'tcx> {
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::Domain,
+        statement: &Statement<'tcx>,
+        _location: Location,
+    ) {
+        match statement.kind {
+            StatementKind::Assign(box (place, ref rvalue)) => {
+                let value = self.eval_rvalue(rvalue, state);
+                self.write(state, place, value);
+            }
+            StatementKind::SetDiscriminant { box place, variant_index } => {
+                // The discriminants of generators are not tracked.
+                let value = match *place.ty(self.body, self.tcx).ty.kind() {
+                    ty::Adt(adt, _) => self.discriminant(adt, variant_index),
+                    _ => FlatSet::Top,
+                };
+                self.write(state, place, value);
+            }
+            StatementKind::Deinit(box place) => self.write(state, place, FlatSet::Top),
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.write(state, local.into(), FlatSet::Top)
+            }
+            // `CopyNonOverlapping` only writes through a pointer.
+            StatementKind::Intrinsic(..)
+            | StatementKind::FakeRead(..)
+            | StatementKind::Retag(..)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::Coverage(..)
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        _state: &mut Self::Domain,
+        _terminator: &Terminator<'tcx>,
+        _location: Location,
+    ) {
+        // Calls and inline assembly write to their destinations in `apply_call_return_effect`.
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut Self::Domain,
+        _block: BasicBlock,
+        return_places: CallReturnPlaces<'_, 'tcx>,
+    ) {
+        return_places.for_each(|place| self.write(state, place, FlatSet::Top));
+    }
+}
+
+impl DebugWithContext<KnownValues<'_, '_>> for IndexVec<Local, FlatSet<u128>> {}
@@ -28,9 +28,9 @@
 use rustc_index::vec::IndexVec;
 use rustc_middle::mir::visit::Visitor as _;
 use rustc_middle::mir::{
-    traversal, AnalysisPhase, Body, ConstQualifs, Constant, LocalDecl, MirPass, MirPhase, Operand,
-    Place, ProjectionElem, Promoted, RuntimePhase, Rvalue, SourceInfo, Statement, StatementKind,
-    TerminatorKind,
+    traversal, AnalysisPhase, Body, ConstQualifs, Constant, LocalDecl, MirPass, MirPassPoint,
+    MirPhase, Operand, Place, ProjectionElem, Promoted, RuntimePhase, Rvalue, SourceInfo,
+    Statement, StatementKind, TerminatorKind,
 };
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, TyCtxt, TypeVisitable};
@@ -39,7 +39,7 @@
 #[macro_use]
 mod pass_manager;
 
-use pass_manager::{self as pm, Lint, MirLint, WithMinOptLevel};
+use pass_manager::{self as pm, ExtraPasses, Lint, MirLint, WithMinOptLevel};
 
 mod abort_unwinding_calls;
 mod add_call_guards;
@@ -54,6 +54,7 @@
 mod const_goto;
 mod const_prop;
 mod const_prop_lint;
+mod copy_prop;
 mod coverage;
 mod dataflow_const_prop;
 mod dead_store_elimination;
@@ -69,8 +70,10 @@
 mod ffi_unwind_calls;
 mod function_item_references;
 mod generator;
+mod gvn;
 mod inline;
 mod instcombine;
+mod jump_threading;
 mod lower_intrinsics;
 mod lower_slice_len;
 mod match_branches;
@@ -94,6 +97,7 @@
 mod simplify_comparison_integral;
 mod simplify_try;
 mod sroa;
+mod ssa;
 mod uninhabited_enum_branching;
 mod unreachable_prop;
 
@@ -454,6 +458,8 @@ fn mir_drops_elaborated_and_const_checked<'tcx>(
         body.tainted_by_errors = Some(error_reported);
     }
 
+    pm::run_extra_passes(tcx, &mut body, MirPassPoint::AfterBorrowck);
+
     run_analysis_to_runtime_passes(tcx, &mut body);
 
     tcx.alloc_steal_mir(body)
@@ -559,7 +565,9 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &unreachable_prop::UnreachablePropagation,
             &uninhabited_enum_branching::UninhabitedEnumBranching,
             &o1(simplify::SimplifyCfg::new("after-uninhabited-enum-branching")),
+            &ExtraPasses(MirPassPoint::BeforeInlining),
             &inline::Inline,
+            &ExtraPasses(MirPassPoint::AfterInlining),
             &remove_storage_markers::RemoveStorageMarkers,
             &remove_zsts::RemoveZsts,
             &const_goto::ConstGoto,
@@ -569,11 +577,14 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
             &multiple_return_terminators::MultipleReturnTerminators,
             &instcombine::InstCombine,
+            &jump_threading::JumpThreading,
             &separate_const_switch::SeparateConstSwitch,
             //
             // FIXME(#70073): This pass is responsible for both optimization as well as some lints.
             &const_prop::ConstProp,
             &dataflow_const_prop::DataflowConstProp,
+            // Runs after const-prop, so that the rvalues const-prop evaluated are not numbered.
+            &gvn::GVN,
             //
             // Const-prop runs unconditionally, but doesn't mutate the MIR at mir-opt-level=0.
             &const_debuginfo::ConstDebugInfo,
@@ -583,6 +594,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &simplify_try::SimplifyArmIdentity,
             &simplify_try::SimplifyBranchSame,
             &dead_store_elimination::DeadStoreElimination,
+            &copy_prop::CopyProp,
             &dest_prop::DestinationPropagation,
             &o1(simplify_branches::SimplifyConstCondition::new("final")),
             &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
@@ -591,6 +603,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &simplify::SimplifyLocals,
             &multiple_return_terminators::MultipleReturnTerminators,
             &deduplicate_blocks::DeduplicateBlocks,
+            &ExtraPasses(MirPassPoint::PreCodegen),
             // Some cleanup necessary at least for LLVM and potentially other codegen backends.
             &add_call_guards::CriticalCallEdges,
             // Dump the end result for testing and debugging purposes.
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use rustc_middle::mir::{self, Body, MirPhase, RuntimePhase};
+use rustc_middle::mir::{self, Body, MirPassPoint, MirPhase, RuntimePhase};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::Session;
 
@@ -68,6 +68,29 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     }
 }
 
+/// Runs the passes that the driver registered at a point of the pipeline, for pass lists.
+pub struct ExtraPasses(pub MirPassPoint);
+
+impl<'tcx> MirPass<'tcx> for ExtraPasses {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        run_extra_passes(tcx, body, self.0)
+    }
+
+    fn is_mir_dump_enabled(&self) -> bool {
+        // The registered passes are dumped on their own.
+        false
+    }
+}
+
+/// Runs the passes that the driver registered at `point`, dumping and validating the MIR like
+/// `run_passes` does.
+pub fn run_extra_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, point: MirPassPoint) {
+    let passes: Vec<_> = tcx.extra_mir_passes.at(point).collect();
+    if !passes.is_empty() {
+        run_passes(tcx, body, &passes, None);
+    }
+}
+
 /// Run the sequence of passes without validating the MIR after each pass. The MIR is still
 /// validated at the end.
 pub fn run_passes_no_validate<'tcx>(
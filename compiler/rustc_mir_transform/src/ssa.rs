@@ -0,0 +1,192 @@
+//! The SSA locals of a MIR body.
+//!
+//! A local is SSA if it is assigned exactly once, by an assignment that dominates all of its
+//! uses, and if it is never borrowed. Arguments are assigned before the body runs. Wherever an
+//! SSA local is used, it holds the value of its only assignment, so passes can reason about its
+//! value without running a dataflow analysis.
+//!
+//! An SSA local whose assignment copies, or moves, another SSA local holds the same value as the
+//! copied local. Such assignments form chains of copies, and the locals of a chain form a copy
+//! class, represented by the first local of the chain: the only one of the class that is not
+//! assigned a copy.
+
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::middle::resolve_lifetime::Set1;
+use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_mir_dataflow::impls::borrowed_locals;
+
+/// Where an SSA local is assigned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Assignment {
+    /// The local is an argument, which is assigned before the body runs.
+    Arg,
+    At(Location),
+}
+
+/// The SSA locals of a body, where they are assigned, and their copy classes.
+pub struct SsaLocals {
+    assignments: IndexVec<Local, Set1<Assignment>>,
+    /// The locals assigned by a statement or a terminator, in reverse postorder of their
+    /// assignments. The assignment of an SSA local comes after the ones of the SSA locals it
+    /// reads, as they dominate it.
+    assignment_order: Vec<Local>,
+    /// The head of the copy class of each local. The locals that are not SSA are the only
+    /// members of their class.
+    copy_classes: IndexVec<Local, Local>,
+}
+
+impl SsaLocals {
+    pub fn new(body: &Body<'_>, dominators: &Dominators<BasicBlock>) -> SsaLocals {
+        let mut assignments = IndexVec::from_elem(Set1::Empty, &body.local_decls);
+        for arg in body.args_iter() {
+            assignments[arg].insert(Assignment::Arg);
+        }
+        // The return place is read by the `Return` terminators, after the end of the body.
+        assignments[RETURN_PLACE] = Set1::Many;
+        // Borrowed locals can be written through their references. Dropped locals count as
+        // borrowed, as dropping a local may call `Drop::drop` on a mutable reference to it.
+        for local in borrowed_locals(body).iter() {
+            assignments[local] = Set1::Many;
+        }
+
+        let mut visitor =
+            SsaVisitor { assignments, assignment_order: Vec::new(), uses: Vec::new() };
+        for (block, data) in traversal::reverse_postorder(body) {
+            visitor.visit_basic_block_data(block, data);
+        }
+        let SsaVisitor { mut assignments, assignment_order, uses } = visitor;
+
+        for (local, location) in uses {
+            if let Set1::One(Assignment::At(assignment)) = assignments[local]
+                && (assignment == location || !assignment.dominates(location, dominators))
+            {
+                assignments[local] = Set1::Many;
+            }
+        }
+
+        let copy_classes = body.local_decls.indices().collect();
+        let mut ssa = SsaLocals { assignments, assignment_order, copy_classes };
+        ssa.compute_copy_classes(body);
+        ssa
+    }
+
+    pub fn is_ssa(&self, local: Local) -> bool {
+        matches!(self.assignments[local], Set1::One(_))
+    }
+
+    /// Returns the SSA locals assigned by a statement, with the rvalue assigned to them, in
+    /// reverse postorder of their assignments.
+    pub fn assignments<'a, 'tcx>(
+        &'a self,
+        body: &'a Body<'tcx>,
+    ) -> impl Iterator<Item = (Local, &'a Rvalue<'tcx>)> + 'a {
+        self.assignment_order.iter().filter_map(|&local| {
+            let Set1::One(Assignment::At(location)) = self.assignments[local] else { return None };
+            let data = &body.basic_blocks[location.block];
+            let statement = data.statements.get(location.statement_index)?;
+            let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else {
+                return None;
+            };
+            assert_eq!(place.as_local(), Some(local));
+            Some((local, rvalue))
+        })
+    }
+
+    /// Returns the head of the copy class of each local.
+    pub fn copy_classes(&self) -> &IndexVec<Local, Local> {
+        &self.copy_classes
+    }
+
+    /// Makes `property` hold for the members of a copy class only if it holds for all of them.
+    pub fn meet_copy_equivalence(&self, property: &mut BitSet<Local>) {
+        for (local, &head) in self.copy_classes.iter_enumerated() {
+            if !property.contains(local) {
+                property.remove(head);
+            }
+        }
+        for (local, &head) in self.copy_classes.iter_enumerated() {
+            if !property.contains(head) {
+                property.remove(local);
+            }
+        }
+    }
+
+    fn compute_copy_classes(&mut self, body: &Body<'_>) {
+        let copies: Vec<_> = self
+            .assignments(body)
+            .filter_map(|(local, rvalue)| match *rvalue {
+                Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+                | Rvalue::CopyForDeref(place) => {
+                    place.as_local().filter(|&rhs| self.is_ssa(rhs)).map(|rhs| (local, rhs))
+                }
+                _ => None,
+            })
+            .collect();
+        // The assignment of `rhs` comes first, so its class is already known.
+        for (local, rhs) in copies {
+            self.copy_classes[local] = self.copy_classes[rhs];
+        }
+    }
+}
+
+/// Collects the assignments and the uses of the locals in the reachable blocks of a body.
+struct SsaVisitor {
+    assignments: IndexVec<Local, Set1<Assignment>>,
+    assignment_order: Vec<Local>,
+    uses: Vec<(Local, Location)>,
+}
+
+impl<'tcx> Visitor<'tcx> for SsaVisitor {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+        match context {
+            PlaceContext::MutatingUse(
+                MutatingUseContext::Store
+                | MutatingUseContext::Call
+                | MutatingUseContext::AsmOutput
+                | MutatingUseContext::Yield,
+            ) => {
+                self.assignments[local].insert(Assignment::At(location));
+                if let Set1::One(_) = self.assignments[local] {
+                    self.assignment_order.push(local);
+                }
+            }
+            PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::Copy
+                | NonMutatingUseContext::Move
+                | NonMutatingUseContext::Inspect
+                | NonMutatingUseContext::Projection,
+            ) => self.uses.push((local, location)),
+            PlaceContext::NonUse(_) => {}
+            // Borrows, drops, and writes to a part of the local.
+            _ => self.assignments[local] = Set1::Many,
+        }
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if place.projection.first() == Some(&PlaceElem::Deref) {
+            // Only the pointer is read, whatever is done with the pointee.
+            let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy);
+            self.visit_local(place.local, context, location);
+        } else if !place.projection.is_empty() {
+            let context =
+                if context.is_mutating_use() || context.is_borrow() || context.is_address_of() {
+                    PlaceContext::MutatingUse(MutatingUseContext::Projection)
+                } else {
+                    PlaceContext::NonMutatingUse(NonMutatingUseContext::Projection)
+                };
+            self.visit_local(place.local, context, location);
+        } else {
+            self.visit_local(place.local, context, location);
+        }
+
+        for elem in place.projection {
+            if let PlaceElem::Index(local) = elem {
+                let context = PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy);
+                self.visit_local(local, context, location);
+            }
+        }
+    }
+}
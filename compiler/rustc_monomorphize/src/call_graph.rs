@@ -0,0 +1,102 @@
+//! The call graph of the functions in the codegen units, which `-Z emit-stack-sizes-report`
+//! combines with the frame sizes reported by the backend.
+//!
+//! Unlike the graph of mono items built by the collector, which records every item that the
+//! code of an item may use, this graph only records calls: a function that takes a function
+//! pointer does not call it. Calls through function pointers and trait objects cannot be
+//! followed, and are only recorded as indirect calls.
+
+use rustc_middle::mir::mono::{MonoCallGraph, MonoFunction, MonoItem};
+use rustc_middle::mir::TerminatorKind;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+
+fn mono_call_graph(tcx: TyCtxt<'_>, (): ()) -> MonoCallGraph {
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    let mut graph = MonoCallGraph::default();
+    for cgu in codegen_units {
+        for (item, _) in cgu.items_in_deterministic_order(tcx) {
+            let MonoItem::Fn(instance) = item else { continue };
+            let symbol = item.symbol_name(tcx).name.to_string();
+            // Items with a local copy in several codegen units share their symbol name.
+            if graph.functions.contains_key(&symbol) {
+                continue;
+            }
+            let function = calls(tcx, instance, &mut graph);
+            graph.names.insert(symbol.clone(), with_no_trimmed_paths!(instance.to_string()));
+            graph.functions.insert(symbol, function);
+        }
+    }
+    graph
+}
+
+fn calls<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    graph: &mut MonoCallGraph,
+) -> MonoFunction {
+    let body = tcx.instance_mir(instance.def);
+    let param_env = ty::ParamEnv::reveal_all();
+    let mut function = MonoFunction::default();
+    let mut add_callee = |callee: Instance<'tcx>| {
+        let symbol = tcx.symbol_name(callee).name.to_string();
+        if !function.callees.contains(&symbol) {
+            graph
+                .names
+                .entry(symbol.clone())
+                .or_insert_with(|| with_no_trimmed_paths!(callee.to_string()));
+            function.callees.push(symbol);
+        }
+    };
+
+    let mut has_indirect_calls = false;
+    for block in body.basic_blocks.iter() {
+        match &block.terminator().kind {
+            TerminatorKind::Call { func, .. } => {
+                let callee_ty = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    param_env,
+                    func.ty(body, tcx),
+                );
+                match *callee_ty.kind() {
+                    ty::FnDef(def_id, substs) => {
+                        match Instance::resolve(tcx, param_env, def_id, substs) {
+                            // Intrinsics are not calls once codegened.
+                            Ok(Some(Instance { def: InstanceDef::Intrinsic(..), .. })) => {}
+                            Ok(Some(Instance { def: InstanceDef::Virtual(..), .. })) => {
+                                has_indirect_calls = true;
+                            }
+                            Ok(Some(callee)) => add_callee(callee),
+                            _ => has_indirect_calls = true,
+                        }
+                    }
+                    ty::FnPtr(_) => has_indirect_calls = true,
+                    _ => {}
+                }
+            }
+            TerminatorKind::Drop { place, .. } | TerminatorKind::DropAndReplace { place, .. } => {
+                let ty = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    param_env,
+                    place.ty(body, tcx).ty,
+                );
+                if ty.is_trait() {
+                    has_indirect_calls = true;
+                    continue;
+                }
+                let callee = Instance::resolve_drop_in_place(tcx, ty);
+                if !matches!(callee.def, InstanceDef::DropGlue(_, None)) {
+                    add_callee(callee);
+                }
+            }
+            _ => {}
+        }
+    }
+    function.has_indirect_calls = has_indirect_calls;
+    function
+}
+
+pub fn provide(providers: &mut Providers) {
+    providers.mono_call_graph = mono_call_graph;
+}
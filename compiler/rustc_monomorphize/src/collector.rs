@@ -174,6 +174,7 @@
 //! regardless of whether it is actually needed or not.
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::profiling::ItemPhase;
 use rustc_data_structures::sync::{par_for_each_in, MTLock, MTRef};
 use rustc_hir as hir;
 use rustc_hir::def::DefKind;
@@ -475,6 +476,9 @@ fn collect_items_rec<'tcx>(
             ));
             check_type_length_limit(tcx, instance);
 
+            let _timer = tcx.prof.item_activity(ItemPhase::Monomorphization, || {
+                tcx.time_by_item_key(instance.def_id())
+            });
             rustc_data_structures::stack::ensure_sufficient_stack(|| {
                 collect_neighbours(tcx, instance, &mut neighbors);
             });
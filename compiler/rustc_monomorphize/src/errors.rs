@@ -2,7 +2,7 @@
 
 use rustc_errors::ErrorGuaranteed;
 use rustc_errors::IntoDiagnostic;
-use rustc_macros::{Diagnostic, LintDiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::Span;
 
 #[derive(Diagnostic)]
@@ -66,6 +66,45 @@ pub struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_reachable_panic)]
+pub struct ReachablePanic {
+    pub function: String,
+    #[subdiagnostic]
+    pub site: PanicSite,
+    #[note(call_chain)]
+    pub through_calls: Option<()>,
+    pub call_chain: String,
+    #[note(others)]
+    pub has_others: Option<()>,
+    pub others: usize,
+}
+
+#[derive(Subdiagnostic)]
+pub enum PanicSite {
+    #[note(monomorphize_panic_call)]
+    PanicCall {
+        #[primary_span]
+        span: Span,
+        callee: String,
+    },
+    #[note(monomorphize_panic_assert)]
+    Assert {
+        #[primary_span]
+        span: Span,
+    },
+    #[note(monomorphize_panic_unchecked_call)]
+    UncheckedCall {
+        #[primary_span]
+        span: Span,
+    },
+    #[note(monomorphize_panic_virtual_drop)]
+    VirtualDrop {
+        #[primary_span]
+        span: Span,
+    },
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_unknown_partition_strategy)]
 pub struct UnknownPartitionStrategy;
@@ -16,8 +16,10 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 
+mod call_graph;
 mod collector;
 mod errors;
+mod no_panic;
 mod partitioning;
 mod polymorphize;
 mod util;
@@ -46,6 +48,7 @@ fn custom_coerce_unsize_info<'tcx>(
 }
 
 pub fn provide(providers: &mut Providers) {
+    call_graph::provide(providers);
     partitioning::provide(providers);
     polymorphize::provide(providers);
 }
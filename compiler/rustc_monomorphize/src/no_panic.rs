@@ -0,0 +1,214 @@
+//! The check of functions marked `#[no_panic]`, reported by the `reachable_panics` lint.
+//!
+//! Starting at every instance of a `#[no_panic]` function, the graph of mono items built by
+//! the collector is searched for code that can panic:
+//!
+//! - a call to one of the panic entry points of the standard library, which are lang items;
+//! - an `Assert` terminator, which panics when its condition does not hold;
+//! - a call to a function whose body is not part of the graph, like a foreign function, a
+//!   function pointer, a trait object method or a function codegened upstream, whatever the
+//!   panic strategy, as nothing is known of what these functions do;
+//! - a drop of a trait object, which runs the destructor found in its vtable.
+//!
+//! The graph contains every item that the code of an item may use, including the drop glue
+//! it needs and the methods of the vtables it creates, so the check is conservative: code
+//! that is only reachable through a function pointer that is never called is still reported.
+//! The search is breadth first, and the shortest chain of calls to a panic is reported.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::DefId;
+use rustc_hir::LangItem;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::mir::TerminatorKind;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_session::lint::builtin::REACHABLE_PANICS;
+use rustc_span::symbol::sym;
+
+use std::collections::VecDeque;
+
+use crate::collector::InliningMap;
+use crate::errors::{PanicSite, ReachablePanic};
+
+/// The panic entry points. Calls to them are reported rather than followed.
+const PANIC_LANG_ITEMS: &[LangItem] = &[
+    LangItem::Panic,
+    LangItem::PanicFmt,
+    LangItem::PanicDisplay,
+    LangItem::ConstPanicFmt,
+    LangItem::PanicBoundsCheck,
+    LangItem::PanicImpl,
+    LangItem::PanicNoUnwind,
+    LangItem::BeginPanic,
+];
+
+pub(crate) fn check_no_panic<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+) {
+    let mut roots: Vec<_> = items
+        .iter()
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance @ Instance { def: InstanceDef::Item(def), .. })
+                if def.did.is_local() && tcx.has_attr(def.did, sym::no_panic) =>
+            {
+                Some(instance)
+            }
+            _ => None,
+        })
+        .collect();
+    if roots.is_empty() {
+        return;
+    }
+    roots.sort_by_cached_key(|instance| tcx.def_span(instance.def_id()));
+
+    let panic_fns: FxHashSet<DefId> =
+        PANIC_LANG_ITEMS.iter().filter_map(|&item| tcx.lang_items().get(item)).collect();
+    let mut accesses = FxHashMap::default();
+    inlining_map.iter_accesses(|accessor, accessees| {
+        accesses.insert(accessor, accessees.to_vec());
+    });
+
+    for root in roots {
+        check_root(tcx, root, items, &accesses, &panic_fns);
+    }
+}
+
+fn check_root<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    root: Instance<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    accesses: &FxHashMap<MonoItem<'tcx>, Vec<MonoItem<'tcx>>>,
+    panic_fns: &FxHashSet<DefId>,
+) {
+    let root_item = MonoItem::Fn(root);
+    // Maps each item found by the search to the item it was first reached from.
+    let mut reached_from = FxHashMap::default();
+    let mut queue = VecDeque::from([root_item]);
+    let mut first_site = None;
+    let mut other_sites = 0;
+
+    while let Some(item) = queue.pop_front() {
+        if let MonoItem::Fn(instance) = item {
+            let mut sites = panic_sites(tcx, instance, items, panic_fns).into_iter();
+            if first_site.is_none() {
+                first_site = sites.next().map(|site| (item, site));
+            }
+            other_sites += sites.len();
+        }
+
+        for &accessed in accesses.get(&item).into_iter().flatten() {
+            // Drops of trait objects are reported where they happen, so their drop glue
+            // isn't searched.
+            let is_reported = match accessed {
+                MonoItem::Fn(instance) => {
+                    panic_fns.contains(&instance.def_id()) || is_virtual_drop_glue(instance)
+                }
+                _ => false,
+            };
+            if is_reported || accessed == root_item || reached_from.contains_key(&accessed) {
+                continue;
+            }
+            reached_from.insert(accessed, item);
+            queue.push_back(accessed);
+        }
+    }
+
+    let Some((mut item, site)) = first_site else { return };
+    let mut chain = vec![item];
+    while let Some(&caller) = reached_from.get(&item) {
+        chain.push(caller);
+        item = caller;
+    }
+    let path: Vec<_> = chain
+        .iter()
+        .rev()
+        .map(|item| match item {
+            MonoItem::Fn(instance) => with_no_trimmed_paths!(format!("`{instance}`")),
+            _ => with_no_trimmed_paths!(format!("`{item}`")),
+        })
+        .collect();
+
+    let def_id = root.def_id().expect_local();
+    tcx.emit_spanned_lint(
+        REACHABLE_PANICS,
+        tcx.hir().local_def_id_to_hir_id(def_id),
+        tcx.def_span(def_id),
+        ReachablePanic {
+            function: with_no_trimmed_paths!(root.to_string()),
+            site,
+            through_calls: (chain.len() > 1).then_some(()),
+            call_chain: path.join(" -> "),
+            has_others: (other_sites > 0).then_some(()),
+            others: other_sites,
+        },
+    );
+}
+
+/// Returns the places in the body of `instance` that can panic, in the order of its blocks.
+fn panic_sites<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    panic_fns: &FxHashSet<DefId>,
+) -> Vec<PanicSite> {
+    let body = tcx.instance_mir(instance.def);
+    let param_env = ty::ParamEnv::reveal_all();
+    let mut sites = Vec::new();
+    for block in body.basic_blocks.iter() {
+        // Cleanup blocks only run while unwinding from a panic reported elsewhere.
+        if block.is_cleanup {
+            continue;
+        }
+        let terminator = block.terminator();
+        let span = terminator.source_info.span;
+        match &terminator.kind {
+            TerminatorKind::Assert { .. } => sites.push(PanicSite::Assert { span }),
+            TerminatorKind::Drop { place, .. } | TerminatorKind::DropAndReplace { place, .. } => {
+                let ty = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    param_env,
+                    place.ty(body, tcx).ty,
+                );
+                if ty.is_trait() {
+                    sites.push(PanicSite::VirtualDrop { span });
+                }
+            }
+            TerminatorKind::Call { func, .. } => {
+                let callee_ty = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    param_env,
+                    func.ty(body, tcx),
+                );
+                match *callee_ty.kind() {
+                    ty::FnDef(def_id, _) if panic_fns.contains(&def_id) => {
+                        let callee = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+                        sites.push(PanicSite::PanicCall { span, callee });
+                    }
+                    ty::FnDef(def_id, substs) => {
+                        match Instance::resolve(tcx, param_env, def_id, substs) {
+                            // Intrinsics have no body, they are lowered by the backend.
+                            Ok(Some(Instance { def: InstanceDef::Intrinsic(_), .. })) => {}
+                            Ok(Some(callee)) if is_virtual_drop_glue(callee) => {
+                                sites.push(PanicSite::VirtualDrop { span });
+                            }
+                            Ok(Some(callee)) if items.contains(&MonoItem::Fn(callee)) => {}
+                            _ => sites.push(PanicSite::UncheckedCall { span }),
+                        }
+                    }
+                    ty::FnPtr(_) => sites.push(PanicSite::UncheckedCall { span }),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    sites
+}
+
+/// Whether `instance` is the drop glue of a trait object, which calls the destructor found in
+/// its vtable.
+fn is_virtual_drop_glue(instance: Instance<'_>) -> bool {
+    matches!(instance.def, InstanceDef::DropGlue(_, Some(ty)) if ty.is_trait())
+}
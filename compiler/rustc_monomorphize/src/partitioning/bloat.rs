@@ -0,0 +1,185 @@
+//! The monomorphization bloat report printed by `-Z print-mono-bloat`.
+//!
+//! For every generic definition that was instantiated, the report lists how
+//! often it was instantiated, how many copies of its instances ended up in the
+//! codegen units, and how many MIR statements its instances contribute. Each
+//! instance is listed with a chain of callers that introduced it, starting at a
+//! non-generic item. Of all such chains, the shortest one is shown.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::pluralize;
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::mono::{CodegenUnit, MonoItem};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, TyCtxt};
+
+use std::collections::VecDeque;
+
+use crate::collector::InliningMap;
+
+struct GenericDefinition<'tcx> {
+    name: String,
+    /// The instances, with their copies in the codegen units and their MIR statement counts.
+    instances: Vec<(Instance<'tcx>, usize, usize)>,
+    copies: usize,
+    statements: usize,
+}
+
+pub(super) fn print_mono_bloat<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+    codegen_units: &[CodegenUnit<'tcx>],
+) {
+    let introduced_by = introducing_callers(items, inlining_map);
+
+    let mut copies: FxHashMap<MonoItem<'tcx>, usize> = FxHashMap::default();
+    for cgu in codegen_units {
+        for &mono_item in cgu.items().keys() {
+            *copies.entry(mono_item).or_default() += 1;
+        }
+    }
+
+    let mut definitions: FxHashMap<DefId, GenericDefinition<'tcx>> = FxHashMap::default();
+    for &mono_item in items {
+        let MonoItem::Fn(instance) = mono_item else { continue };
+        if !mono_item.is_generic_fn() {
+            continue;
+        }
+
+        let def_id = instance.def_id();
+        let definition = definitions.entry(def_id).or_insert_with(|| GenericDefinition {
+            name: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+            instances: vec![],
+            copies: 0,
+            statements: 0,
+        });
+        let copies = copies.get(&mono_item).copied().unwrap_or(0);
+        let statements = statement_count(tcx, instance);
+        definition.instances.push((instance, copies, statements));
+        definition.copies += copies;
+        definition.statements += statements;
+    }
+
+    let mut definitions: Vec<_> = definitions.into_values().collect();
+    definitions.sort_by(|a, b| b.statements.cmp(&a.statements).then_with(|| a.name.cmp(&b.name)));
+
+    for definition in definitions {
+        println!(
+            "MONO_BLOAT {}",
+            report_line(
+                &definition.name,
+                Some(definition.instances.len()),
+                definition.copies,
+                definition.statements,
+            )
+        );
+
+        let mut instances: Vec<(String, Instance<'tcx>, usize, usize)> = definition
+            .instances
+            .into_iter()
+            .map(|(instance, copies, statements)| {
+                (item_name(tcx, MonoItem::Fn(instance)), instance, copies, statements)
+            })
+            .collect();
+        instances.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, instance, copies, statements) in instances {
+            let mut line = report_line(&name, None, copies, statements);
+            let chain = caller_chain(&introduced_by, MonoItem::Fn(instance));
+            if !chain.is_empty() {
+                line.push_str(", introduced by ");
+                for (i, &caller) in chain.iter().enumerate() {
+                    if i > 0 {
+                        line.push_str(" -> ");
+                    }
+                    line.push_str(&format!("`{}`", item_name(tcx, caller)));
+                }
+            }
+            println!("MONO_BLOAT     {}", line);
+        }
+    }
+}
+
+/// Formats the counts of a generic definition, or of one of its instances if `instantiations`
+/// is `None`.
+fn report_line(
+    name: &str,
+    instantiations: Option<usize>,
+    copies: usize,
+    statements: usize,
+) -> String {
+    let mut line = format!("`{}`: ", name);
+    if let Some(instantiations) = instantiations {
+        line.push_str(&format!("{} instantiation{}, ", instantiations, pluralize!(instantiations)));
+    }
+    line.push_str(&format!(
+        "{} {} in codegen units, {} MIR statement{}",
+        copies,
+        if copies == 1 { "copy" } else { "copies" },
+        statements,
+        pluralize!(statements)
+    ));
+    line
+}
+
+/// The name of an instance in the report, which is also used for the callers that introduced it.
+fn item_name<'tcx>(tcx: TyCtxt<'tcx>, item: MonoItem<'tcx>) -> String {
+    match item {
+        MonoItem::Fn(instance) => with_no_trimmed_paths!(instance.to_string()),
+        MonoItem::Static(def_id) => with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+        MonoItem::GlobalAsm(..) => with_no_trimmed_paths!(item.to_string()),
+    }
+}
+
+fn statement_count<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> usize {
+    tcx.instance_mir(instance.def).basic_blocks.iter().map(|block| block.statements.len()).sum()
+}
+
+/// Maps every generic mono item to the item that first used it, in a
+/// breadth-first search starting at all non-generic items.
+fn introducing_callers<'tcx>(
+    items: &FxHashSet<MonoItem<'tcx>>,
+    inlining_map: &InliningMap<'tcx>,
+) -> FxHashMap<MonoItem<'tcx>, MonoItem<'tcx>> {
+    let mut accesses: FxHashMap<MonoItem<'tcx>, Vec<MonoItem<'tcx>>> = FxHashMap::default();
+    inlining_map.iter_accesses(|accessor, accessees| {
+        accesses.insert(accessor, accessees.to_vec());
+    });
+
+    // Start from the items in a deterministic order, so that the same chains
+    // are reported in every compilation.
+    let mut roots: Vec<(String, MonoItem<'tcx>)> = items
+        .iter()
+        .filter(|item| !item.is_generic_fn())
+        .map(|&item| (with_no_trimmed_paths!(item.to_string()), item))
+        .collect();
+    roots.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut introduced_by = FxHashMap::default();
+    let mut queue: VecDeque<_> = roots.into_iter().map(|(_, item)| item).collect();
+    while let Some(item) = queue.pop_front() {
+        for &accessee in accesses.get(&item).map_or(&[][..], |a| &a[..]) {
+            if accessee.is_generic_fn() && !introduced_by.contains_key(&accessee) {
+                introduced_by.insert(accessee, item);
+                queue.push_back(accessee);
+            }
+        }
+    }
+    introduced_by
+}
+
+/// The callers that introduced `item`, starting with a non-generic item.
+fn caller_chain<'tcx>(
+    introduced_by: &FxHashMap<MonoItem<'tcx>, MonoItem<'tcx>>,
+    item: MonoItem<'tcx>,
+) -> Vec<MonoItem<'tcx>> {
+    let mut chain = vec![];
+    let mut current = item;
+    while let Some(&caller) = introduced_by.get(&current) {
+        chain.push(caller);
+        current = caller;
+    }
+    chain.reverse();
+    chain
+}
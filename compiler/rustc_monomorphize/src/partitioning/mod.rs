@@ -92,6 +92,7 @@
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
 
+mod bloat;
 mod default;
 mod merging;
 
@@ -109,6 +110,7 @@
 use crate::collector::InliningMap;
 use crate::collector::{self, MonoItemCollectionMode};
 use crate::errors::{SymbolAlreadyDefined, UnknownPartitionStrategy};
+use crate::no_panic;
 
 pub struct PartitioningCx<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -373,6 +375,8 @@ fn collect_and_partition_mono_items<'tcx>(
 
     let (items, inlining_map) = collector::collect_crate_mono_items(tcx, collection_mode);
 
+    no_panic::check_no_panic(tcx, &items, &inlining_map);
+
     tcx.sess.abort_if_errors();
 
     let (codegen_units, _) = tcx.sess.time("partition_and_assert_distinct_symbols", || {
@@ -402,6 +406,25 @@ fn collect_and_partition_mono_items<'tcx>(
         }
     }
 
+    tcx.prof.with_profiler(|profiler| {
+        if !profiler.item_times_enabled() {
+            return;
+        }
+        // `-Z time-by-item` splits the time spent on a CGU between its items.
+        for cgu in codegen_units {
+            let items = cgu.items().keys().map(|mono_item| {
+                let def_id = match *mono_item {
+                    MonoItem::Fn(instance) => instance.def_id(),
+                    MonoItem::Static(def_id) => def_id,
+                    MonoItem::GlobalAsm(item_id) => item_id.owner_id.to_def_id(),
+                };
+                let (name, location) = tcx.time_by_item_key(def_id);
+                (name, location, mono_item.size_estimate(tcx) as u64)
+            });
+            profiler.add_codegen_unit_items(cgu.name().as_str(), items);
+        }
+    });
+
     let mono_items: DefIdSet = items
         .iter()
         .filter_map(|mono_item| match *mono_item {
@@ -462,6 +485,10 @@ fn collect_and_partition_mono_items<'tcx>(
         }
     }
 
+    if tcx.sess.opts.unstable_opts.print_mono_bloat {
+        bloat::print_mono_bloat(tcx, &items, &inlining_map, codegen_units);
+    }
+
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
@@ -3,8 +3,8 @@
 use rustc_ast::tokenstream::{DelimSpan, Spacing, TokenStream, TokenTree};
 use rustc_ast_pretty::pprust::token_to_string;
 use rustc_data_structures::fx::FxHashMap;
-use rustc_errors::{PErr, PResult};
-use rustc_span::Span;
+use rustc_errors::{pluralize, Applicability, PErr, PResult};
+use rustc_span::{BytePos, Pos, Span};
 
 pub(super) struct TokenTreesReader<'a> {
     string_reader: StringReader<'a>,
@@ -40,7 +40,12 @@ pub(super) fn parse_all_token_trees(
             last_delim_empty_block_spans: FxHashMap::default(),
             matching_block_spans: Vec::new(),
         };
-        let res = tt_reader.parse_token_trees(/* is_delimited */ false);
+        let res = match tt_reader.parse_token_trees(/* is_delimited */ false) {
+            Ok(stream) if !tt_reader.open_braces.is_empty() => {
+                Ok(tt_reader.report_unclosed_at_eof(stream))
+            }
+            res => res,
+        };
         (res, tt_reader.unmatched_braces)
     }
 
@@ -59,9 +64,7 @@ fn parse_token_trees(&mut self, is_delimited: bool) -> PResult<'a, TokenStream>
                     };
                 }
                 token::Eof => {
-                    if is_delimited {
-                        self.eof_err().emit();
-                    }
+                    // Unclosed delimiters are reported once the whole file has been read.
                     return Ok(TokenStream::new(buf));
                 }
                 _ => {
@@ -89,11 +92,210 @@ fn parse_token_trees(&mut self, is_delimited: bool) -> PResult<'a, TokenStream>
         }
     }
 
+    /// Reports the delimiters that are still open at the end of the file, given the token trees
+    /// of the file, and returns the token trees to parse.
+    fn report_unclosed_at_eof(&mut self, stream: TokenStream) -> TokenStream {
+        if let Some(stream) = self.recover_unclosed_braces(&stream) {
+            return stream;
+        }
+        // The token trees of each of the unclosed delimiters end at the end of the file.
+        for _ in 0..self.open_braces.len() {
+            self.eof_err().emit();
+        }
+        stream
+    }
+
+    /// Guesses where the unclosed delimiters were meant to be closed from the indentation of
+    /// the code, and closes them there with a single error, so that the rest of the file is
+    /// parsed normally instead of as the contents of the unclosed delimiters.
+    ///
+    /// This is only done if all of them are braces that end their line, like the braces of
+    /// items and blocks, if the contents of the braces of the file are consistently indented,
+    /// and if one of them was likely not meant to be closed at the end of the file. Returns
+    /// `None` otherwise. The error is the same as without recovery, with a suggestion.
+    fn recover_unclosed_braces(&mut self, stream: &TokenStream) -> Option<TokenStream> {
+        if self.string_reader.override_span.is_some()
+            || !self.unmatched_braces.is_empty()
+            || self.open_braces.iter().any(|&(delim, _)| delim != Delimiter::Brace)
+        {
+            return None;
+        }
+
+        let mut tokens = Vec::new();
+        flatten_token_trees(stream, &self.open_braces, &mut tokens);
+        if !self.is_consistently_indented(&tokens) {
+            return None;
+        }
+        let mut closes: Vec<usize> = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| {
+                matches!(token, FlatToken::Open(_, span)
+                    if self.open_braces.iter().any(|&(_, sp)| sp == *span))
+            })
+            .map(|(open, _)| self.guess_close(&tokens, open).unwrap_or(tokens.len()))
+            .collect();
+        if closes.iter().all(|&close| close == tokens.len()) {
+            return None;
+        }
+        closes.sort();
+
+        // Rebuild the token trees with the guessed closing braces, checking that each of them
+        // closes a brace and does not make other delimiters mismatched.
+        let mut stack: Vec<(Delimiter, Span, Vec<TokenTree>)> = Vec::new();
+        let mut buf = Vec::new();
+        // The opening brace closed by each guessed closing brace, with the index of the token
+        // it is inserted before.
+        let mut closed = Vec::new();
+        let mut closes = closes.into_iter().peekable();
+        for i in 0..=tokens.len() {
+            while closes.next_if_eq(&i).is_some() {
+                let (Delimiter::Brace, open_span, tts) = stack.pop()? else { return None };
+                let close_span = match tokens.get(i) {
+                    Some(token) => token.span().shrink_to_lo(),
+                    None => self.token.span,
+                };
+                let tree = TokenTree::Delimited(
+                    DelimSpan::from_pair(open_span, close_span),
+                    Delimiter::Brace,
+                    TokenStream::new(tts),
+                );
+                stack.last_mut().map_or(&mut buf, |(_, _, tts)| tts).push(tree);
+                closed.push((open_span, i));
+            }
+            match tokens.get(i) {
+                Some(FlatToken::Token(token, spacing)) => {
+                    let tree = TokenTree::Token(token.clone(), *spacing);
+                    stack.last_mut().map_or(&mut buf, |(_, _, tts)| tts).push(tree);
+                }
+                Some(&FlatToken::Open(delim, span)) => stack.push((delim, span, Vec::new())),
+                Some(&FlatToken::Close(delim, span)) => {
+                    let (open_delim, open_span, tts) = stack.pop()?;
+                    if open_delim != delim {
+                        return None;
+                    }
+                    let tree = TokenTree::Delimited(
+                        DelimSpan::from_pair(open_span, span),
+                        delim,
+                        TokenStream::new(tts),
+                    );
+                    stack.last_mut().map_or(&mut buf, |(_, _, tts)| tts).push(tree);
+                }
+                None => {}
+            }
+        }
+        if !stack.is_empty() {
+            return None;
+        }
+
+        // Suggest each closing brace on its own line, with the indentation of the line of its
+        // opening brace.
+        let src = self.string_reader.src;
+        let mut suggestion: Vec<(Span, String)> = Vec::new();
+        for &(open_span, i) in &closed {
+            let (pos, prefix) = match tokens.get(i) {
+                Some(token) => (self.line_start(token.span().lo()), ""),
+                None if src.is_empty() || src.ends_with('\n') => (self.token.span.lo(), ""),
+                None => (self.token.span.lo(), "\n"),
+            };
+            let close = format!("{prefix}{}}}\n", self.indentation(open_span.lo()));
+            match suggestion.last_mut() {
+                Some((span, snippet)) if span.lo() == pos => snippet.push_str(&close),
+                _ => suggestion.push((Span::with_root_ctxt(pos, pos), close)),
+            }
+        }
+
+        // The token trees are not unmatched anymore, so unlike `eof_err` this does not record
+        // the unclosed braces for the recovery of the parser.
+        let mut err = self.unclosed_at_eof_err();
+        err.multipart_suggestion_verbose(
+            format!("add the missing closing brace{}", pluralize!(closed.len())),
+            suggestion,
+            Applicability::MachineApplicable,
+        );
+        err.emit();
+
+        self.open_braces.clear();
+        Some(TokenStream::new(buf))
+    }
+
+    /// Whether the first line of the contents of each brace of `tokens` that ends its line is
+    /// more indented than the line of the brace, which is not the case for code that is not
+    /// indented at all, for example.
+    fn is_consistently_indented(&self, tokens: &[FlatToken]) -> bool {
+        tokens.windows(2).all(|pair| match pair {
+            [FlatToken::Open(Delimiter::Brace, open), next]
+                if self.starts_line(next.span().lo()) =>
+            {
+                matches!(next, FlatToken::Close(..))
+                    || self.indentation(next.span().lo()).len() > self.indentation(open.lo()).len()
+            }
+            _ => true,
+        })
+    }
+
+    /// Guesses where the unclosed brace `tokens[open]` was meant to be closed: before the
+    /// first line after it that is not more indented than the line of the brace, or before a
+    /// closing brace that is less indented than the line of the brace it closes, which likely
+    /// means that the missing closing brace belongs there. Returns the index of the token the
+    /// closing brace is inserted before, or `None` if the brace does not end its line.
+    fn guess_close(&self, tokens: &[FlatToken], open: usize) -> Option<usize> {
+        let margin = self.indentation(tokens[open].span().lo()).len();
+        // The delimiters opened after the brace, with the indentation of their line.
+        let mut open_delims: Vec<(Delimiter, usize)> = Vec::new();
+        for (i, token) in tokens.iter().enumerate().skip(open + 1) {
+            let lo = token.span().lo();
+            let starts_line = self.starts_line(lo);
+            if i == open + 1 && !starts_line {
+                return None;
+            }
+            // Closing braces can only be inserted where they would not close other delimiters.
+            if starts_line && open_delims.iter().all(|&(delim, _)| delim == Delimiter::Brace) {
+                let indentation = self.indentation(lo).len();
+                match *token {
+                    _ if indentation <= margin => return Some(i),
+                    FlatToken::Close(Delimiter::Brace, _)
+                        if open_delims.last().map_or(false, |&(_, m)| indentation < m) =>
+                    {
+                        return Some(i);
+                    }
+                    _ => {}
+                }
+            }
+            match *token {
+                FlatToken::Open(delim, span) => {
+                    open_delims.push((delim, self.indentation(span.lo()).len()))
+                }
+                FlatToken::Close(..) => {
+                    open_delims.pop();
+                }
+                FlatToken::Token(..) => {}
+            }
+        }
+        Some(tokens.len())
+    }
+
+    /// Returns the position of the start of the line of `pos`.
+    fn line_start(&self, pos: BytePos) -> BytePos {
+        let before = self.string_reader.str_from_to(self.string_reader.start_pos, pos);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        self.string_reader.start_pos + BytePos::from_usize(line_start)
+    }
+
+    /// Whether only whitespace precedes `pos` on its line.
+    fn starts_line(&self, pos: BytePos) -> bool {
+        self.string_reader.str_from_to(self.line_start(pos), pos).trim().is_empty()
+    }
+
+    /// Returns the indentation of the line of `pos`.
+    fn indentation(&self, pos: BytePos) -> &'a str {
+        let src = self.string_reader.src;
+        let line = &src[self.string_reader.src_index(self.line_start(pos))..];
+        &line[..line.find(|c: char| c != ' ' && c != '\t').unwrap_or(line.len())]
+    }
+
     fn eof_err(&mut self) -> PErr<'a> {
-        let msg = "this file contains an unclosed delimiter";
-        let mut err = self.string_reader.sess.span_diagnostic.struct_span_err(self.token.span, msg);
         for &(_, sp) in &self.open_braces {
-            err.span_label(sp, "unclosed delimiter");
             self.unmatched_braces.push(UnmatchedBrace {
                 expected_delim: Delimiter::Brace,
                 found_delim: None,
@@ -102,6 +304,15 @@ fn eof_err(&mut self) -> PErr<'a> {
                 candidate_span: None,
             });
         }
+        self.unclosed_at_eof_err()
+    }
+
+    fn unclosed_at_eof_err(&self) -> PErr<'a> {
+        let msg = "this file contains an unclosed delimiter";
+        let mut err = self.string_reader.sess.span_diagnostic.struct_span_err(self.token.span, msg);
+        for &(_, sp) in &self.open_braces {
+            err.span_label(sp, "unclosed delimiter");
+        }
 
         if let Some((delim, _)) = self.open_braces.last() {
             if let Some((_, open_sp, close_sp)) =
@@ -218,8 +429,8 @@ fn parse_token_tree_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
                 }
             }
             token::Eof => {
-                // Silently recover, the EOF token will be seen again
-                // and an error emitted then. Thus we don't pop from
+                // Silently recover, an error is emitted once the whole
+                // file has been read. Thus we don't pop from
                 // self.open_braces here.
             }
             _ => unreachable!(),
@@ -256,3 +467,43 @@ fn close_delim_err(&mut self, delim: Delimiter) -> PErr<'a> {
         err
     }
 }
+
+/// A token of the token trees of a file, with the delimiters of the token trees as tokens of
+/// their own, used to rebuild the token trees when recovering from unclosed braces.
+enum FlatToken {
+    Token(Token, Spacing),
+    Open(Delimiter, Span),
+    Close(Delimiter, Span),
+}
+
+impl FlatToken {
+    fn span(&self) -> Span {
+        match *self {
+            FlatToken::Token(ref token, _) => token.span,
+            FlatToken::Open(_, span) | FlatToken::Close(_, span) => span,
+        }
+    }
+}
+
+/// Flattens `stream` into `tokens`. The delimiters opened at `unclosed` were closed by the end
+/// of the file, so they have no closing token.
+fn flatten_token_trees(
+    stream: &TokenStream,
+    unclosed: &[(Delimiter, Span)],
+    tokens: &mut Vec<FlatToken>,
+) {
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Token(token, spacing) => {
+                tokens.push(FlatToken::Token(token.clone(), *spacing))
+            }
+            &TokenTree::Delimited(span, delim, ref tts) => {
+                tokens.push(FlatToken::Open(delim, span.open));
+                flatten_token_trees(tts, unclosed, tokens);
+                if !unclosed.iter().any(|&(_, sp)| sp == span.open) {
+                    tokens.push(FlatToken::Close(delim, span.close));
+                }
+            }
+        }
+    }
+}
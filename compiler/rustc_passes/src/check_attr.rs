@@ -135,8 +135,8 @@ fn check_attributes(
                 | sym::rustc_dirty
                 | sym::rustc_if_this_changed
                 | sym::rustc_then_this_would_need => self.check_rustc_dirty_clean(&attr),
-                sym::cmse_nonsecure_entry => {
-                    self.check_cmse_nonsecure_entry(hir_id, attr, span, target)
+                sym::cmse_nonsecure_entry | sym::no_panic => {
+                    self.check_fn_definition_attr(hir_id, attr, span, target)
                 }
                 sym::collapse_debuginfo => self.check_collapse_debuginfo(attr, span, target),
                 sym::const_trait => self.check_const_trait(attr, span, target),
@@ -395,8 +395,8 @@ fn check_naked(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Targe
         }
     }
 
-    /// Checks if `#[cmse_nonsecure_entry]` is applied to a function definition.
-    fn check_cmse_nonsecure_entry(
+    /// Checks if `#[cmse_nonsecure_entry]` or `#[no_panic]` is applied to a function definition.
+    fn check_fn_definition_attr(
         &self,
         hir_id: HirId,
         attr: &Attribute,
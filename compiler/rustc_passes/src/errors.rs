@@ -1508,3 +1508,10 @@ pub struct ChangeFieldsToBeOfUnitType {
     #[suggestion_part(code = "()")]
     pub spans: Vec<Span>,
 }
+
+#[derive(Diagnostic)]
+#[diag(passes_item_deps_write_error)]
+pub struct ItemDepsWriteError<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
@@ -0,0 +1,293 @@
+//! Item-level dependency graph, as dumped by `-Z dump-item-deps=<path>`.
+//!
+//! For every item with a body (functions, methods, constants and statics),
+//! this records which other items the body and signature refer to, using the
+//! typeck results of the body. Closures, generators and anonymous constants
+//! are attributed to the item they are defined in.
+//!
+//! Nodes are keyed by their `DefPathHash`, which is stable across compilation
+//! sessions, so graphs of different crates can be joined by external tools.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::subst::{GenericArgKind, SubstsRef};
+use rustc_middle::ty::{self, DefIdTree, Ty, TyCtxt};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::errors::ItemDepsWriteError;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum EdgeKind {
+    /// A function or method is called, statically or through a trait.
+    Call,
+    /// A function or constant is used without being called.
+    Reference,
+    /// A static is read, written or borrowed.
+    Static,
+    /// A struct, enum, union, foreign type or trait object type is used.
+    Type,
+    /// An impl is selected to resolve a call to a trait method.
+    TraitImpl,
+}
+
+#[derive(Serialize)]
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Serialize)]
+struct Node {
+    id: String,
+    path: String,
+    kind: &'static str,
+    local: bool,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct Edge {
+    from: String,
+    to: String,
+    kind: EdgeKind,
+}
+
+pub fn dump_item_deps(tcx: TyCtxt<'_>) {
+    let Some(path) = &tcx.sess.opts.unstable_opts.dump_item_deps else { return };
+
+    let mut edges = FxHashSet::default();
+    let mut sources = FxHashSet::default();
+    for def_id in tcx.hir().body_owners() {
+        // Closures and inline constants are visited with the body that is
+        // type-checked together with them.
+        if tcx.typeck_root_def_id(def_id.to_def_id()) != def_id.to_def_id() {
+            continue;
+        }
+        let from = enclosing_item(tcx, def_id);
+        sources.insert(from);
+
+        let mut collector = DepCollector {
+            tcx,
+            typeck_results: tcx.typeck(def_id),
+            param_env: tcx.param_env(def_id),
+            from,
+            callee: None,
+            edges: &mut edges,
+        };
+        match tcx.def_kind(def_id) {
+            DefKind::Fn | DefKind::AssocFn => {
+                let sig = tcx.fn_sig(def_id).skip_binder();
+                for &ty in sig.inputs_and_output {
+                    collector.record_ty(ty);
+                }
+            }
+            DefKind::Const | DefKind::AssocConst | DefKind::Static(_) => {
+                collector.record_ty(tcx.type_of(def_id));
+            }
+            _ => {}
+        }
+        collector.visit_body(tcx.hir().body(tcx.hir().body_owned_by(def_id)));
+    }
+
+    let mut nodes = BTreeMap::default();
+    let mut add_node = |def_id: DefId| {
+        let id = tcx.def_path_hash(def_id).0.to_hex();
+        nodes.entry(id.clone()).or_insert_with(|| Node {
+            id: id.clone(),
+            path: format!(
+                "{}{}",
+                tcx.crate_name(def_id.krate),
+                tcx.def_path(def_id).to_string_no_crate_verbose()
+            ),
+            kind: tcx.def_kind(def_id).descr(def_id),
+            local: def_id.is_local(),
+        });
+        id
+    };
+    for &from in &sources {
+        add_node(from);
+    }
+    let mut edges: Vec<_> = edges
+        .into_iter()
+        .map(|(from, to, kind)| Edge { from: add_node(from), to: add_node(to), kind })
+        .collect();
+    edges.sort_unstable();
+
+    let graph = Graph { nodes: nodes.into_values().collect(), edges };
+    let result = serde_json::to_string_pretty(&graph)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(path, json + "\n"));
+    if let Err(error) = result {
+        tcx.sess.emit_err(ItemDepsWriteError { path, error });
+    }
+}
+
+/// Returns the item that the uses in the body of `def_id` are attributed to,
+/// skipping closures, generators and anonymous constants.
+fn enclosing_item(tcx: TyCtxt<'_>, mut def_id: LocalDefId) -> DefId {
+    while let DefKind::Closure | DefKind::Generator | DefKind::AnonConst | DefKind::InlineConst =
+        tcx.def_kind(def_id)
+    {
+        def_id = tcx.local_parent(def_id);
+    }
+    def_id.to_def_id()
+}
+
+/// Returns the item that `def_id` is part of, going from constructors and
+/// variants to the struct or enum they belong to.
+fn target_item(tcx: TyCtxt<'_>, mut def_id: DefId) -> DefId {
+    while let DefKind::Ctor(..) | DefKind::Variant = tcx.def_kind(def_id) {
+        def_id = tcx.parent(def_id);
+    }
+    def_id
+}
+
+struct DepCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typeck_results: &'tcx ty::TypeckResults<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    from: DefId,
+    /// The path callee of the call expression being visited, which is
+    /// recorded as a call rather than as a reference. Cleared once the path
+    /// is visited.
+    callee: Option<hir::HirId>,
+    edges: &'a mut FxHashSet<(DefId, DefId, EdgeKind)>,
+}
+
+impl<'tcx> DepCollector<'_, 'tcx> {
+    fn record(&mut self, to: DefId, kind: EdgeKind) {
+        let to = target_item(self.tcx, to);
+        if to != self.from {
+            self.edges.insert((self.from, to, kind));
+        }
+    }
+
+    fn record_res(&mut self, res: Res) {
+        let Res::Def(def_kind, def_id) = res else { return };
+        match def_kind {
+            DefKind::Fn | DefKind::AssocFn | DefKind::Const | DefKind::AssocConst => {
+                self.record(def_id, EdgeKind::Reference)
+            }
+            DefKind::Static(_) => self.record(def_id, EdgeKind::Static),
+            DefKind::Struct
+            | DefKind::Union
+            | DefKind::Enum
+            | DefKind::Variant
+            | DefKind::Ctor(..)
+            | DefKind::TyAlias
+            | DefKind::ForeignTy => self.record(def_id, EdgeKind::Type),
+            _ => {}
+        }
+    }
+
+    fn record_call(&mut self, def_id: DefId, substs: SubstsRef<'tcx>) {
+        if let DefKind::Ctor(..) = self.tcx.def_kind(def_id) {
+            self.record(def_id, EdgeKind::Type);
+            return;
+        }
+        self.record(def_id, EdgeKind::Call);
+
+        // For calls to trait methods, also record the impl that is used, if
+        // it is already known in the generic context of the body.
+        if self.tcx.trait_of_item(def_id).is_none() {
+            return;
+        }
+        let Ok(Some(instance)) = ty::Instance::resolve(self.tcx, self.param_env, def_id, substs)
+        else {
+            return;
+        };
+        let callee = instance.def_id();
+        if let Some(impl_def_id) = self.tcx.impl_of_method(callee) {
+            self.record(callee, EdgeKind::Call);
+            self.record(impl_def_id, EdgeKind::TraitImpl);
+        }
+    }
+
+    fn record_ty(&mut self, ty: Ty<'tcx>) {
+        for arg in ty.walk() {
+            let GenericArgKind::Type(ty) = arg.unpack() else { continue };
+            match *ty.kind() {
+                ty::Adt(adt_def, _) => self.record(adt_def.did(), EdgeKind::Type),
+                ty::Foreign(def_id) => self.record(def_id, EdgeKind::Type),
+                ty::Dynamic(predicates, ..) => {
+                    if let Some(def_id) = predicates.principal_def_id() {
+                        self.record(def_id, EdgeKind::Type);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for DepCollector<'_, 'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_anon_const(&mut self, c: &'tcx hir::AnonConst) {
+        // Only inline constants share the typeck results of their parent,
+        // other anonymous constants are visited as bodies of their own.
+        if let DefKind::InlineConst = self.tcx.def_kind(self.tcx.hir().local_def_id(c.hir_id)) {
+            intravisit::walk_anon_const(self, c);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        match expr.kind {
+            hir::ExprKind::Path(ref qpath) => {
+                // Callees have already been recorded as calls.
+                if self.callee == Some(expr.hir_id) {
+                    self.callee = None;
+                } else {
+                    self.record_res(self.typeck_results.qpath_res(qpath, expr.hir_id));
+                }
+            }
+            hir::ExprKind::Struct(ref qpath, ..) => {
+                self.record_res(self.typeck_results.qpath_res(qpath, expr.hir_id));
+            }
+            hir::ExprKind::Call(callee, _) => {
+                if let ty::FnDef(def_id, substs) = *self.typeck_results.expr_ty(callee).kind() {
+                    self.record_call(def_id, substs);
+                    // A path callee is visited right after the call, and
+                    // clears the field again.
+                    if let hir::ExprKind::Path(_) = callee.kind {
+                        self.callee = Some(callee.hir_id);
+                    }
+                }
+            }
+            _ => {
+                // Method calls and overloaded operators.
+                if let Some((DefKind::AssocFn, def_id)) =
+                    self.typeck_results.type_dependent_def(expr.hir_id)
+                {
+                    self.record_call(def_id, self.typeck_results.node_substs(expr.hir_id));
+                }
+            }
+        }
+        if let Some(ty) = self.typeck_results.expr_ty_opt(expr) {
+            self.record_ty(ty);
+        }
+
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        if let hir::PatKind::Path(ref qpath) = pat.kind {
+            self.record_res(self.typeck_results.qpath_res(qpath, pat.hir_id));
+        }
+        if let Some(ty) = self.typeck_results.node_type_opt(pat.hir_id) {
+            self.record_ty(ty);
+        }
+
+        intravisit::walk_pat(self, pat);
+    }
+}
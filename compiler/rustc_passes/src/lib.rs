@@ -29,6 +29,7 @@
 mod errors;
 pub mod hir_id_validator;
 pub mod hir_stats;
+pub mod item_deps;
 mod lang_items;
 pub mod layout_test;
 mod lib_features;
@@ -1,7 +1,8 @@
+use crate::keys::Key;
 use crate::QueryCtxt;
 use measureme::{StringComponent, StringId};
 use rustc_data_structures::fx::FxHashMap;
-use rustc_data_structures::profiling::SelfProfiler;
+use rustc_data_structures::profiling::{ItemPhase, QueryInvocationId, SelfProfiler};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LocalDefId, LOCAL_CRATE};
 use rustc_hir::definitions::DefPathData;
 use rustc_middle::ty::{TyCtxt, WithOptConstParam};
@@ -218,6 +219,17 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
     tcx.prof.with_profiler(|profiler| {
         let event_id_builder = profiler.event_id_builder();
 
+        // The summary records query events by invocation id, so tell it which
+        // invocations belong to this query.
+        if profiler.summary_enabled() {
+            let mut query_invocation_ids = Vec::new();
+            query_cache.iter(&mut |_, _, i| query_invocation_ids.push(i.into()));
+            profiler.attribute_query_invocations_to_summary(
+                query_name,
+                query_invocation_ids.into_iter(),
+            );
+        }
+
         // Walk the entire query cache and allocate the appropriate
         // string representations. Each cache entry is uniquely
         // identified by its dep_node_index.
@@ -271,6 +283,49 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
     });
 }
 
+/// The invocations of the query of `query_cache` that are keyed by an item,
+/// with the `DefId` of that item.
+fn item_query_invocations<C>(query_cache: &C) -> Vec<(DefId, QueryInvocationId)>
+where
+    C: QueryCache,
+    C::Key: Key,
+{
+    let mut invocations = Vec::new();
+    query_cache.iter(&mut |k, _, i| {
+        if let Some(def_id) = k.key_as_def_id() {
+            invocations.push((def_id, i.into()));
+        }
+    });
+    invocations
+}
+
+/// Attributes the time spent in the queries that `-Z time-by-item` splits
+/// by item to the items they are keyed by.
+fn alloc_item_query_invocations(tcx: TyCtxt<'_>) {
+    tcx.prof.with_profiler(|profiler| {
+        if !profiler.item_times_enabled() {
+            return;
+        }
+
+        // Computing the name and location of an item might invoke queries. If
+        // their caches were walked already, their invocations would be left
+        // without a string, so the keys of all these queries are collected
+        // first, and resolved before any query strings are allocated.
+        let caches = &tcx.query_caches;
+        let invocations = [
+            (ItemPhase::TypeCheck, item_query_invocations(&caches.typeck)),
+            (ItemPhase::BorrowCheck, item_query_invocations(&caches.mir_borrowck)),
+            (ItemPhase::MirOptimization, item_query_invocations(&caches.optimized_mir)),
+        ];
+        for (phase, invocations) in invocations {
+            profiler.attribute_query_invocations_to_items(
+                phase,
+                invocations.into_iter().map(|(def_id, id)| (id, tcx.time_by_item_key(def_id))),
+            );
+        }
+    });
+}
+
 /// All self-profiling events generated by the query engine use
 /// virtual `StringId`s for their `event_id`. This method makes all
 /// those virtual `StringId`s point to actual strings.
@@ -283,6 +338,10 @@ pub fn alloc_self_profile_query_strings<'tcx>(tcx: TyCtxt<'tcx>) {
         return;
     }
 
+    // This has to happen before the invocations are attributed to their
+    // queries in the summary.
+    alloc_item_query_invocations(tcx);
+
     let mut string_cache = QueryKeyStringCache::new();
     let queries = QueryCtxt::from_tcx(tcx);
 
@@ -242,6 +242,10 @@ pub(crate) fn check_unused(&mut self, krate: &ast::Crate) {
                                 instead",
                             );
                         }
+                        if import.used.get() {
+                            let extern_crate = self.local_def_id(import.root_id);
+                            self.crate_loader.process_macro_use_extern(extern_crate, import.span);
+                        }
                     }
                 }
                 ImportKind::ExternCrate { id, .. } => {
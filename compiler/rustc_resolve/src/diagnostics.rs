@@ -1,10 +1,10 @@
-use std::ptr;
+use std::{mem, ptr};
 
 use rustc_ast::ptr::P;
 use rustc_ast::visit::{self, Visitor};
 use rustc_ast::{self as ast, Crate, ItemKind, ModKind, NodeId, Path, CRATE_NODE_ID};
 use rustc_ast_pretty::pprust;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use rustc_errors::struct_span_err;
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, ErrorGuaranteed, MultiSpan};
 use rustc_feature::BUILTIN_ATTRIBUTES;
@@ -94,6 +94,8 @@ pub(crate) struct ImportSuggestion {
     pub descr: &'static str,
     pub path: Path,
     pub accessible: bool,
+    /// Whether the item, or one of the modules of its path, is `#[doc(hidden)]`.
+    pub doc_hidden: bool,
     /// An extra note that should be issued if this item is suggested
     pub note: Option<String>,
 }
@@ -139,26 +141,75 @@ pub(crate) fn report_errors(&mut self, krate: &Crate) {
     }
 
     fn report_with_use_injections(&mut self, krate: &Crate) {
-        for UseError { mut err, candidates, def_id, instead, suggestion, path, is_call } in
-            self.use_injections.drain(..)
-        {
-            let (span, found_use) = if let Some(def_id) = def_id.as_local() {
-                UsePlacementFinder::check(krate, self.def_id_to_node_id[def_id])
-            } else {
-                (None, FoundUse::No)
-            };
+        let use_errors = mem::take(&mut self.use_injections);
+
+        let mut placements = FxIndexMap::default();
+        for use_error in &use_errors {
+            placements.entry(use_error.def_id).or_insert_with(|| {
+                if let Some(def_id) = use_error.def_id.as_local() {
+                    UsePlacementFinder::check(krate, self.def_id_to_node_id[def_id])
+                } else {
+                    UsePlacement { span: None, found_use: FoundUse::No, use_trees: Vec::new() }
+                }
+            });
+        }
+
+        // The import that fixes each error, if there is no doubt about which candidate to
+        // import. Importing a candidate does not fix a path with several segments, and an
+        // error about a name that resolved to something else is only a guess.
+        let imports: Vec<_> = use_errors
+            .iter()
+            .map(|use_error| {
+                if use_error.instead || use_error.path.len() != 1 {
+                    return None;
+                }
+                let candidate = preferred_candidate(&use_error.candidates)?;
+                Some(path_names_to_string(&candidate.path))
+            })
+            .collect();
+        // The edits adding the imports of a module are computed together, so that the
+        // errors whose imports are merged into the same `use` item suggest the same edit.
+        let mut edits = FxHashMap::default();
+        for (&def_id, placement) in &placements {
+            let paths: Vec<&str> = use_errors
+                .iter()
+                .zip(&imports)
+                .filter(|(use_error, _)| use_error.def_id == def_id)
+                .filter_map(|(_, import)| import.as_deref())
+                .collect();
+            let module_edits = import_edits(self.session.source_map(), placement, &paths);
+            edits.extend(module_edits.into_iter().map(|(path, edit)| ((def_id, path), edit)));
+        }
+
+        let mut fixed_imports = FxHashSet::default();
+        for (use_error, import) in use_errors.into_iter().zip(imports) {
+            let UseError { mut err, candidates, def_id, instead, suggestion, path, is_call } =
+                use_error;
+            let placement = &placements[&def_id];
+            let fix = import.and_then(|import| {
+                let (span, snippet) = edits.get(&(def_id, import.clone()))?.clone();
+                // Another error of the module may need the same import, only one of them can
+                // add it.
+                let applicability = if fixed_imports.insert((def_id, import.clone())) {
+                    Applicability::MachineApplicable
+                } else {
+                    Applicability::MaybeIncorrect
+                };
+                Some(ImportFix { path: import, span, snippet, applicability })
+            });
 
             if !candidates.is_empty() {
                 show_candidates(
                     &self.session,
                     &self.source_span,
                     &mut err,
-                    span,
+                    placement.span,
                     &candidates,
                     if instead { Instead::Yes } else { Instead::No },
-                    found_use,
+                    placement.found_use,
                     DiagnosticMode::Normal,
                     path,
+                    fix,
                 );
                 err.emit();
             } else if let Some((span, msg, sugg, appl)) = suggestion {
@@ -725,6 +776,7 @@ pub(crate) fn into_struct_error(
                         FoundUse::Yes,
                         DiagnosticMode::Pattern,
                         vec![],
+                        None,
                     );
                 }
                 err
@@ -1295,10 +1347,10 @@ fn lookup_import_candidates_from_module<FilterFn>(
     {
         let mut candidates = Vec::new();
         let mut seen_modules = FxHashSet::default();
-        let mut worklist = vec![(start_module, Vec::<ast::PathSegment>::new(), true)];
+        let mut worklist = vec![(start_module, Vec::<ast::PathSegment>::new(), true, false)];
         let mut worklist_via_import = vec![];
 
-        while let Some((in_module, path_segments, accessible)) = match worklist.pop() {
+        while let Some((in_module, path_segments, accessible, doc_hidden)) = match worklist.pop() {
             None => worklist_via_import.pop(),
             Some(x) => Some(x),
         } {
@@ -1406,6 +1458,8 @@ fn lookup_import_candidates_from_module<FilterFn>(
                                 descr: res.descr(),
                                 path,
                                 accessible: child_accessible,
+                                doc_hidden: doc_hidden
+                                    || did.map_or(false, |did| this.is_doc_hidden(did)),
                                 note,
                             });
                         }
@@ -1424,8 +1478,9 @@ fn lookup_import_candidates_from_module<FilterFn>(
                     if !is_extern_crate_that_also_appears_in_prelude {
                         // add the module to the lookup
                         if seen_modules.insert(module.def_id()) {
+                            let doc_hidden = doc_hidden || this.is_doc_hidden(module.def_id());
                             if via_import { &mut worklist_via_import } else { &mut worklist }
-                                .push((module, path_segments, child_accessible));
+                                .push((module, path_segments, child_accessible, doc_hidden));
                         }
                     }
                 }
@@ -1440,6 +1495,18 @@ fn lookup_import_candidates_from_module<FilterFn>(
         candidates
     }
 
+    /// Whether an item of another crate is hidden from its documentation. The items of the
+    /// current crate are never considered hidden, since they are known to the user anyway.
+    fn is_doc_hidden(&self, def_id: DefId) -> bool {
+        !def_id.is_local()
+            && self.cstore().item_attrs_untracked(def_id, self.session).any(|attr| {
+                attr.has_name(sym::doc)
+                    && attr
+                        .meta_item_list()
+                        .map_or(false, |list| ast::attr::list_contains_name(&list, sym::hidden))
+            })
+    }
+
     /// When name resolution fails, this method can be used to look up candidate
     /// entities with the expected name. It allows filtering them using the
     /// supplied predicate (which should be used to only accept the types of
@@ -1522,6 +1589,7 @@ pub(crate) fn unresolved_macro_suggestions(
             FoundUse::Yes,
             DiagnosticMode::Normal,
             vec![],
+            None,
         );
 
         if macro_kind == MacroKind::Derive && (ident.name == sym::Send || ident.name == sym::Sync) {
@@ -2478,11 +2546,30 @@ enum Instead {
 }
 
 /// Whether an existing place with an `use` item was found.
+#[derive(Clone, Copy)]
 enum FoundUse {
     Yes,
     No,
 }
 
+/// Where to add the imports suggested for a module.
+struct UsePlacement {
+    /// This is `None` if all placement locations are inside expansions.
+    span: Option<Span>,
+    found_use: FoundUse,
+    /// The trees of the `use` items of the module that new imports can be merged into.
+    use_trees: Vec<ast::UseTree>,
+}
+
+/// The edit adding the import of the candidate preferred for an error, computed together
+/// with the imports of the other errors of its module.
+struct ImportFix {
+    path: String,
+    span: Span,
+    snippet: String,
+    applicability: Applicability,
+}
+
 /// Whether a binding is part of a pattern or a use statement. Used for diagnostics.
 enum DiagnosticMode {
     Normal,
@@ -2510,6 +2597,7 @@ pub(crate) fn import_candidates(
         FoundUse::Yes,
         DiagnosticMode::Import,
         vec![],
+        None,
     );
 }
 
@@ -2527,6 +2615,7 @@ fn show_candidates(
     found_use: FoundUse,
     mode: DiagnosticMode,
     path: Vec<Segment>,
+    fix: Option<ImportFix>,
 ) {
     if candidates.is_empty() {
         return;
@@ -2587,19 +2676,38 @@ fn show_candidates(
                 DiagnosticMode::Import => "",
                 DiagnosticMode::Normal => "use ",
             };
-            for candidate in &mut accessible_path_strings {
-                // produce an additional newline to separate the new use statement
-                // from the directly following item.
-                let additional_newline = if let FoundUse::Yes = found_use { "" } else { "\n" };
-                candidate.0 = format!("{}{};\n{}", add_use, &candidate.0, additional_newline);
-            }
+            match fix {
+                // The fix adding the only candidate replaces the suggestion to import it.
+                Some(fix) if accessible_path_strings.len() == 1 => {
+                    err.span_suggestion_verbose(fix.span, &msg, fix.snippet, fix.applicability);
+                }
+                fix => {
+                    for candidate in &mut accessible_path_strings {
+                        // produce an additional newline to separate the new use statement
+                        // from the directly following item.
+                        let additional_newline =
+                            if let FoundUse::Yes = found_use { "" } else { "\n" };
+                        candidate.0 =
+                            format!("{}{};\n{}", add_use, &candidate.0, additional_newline);
+                    }
 
-            err.span_suggestions(
-                span,
-                &msg,
-                accessible_path_strings.into_iter().map(|a| a.0),
-                Applicability::MaybeIncorrect,
-            );
+                    err.span_suggestions(
+                        span,
+                        &msg,
+                        accessible_path_strings.into_iter().map(|a| a.0),
+                        Applicability::MaybeIncorrect,
+                    );
+                    // Tools can still add the preferred candidate.
+                    if let Some(fix) = fix {
+                        err.tool_only_span_suggestion(
+                            fix.span,
+                            &format!("consider importing `{}`", fix.path),
+                            fix.snippet,
+                            fix.applicability,
+                        );
+                    }
+                }
+            }
             if let [first, .., last] = &path[..] {
                 let sp = first.ident.span.until(last.ident.span);
                 if sp.can_be_used_for_suggestions() {
@@ -2703,17 +2811,23 @@ struct UsePlacementFinder {
     target_module: NodeId,
     first_legal_span: Option<Span>,
     first_use_span: Option<Span>,
+    use_trees: Vec<ast::UseTree>,
 }
 
 impl UsePlacementFinder {
-    fn check(krate: &Crate, target_module: NodeId) -> (Option<Span>, FoundUse) {
-        let mut finder =
-            UsePlacementFinder { target_module, first_legal_span: None, first_use_span: None };
+    fn check(krate: &Crate, target_module: NodeId) -> UsePlacement {
+        let mut finder = UsePlacementFinder {
+            target_module,
+            first_legal_span: None,
+            first_use_span: None,
+            use_trees: Vec::new(),
+        };
         finder.visit_crate(krate);
+        let use_trees = finder.use_trees;
         if let Some(use_span) = finder.first_use_span {
-            (Some(use_span), FoundUse::Yes)
+            UsePlacement { span: Some(use_span), found_use: FoundUse::Yes, use_trees }
         } else {
-            (finder.first_legal_span, FoundUse::No)
+            UsePlacement { span: finder.first_legal_span, found_use: FoundUse::No, use_trees }
         }
     }
 }
@@ -2726,6 +2840,7 @@ fn visit_crate(&mut self, c: &Crate) {
                 self.first_legal_span = Some(inject);
             }
             self.first_use_span = search_for_any_use_in_items(&c.items);
+            self.use_trees = mergeable_use_trees(&c.items);
             return;
         } else {
             visit::walk_crate(self, c);
@@ -2740,6 +2855,7 @@ fn visit_item(&mut self, item: &'tcx ast::Item) {
                     self.first_legal_span = Some(inject);
                 }
                 self.first_use_span = search_for_any_use_in_items(items);
+                self.use_trees = mergeable_use_trees(items);
                 return;
             }
         } else {
@@ -2759,12 +2875,132 @@ fn search_for_any_use_in_items(items: &[P<ast::Item>]) -> Option<Span> {
     return None;
 }
 
+/// Returns the trees of the `use` items of a module that new imports can be merged into. The
+/// imports merged into an item get its visibility and attributes, so only the private items
+/// without attributes are considered.
+fn mergeable_use_trees(items: &[P<ast::Item>]) -> Vec<ast::UseTree> {
+    items
+        .iter()
+        .filter_map(|item| match &item.kind {
+            ItemKind::Use(tree)
+                if item.attrs.is_empty()
+                    && matches!(item.vis.kind, ast::VisibilityKind::Inherited)
+                    && is_span_suitable_for_use_injection(item.span) =>
+            {
+                Some(tree.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 fn is_span_suitable_for_use_injection(s: Span) -> bool {
     // don't suggest placing a use before the prelude
     // import or other generated ones
     !s.from_expansion()
 }
 
+/// Returns the candidate to import among `candidates`, if ranking them leaves no doubt. The
+/// items of the current crate come first, then the items that are not hidden from the
+/// documentation, then the ones with the shortest path, so that the shortest public
+/// re-export of an item is preferred. A path in `core` comes after the same path in `std`.
+fn preferred_candidate(candidates: &[ImportSuggestion]) -> Option<&ImportSuggestion> {
+    let rank = |candidate: &ImportSuggestion| {
+        let is_local = candidate.did.map_or(false, |did| did.is_local());
+        let segments = &candidate.path.segments;
+        let is_core = segments.first().map_or(false, |segment| segment.ident.name == sym::core);
+        (!is_local, candidate.doc_hidden, segments.len(), is_core)
+    };
+    let accessible = candidates.iter().filter(|candidate| candidate.accessible);
+    let best = accessible.clone().min_by_key(|candidate| rank(candidate))?;
+    let mut best_ranked = accessible.filter(|candidate| rank(candidate) == rank(best));
+    best_ranked.next();
+    best_ranked.next().is_none().then_some(best)
+}
+
+/// Computes the edits adding the imports of `paths` to a module, keyed by path. An import is
+/// merged into the first `use` item of the module that imports from the same module, and is
+/// added as a new `use` item otherwise. The imports merged into the same `use` item share the
+/// edit rewriting it.
+fn import_edits(
+    source_map: &SourceMap,
+    placement: &UsePlacement,
+    paths: &[&str],
+) -> FxHashMap<String, (Span, String)> {
+    let mut paths = paths.to_vec();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let mut edits = FxHashMap::default();
+    for tree in &placement.use_trees {
+        let Some(parent) = use_tree_parent(tree) else { continue };
+        let (merged_paths, names): (Vec<_>, Vec<_>) = paths
+            .iter()
+            .filter(|path| !edits.contains_key(**path))
+            .filter_map(|path| match path.rsplit_once("::") {
+                Some((prefix, name)) if prefix == parent => Some((*path, name)),
+                _ => None,
+            })
+            .unzip();
+        if names.is_empty() {
+            continue;
+        }
+        let Some(snippet) = merge_use_tree(source_map, tree, &names) else { continue };
+        for path in merged_paths {
+            edits.insert(path.to_string(), (tree.span, snippet.clone()));
+        }
+    }
+
+    if let Some(span) = placement.span {
+        // produce an additional newline to separate the new use statement
+        // from the directly following item.
+        let additional_newline = if let FoundUse::Yes = placement.found_use { "" } else { "\n" };
+        for path in paths {
+            if !edits.contains_key(path) {
+                let snippet = format!("use {path};\n{additional_newline}");
+                edits.insert(path.to_string(), (span, snippet));
+            }
+        }
+    }
+    edits
+}
+
+/// Returns the path of the module that a `use` tree imports from, if it imports items from a
+/// single module.
+fn use_tree_parent(tree: &ast::UseTree) -> Option<String> {
+    let segments = match tree.kind {
+        ast::UseTreeKind::Simple(..) => tree.prefix.segments.split_last()?.1,
+        ast::UseTreeKind::Nested(_) => &tree.prefix.segments[..],
+        ast::UseTreeKind::Glob => return None,
+    };
+    if segments.is_empty() {
+        return None;
+    }
+    Some(segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>().join("::"))
+}
+
+/// Rewrites a `use` tree to also import `names` from the module it imports from, e.g.
+/// `std::fmt::Debug` to `std::fmt::{Debug, Display}`.
+fn merge_use_tree(source_map: &SourceMap, tree: &ast::UseTree, names: &[&str]) -> Option<String> {
+    let names = names.join(", ");
+    match tree.kind {
+        ast::UseTreeKind::Simple(..) => {
+            let last = tree.prefix.segments.last()?.ident.span;
+            let parent = source_map.span_to_snippet(tree.span.until(last)).ok()?;
+            let imported = source_map.span_to_snippet(tree.span.with_lo(last.lo())).ok()?;
+            Some(format!("{parent}{{{imported}, {names}}}"))
+        }
+        ast::UseTreeKind::Nested(ref trees) => {
+            let snippet = source_map.span_to_snippet(tree.span).ok()?;
+            let list = snippet.strip_suffix('}')?.trim_end();
+            let list = list.strip_suffix(',').unwrap_or(list);
+            let separator = if trees.is_empty() { "" } else { ", " };
+            Some(format!("{list}{separator}{names}}}"))
+        }
+        ast::UseTreeKind::Glob => None,
+    }
+}
+
 /// Convert the given number into the corresponding ordinal
 pub(crate) fn ordinalize(v: usize) -> String {
     let suffix = match ((11..=13).contains(&(v % 100)), v % 10) {
@@ -1861,6 +1861,7 @@ fn find_module(&mut self, def_id: DefId) -> Option<(Module<'a>, ImportSuggestion
                                 descr: "module",
                                 path,
                                 accessible: true,
+                                doc_hidden: false,
                                 note: None,
                             },
                         ));
@@ -1690,6 +1690,7 @@ fn record_use(
             });
         }
         if let NameBindingKind::Import { import, binding, ref used } = used_binding.kind {
+            self.note_extern_crate_use(ident, used_binding);
             // Avoid marking `extern crate` items that refer to a name from extern prelude,
             // but not introduce it, as used if they are accessed from lexical scope.
             if is_lexical_scope {
@@ -1711,6 +1712,17 @@ fn record_use(
         }
     }
 
+    /// Notes the use of the crate loaded by `binding` at `ident` for `-Z extern-usage-report`, if
+    /// `binding` is an `extern crate` item, whether the item itself is marked as used or not.
+    fn note_extern_crate_use(&mut self, ident: Ident, binding: &NameBinding<'_>) {
+        if let NameBindingKind::Import { import, .. } = binding.kind
+            && let ImportKind::ExternCrate { id, .. } = import.kind
+        {
+            let extern_crate = self.local_def_id(id);
+            self.crate_loader.process_extern_crate_use(extern_crate, ident.span);
+        }
+    }
+
     #[inline]
     fn add_to_glob_map(&mut self, import: &Import<'_>, ident: Ident) {
         if let ImportKind::Glob { id, .. } = import.kind {
@@ -1860,6 +1872,8 @@ fn extern_prelude_get(&mut self, ident: Ident, finalize: bool) -> Option<&'a Nam
             if let Some(binding) = entry.extern_crate_item {
                 if finalize && entry.introduced_by_item {
                     self.record_use(ident, binding, false);
+                } else if finalize {
+                    self.note_extern_crate_use(ident, binding);
                 }
                 Some(binding)
             } else {
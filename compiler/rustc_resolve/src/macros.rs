@@ -683,13 +683,17 @@ pub(crate) fn finalize_macro_resolutions(&mut self) {
             for seg in &mut path {
                 seg.id = None;
             }
-            match self.resolve_path(
+            let path_res = self.resolve_path(
                 &path,
                 Some(MacroNS),
                 &parent_scope,
                 Some(Finalize::new(ast::CRATE_NODE_ID, path_span)),
                 None,
-            ) {
+            );
+            if let Some(root) = path.first() {
+                self.crate_loader.process_macro_path_extern(root.ident.span);
+            }
+            match path_res {
                 PathResult::NonModule(path_res) if let Some(res) = path_res.full_res() => {
                     check_consistency(self, &path, path_span, kind, initial_res, res)
                 }
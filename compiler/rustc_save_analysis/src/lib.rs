@@ -14,6 +14,7 @@
 #[macro_use]
 mod span_utils;
 mod errors;
+mod lsif;
 mod sig;
 
 use rustc_ast as ast;
@@ -29,7 +30,7 @@
 use rustc_middle::middle::privacy::EffectiveVisibilities;
 use rustc_middle::ty::{self, print::with_no_trimmed_paths, DefIdTree, TyCtxt};
 use rustc_middle::{bug, span_bug};
-use rustc_session::config::{CrateType, Input, OutputType};
+use rustc_session::config::{CrateType, Input, OutputType, SaveAnalysisFormat};
 use rustc_session::cstore::ExternCrate;
 use rustc_session::output::{filename_for_metadata, out_filename};
 use rustc_span::symbol::Ident;
@@ -914,7 +915,8 @@ fn output_file(&self, ctx: &SaveContext<'_>) -> (BufWriter<File>, PathBuf) {
                 let mut out_name = if executable { String::new() } else { "lib".to_owned() };
                 out_name.push_str(&self.cratename);
                 out_name.push_str(&sess.opts.cg.extra_filename);
-                out_name.push_str(".json");
+                out_name.push('.');
+                out_name.push_str(sess.opts.unstable_opts.save_analysis_format.extension());
                 root_path.push(&out_name);
 
                 root_path
@@ -935,8 +937,17 @@ impl SaveHandler for DumpHandler<'_> {
     fn save(&mut self, save_ctxt: &SaveContext<'_>, analysis: &Analysis) {
         let sess = &save_ctxt.tcx.sess;
         let (output, file_name) = self.output_file(&save_ctxt);
-        if let Err(e) = serde_json::to_writer(output, &analysis) {
-            error!("Can't serialize save-analysis: {:?}", e);
+        match sess.opts.unstable_opts.save_analysis_format {
+            SaveAnalysisFormat::Json => {
+                if let Err(e) = serde_json::to_writer(output, &analysis) {
+                    error!("Can't serialize save-analysis: {:?}", e);
+                }
+            }
+            SaveAnalysisFormat::Lsif => {
+                if let Err(e) = lsif::write_lsif(output, save_ctxt, analysis) {
+                    error!("Can't write LSIF index: {:?}", e);
+                }
+            }
         }
 
         if sess.opts.json_artifact_notifications {
@@ -0,0 +1,318 @@
+//! Conversion of the save-analysis data to the [Language Server Index Format][lsif]
+//! (LSIF), selected with `-Z save-analysis-format=lsif`.
+//!
+//! The index is written as line-delimited JSON. Every definition of the crate
+//! gets a result set that holds its definition, hover text, references and
+//! implementations, and that all ranges referring to it point to. References
+//! to items of other crates are not included, but every item exports a moniker
+//! built from its qualified name, so that indexes of several crates can be
+//! linked together.
+//!
+//! Positions are zero-based lines and character offsets. The offsets count
+//! characters, which matches the UTF-16 encoding announced by the index for
+//! all characters in the Basic Multilingual Plane.
+//!
+//! [lsif]: https://microsoft.github.io/language-server-protocol/specifications/lsif/0.4.0/specification/
+
+use rls_data::{Analysis, Def, Id, RelationKind, SpanData};
+use rustc_data_structures::fx::FxHashMap;
+use serde_json::{json, Value};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::SaveContext;
+
+const LSIF_VERSION: &str = "0.4.3";
+
+type VertexId = u64;
+
+/// A range vertex and the document vertex that contains it.
+type Range = (VertexId, VertexId);
+
+/// The result set of a definition and the ranges that are attached to it.
+struct Symbol {
+    result_set: VertexId,
+    definition: Range,
+    references: Vec<Range>,
+    implementations: Vec<Range>,
+}
+
+struct Emitter<'a, W: Write> {
+    out: W,
+    next_id: VertexId,
+    working_dir: &'a Path,
+    documents: BTreeMap<PathBuf, VertexId>,
+    /// The ranges contained in each document, by the id of the document.
+    ranges: BTreeMap<VertexId, Vec<VertexId>>,
+}
+
+impl<W: Write> Emitter<'_, W> {
+    fn emit(&mut self, mut element: Value) -> io::Result<VertexId> {
+        let id = self.next_id;
+        self.next_id += 1;
+        element["id"] = id.into();
+        serde_json::to_writer(&mut self.out, &element)?;
+        self.out.write_all(b"\n")?;
+        Ok(id)
+    }
+
+    fn vertex(&mut self, label: &str, mut vertex: Value) -> io::Result<VertexId> {
+        vertex["type"] = "vertex".into();
+        vertex["label"] = label.into();
+        self.emit(vertex)
+    }
+
+    fn edge(&mut self, label: &str, out_v: VertexId, in_v: VertexId) -> io::Result<()> {
+        self.emit(json!({ "type": "edge", "label": label, "outV": out_v, "inV": in_v }))?;
+        Ok(())
+    }
+
+    fn item_edge(
+        &mut self,
+        out_v: VertexId,
+        in_vs: &[VertexId],
+        document: VertexId,
+        property: Option<&str>,
+    ) -> io::Result<()> {
+        let mut edge = json!({
+            "type": "edge",
+            "label": "item",
+            "outV": out_v,
+            "inVs": in_vs,
+            "document": document,
+        });
+        if let Some(property) = property {
+            edge["property"] = property.into();
+        }
+        self.emit(edge)?;
+        Ok(())
+    }
+
+    fn document(&mut self, file_name: &Path) -> io::Result<VertexId> {
+        if let Some(&id) = self.documents.get(file_name) {
+            return Ok(id);
+        }
+        let uri = file_uri(&self.working_dir.join(file_name));
+        let id = self.vertex("document", json!({ "uri": uri, "languageId": "rust" }))?;
+        self.documents.insert(file_name.to_owned(), id);
+        Ok(id)
+    }
+
+    fn range(&mut self, span: &SpanData) -> io::Result<Range> {
+        let document = self.document(&span.file_name)?;
+        let range = self.vertex(
+            "range",
+            json!({
+                "start": {
+                    "line": span.line_start.zero_indexed().0,
+                    "character": span.column_start.zero_indexed().0,
+                },
+                "end": {
+                    "line": span.line_end.zero_indexed().0,
+                    "character": span.column_end.zero_indexed().0,
+                },
+            }),
+        )?;
+        self.ranges.entry(document).or_default().push(range);
+        Ok((range, document))
+    }
+
+    /// Emits a result vertex with the given label, and the item edges from it
+    /// to the ranges in `items`, grouped by document and by the property of
+    /// the edges.
+    fn result(
+        &mut self,
+        result_set: VertexId,
+        request: &str,
+        label: &str,
+        items: &[(Option<&str>, &[Range])],
+    ) -> io::Result<()> {
+        let result = self.vertex(label, json!({}))?;
+        self.edge(request, result_set, result)?;
+        for &(property, ranges) in items {
+            let mut by_document = BTreeMap::<VertexId, Vec<VertexId>>::new();
+            for &(range, document) in ranges {
+                by_document.entry(document).or_default().push(range);
+            }
+            for (document, ranges) in by_document {
+                self.item_edge(result, &ranges, document, property)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the LSIF index of the crate described by `analysis` to `out`.
+pub(crate) fn write_lsif<W: Write>(
+    out: W,
+    scx: &SaveContext<'_>,
+    analysis: &Analysis,
+) -> io::Result<()> {
+    let sess = &scx.tcx.sess;
+    let working_dir = sess.opts.working_dir.local_path_if_available();
+    let mut emitter = Emitter {
+        out,
+        next_id: 1,
+        working_dir,
+        documents: BTreeMap::new(),
+        ranges: BTreeMap::new(),
+    };
+    let crate_name = analysis.prelude.as_ref().map_or("", |prelude| &prelude.crate_id.name);
+
+    emitter.vertex(
+        "metaData",
+        json!({
+            "version": LSIF_VERSION,
+            "projectRoot": file_uri(working_dir),
+            "positionEncoding": "utf-16",
+            "toolInfo": {
+                "name": "rustc",
+                "version": option_env!("CFG_VERSION").unwrap_or("unknown"),
+            },
+        }),
+    )?;
+    let project = emitter.vertex("project", json!({ "kind": "rust", "name": crate_name }))?;
+
+    // Definitions.
+    let mut symbols: FxHashMap<Id, Symbol> = FxHashMap::default();
+    let mut def_order = Vec::new();
+    for def in &analysis.defs {
+        if symbols.contains_key(&def.id) {
+            continue;
+        }
+        let result_set = emitter.vertex("resultSet", json!({}))?;
+        let definition = emitter.range(&def.span)?;
+        emitter.edge("next", definition.0, result_set)?;
+        emit_hover(&mut emitter, result_set, def)?;
+        // Local variables have qualified names like `x$12`, only items are
+        // exported.
+        if def.qualname.starts_with("::") {
+            let moniker = emitter.vertex(
+                "moniker",
+                json!({
+                    "scheme": "rustc",
+                    "identifier": format!("{}{}", crate_name, def.qualname),
+                    "kind": "export",
+                }),
+            )?;
+            emitter.edge("moniker", result_set, moniker)?;
+        }
+        def_order.push(def.id);
+        symbols.insert(
+            def.id,
+            Symbol { result_set, definition, references: vec![], implementations: vec![] },
+        );
+    }
+
+    // References to definitions of this crate.
+    for reference in &analysis.refs {
+        let Some(result_set) = symbols.get(&reference.ref_id).map(|s| s.result_set) else {
+            continue;
+        };
+        let range = emitter.range(&reference.span)?;
+        emitter.edge("next", range.0, result_set)?;
+        symbols.get_mut(&reference.ref_id).unwrap().references.push(range);
+    }
+
+    // Implementations of traits, at the self type of the impl, and of trait
+    // methods.
+    for relation in &analysis.relations {
+        let RelationKind::Impl { .. } = relation.kind else { continue };
+        if symbols.contains_key(&relation.to) {
+            let range = emitter.range(&relation.span)?;
+            symbols.get_mut(&relation.to).unwrap().implementations.push(range);
+        }
+    }
+    for def in &analysis.defs {
+        let Some(decl_id) = def.decl_id else { continue };
+        let Some(definition) = symbols.get(&def.id).map(|s| s.definition) else { continue };
+        if let Some(symbol) = symbols.get_mut(&decl_id) {
+            symbol.implementations.push(definition);
+        }
+    }
+
+    for id in def_order {
+        let symbol = &symbols[&id];
+        let definition = std::slice::from_ref(&symbol.definition);
+        emitter.result(
+            symbol.result_set,
+            "textDocument/definition",
+            "definitionResult",
+            &[(None, definition)],
+        )?;
+        emitter.result(
+            symbol.result_set,
+            "textDocument/references",
+            "referenceResult",
+            &[(Some("definitions"), definition), (Some("references"), &symbol.references[..])],
+        )?;
+        if !symbol.implementations.is_empty() {
+            emitter.result(
+                symbol.result_set,
+                "textDocument/implementation",
+                "implementationResult",
+                &[(None, &symbol.implementations[..])],
+            )?;
+        }
+    }
+
+    // Containment of ranges in documents, and of documents in the project.
+    let documents: Vec<_> = emitter.documents.values().copied().collect();
+    for (document, ranges) in std::mem::take(&mut emitter.ranges) {
+        emitter.emit(json!({
+            "type": "edge",
+            "label": "contains",
+            "outV": document,
+            "inVs": ranges,
+        }))?;
+    }
+    emitter.emit(json!({
+        "type": "edge",
+        "label": "contains",
+        "outV": project,
+        "inVs": documents,
+    }))?;
+    emitter.out.flush()
+}
+
+fn emit_hover<W: Write>(
+    emitter: &mut Emitter<'_, W>,
+    result_set: VertexId,
+    def: &Def,
+) -> io::Result<()> {
+    let mut contents = vec![];
+    if !def.value.is_empty() {
+        contents.push(json!({ "language": "rust", "value": def.value }));
+    }
+    if !def.docs.trim().is_empty() {
+        contents.push(def.docs.trim().into());
+    }
+    if contents.is_empty() {
+        return Ok(());
+    }
+    let hover = emitter.vertex("hoverResult", json!({ "result": { "contents": contents } }))?;
+    emitter.edge("textDocument/hover", result_set, hover)
+}
+
+/// Returns the `file` URI of an absolute path.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    let path = path.to_string_lossy();
+    if !path.starts_with('/') {
+        // Windows paths like `C:\foo`.
+        uri.push('/');
+    }
+    for c in path.chars() {
+        match c {
+            '\\' => uri.push('/'),
+            ' ' => uri.push_str("%20"),
+            '%' => uri.push_str("%25"),
+            '#' => uri.push_str("%23"),
+            '?' => uri.push_str("%3F"),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
@@ -2,6 +2,7 @@
 use rustc_data_structures::sync::Lock;
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
+use serde::Serialize;
 use std::cmp::{self, Ordering};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -33,16 +34,53 @@ pub enum DataTypeKind {
     Union,
     Enum,
     Closure,
+    Generator,
+}
+
+/// The largest niche of a type, i.e. the scalar whose invalid values can be
+/// used to encode the discriminant of an enclosing enum.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    /// The (wrapping, inclusive) range of values the niche scalar may hold.
+    pub valid_range_start: u128,
+    pub valid_range_end: u128,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DiscriminantEncoding {
+    /// The discriminant is stored as-is in a dedicated tag field.
+    Direct,
+    /// The variants in `niche_variants_start..=niche_variants_end` are encoded
+    /// in the niche of a field of `untagged_variant`, starting at `niche_start`.
+    Niche {
+        untagged_variant: Symbol,
+        niche_variants_start: Symbol,
+        niche_variants_end: Symbol,
+        niche_start: u128,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DiscriminantInfo {
+    pub offset: u64,
+    pub size: u64,
+    pub encoding: DiscriminantEncoding,
 }
 
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
     pub type_description: String,
+    /// A path identifying the type that does not depend on the way types
+    /// are printed in diagnostics, used to sort the JSON output.
+    pub path: String,
     pub align: u64,
     pub overall_size: u64,
     pub packed: bool,
-    pub opt_discr_size: Option<u64>,
+    pub discriminant: Option<DiscriminantInfo>,
+    pub niche: Option<NicheInfo>,
     pub variants: Vec<VariantInfo>,
 }
 
@@ -56,10 +94,12 @@ pub fn record_type_size<S: ToString>(
         &self,
         kind: DataTypeKind,
         type_desc: S,
+        path: String,
         align: Align,
         overall_size: Size,
         packed: bool,
-        opt_discr_size: Option<Size>,
+        discriminant: Option<DiscriminantInfo>,
+        niche: Option<NicheInfo>,
         mut variants: Vec<VariantInfo>,
     ) {
         // Sort variants so the largest ones are shown first. A stable sort is
@@ -69,10 +109,12 @@ pub fn record_type_size<S: ToString>(
         let info = TypeSizeInfo {
             kind,
             type_description: type_desc.to_string(),
+            path,
             align: align.bytes(),
             overall_size: overall_size.bytes(),
             packed,
-            opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            discriminant,
+            niche,
             variants,
         };
         self.type_sizes.borrow_mut().insert(info);
@@ -80,7 +122,9 @@ pub fn record_type_size<S: ToString>(
 
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
-        let mut sorted: Vec<_> = type_sizes.iter().collect();
+        // Generators are only recorded for `-Z print-type-sizes-json`.
+        let mut sorted: Vec<_> =
+            type_sizes.iter().filter(|info| info.kind != DataTypeKind::Generator).collect();
 
         // Primary sort: large-to-small.
         // Secondary sort: description (dictionary order)
@@ -94,16 +138,19 @@ pub fn print_type_sizes(&self) {
 
         for info in sorted {
             let TypeSizeInfo { type_description, overall_size, align, kind, variants, .. } = info;
+            // So are the captures of closures.
+            let variants = if *kind == DataTypeKind::Closure { &[][..] } else { &variants[..] };
             println!(
                 "print-type-size type: `{type_description}`: {overall_size} bytes, alignment: {align} bytes"
             );
             let indent = "    ";
 
-            let discr_size = if let Some(discr_size) = info.opt_discr_size {
-                println!("print-type-size {indent}discriminant: {discr_size} bytes");
-                discr_size
-            } else {
-                0
+            let discr_size = match info.discriminant {
+                Some(DiscriminantInfo { size, encoding: DiscriminantEncoding::Direct, .. }) => {
+                    println!("print-type-size {indent}discriminant: {size} bytes");
+                    size
+                }
+                _ => 0,
             };
 
             // We start this at discr_size (rather than 0) because
@@ -114,7 +161,7 @@ pub fn print_type_sizes(&self) {
 
             let struct_like = match kind {
                 DataTypeKind::Struct | DataTypeKind::Closure => true,
-                DataTypeKind::Enum | DataTypeKind::Union => false,
+                DataTypeKind::Enum | DataTypeKind::Union | DataTypeKind::Generator => false,
             };
             for (i, variant_info) in variants.into_iter().enumerate() {
                 let VariantInfo { ref name, kind: _, align: _, size, ref fields } = *variant_info;
@@ -179,4 +226,174 @@ pub fn print_type_sizes(&self) {
             }
         }
     }
+
+    pub fn print_type_sizes_json(&self) {
+        let type_sizes = self.type_sizes.borrow();
+        // Sort the layouts by path, so that the output does not depend on the
+        // order of the records. Different types can have the same path, e.g.
+        // types of different versions of a crate, so the path is not a key.
+        let mut layouts: Vec<_> = type_sizes.iter().map(JsonTypeLayout::new).collect();
+        layouts.sort_by(|a, b| a.path.cmp(b.path).then_with(|| a.description.cmp(b.description)));
+        println!("{}", serde_json::to_string_pretty(&layouts).unwrap());
+    }
+}
+
+// The types below define the format of `-Z print-type-sizes-json`. Tools
+// depend on it, so fields should only ever be added to it.
+
+#[derive(Serialize)]
+struct JsonTypeLayout<'a> {
+    path: &'a str,
+    kind: &'static str,
+    description: &'a str,
+    size: u64,
+    align: u64,
+    packed: bool,
+    discriminant: Option<JsonDiscriminant<'a>>,
+    niche: Option<JsonNiche>,
+    variants: Vec<JsonVariant<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonDiscriminant<'a> {
+    offset: u64,
+    size: u64,
+    encoding: JsonDiscriminantEncoding<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonDiscriminantEncoding<'a> {
+    Direct,
+    Niche {
+        untagged_variant: &'a str,
+        niche_variants_start: &'a str,
+        niche_variants_end: &'a str,
+        niche_start: u128,
+    },
+}
+
+#[derive(Serialize)]
+struct JsonNiche {
+    offset: u64,
+    size: u64,
+    valid_range_start: u128,
+    valid_range_end: u128,
+}
+
+#[derive(Serialize)]
+struct JsonVariant<'a> {
+    name: Option<&'a str>,
+    /// The end of the last field, or the minimum size for unsized variants.
+    size: u64,
+    sized: bool,
+    align: u64,
+    fields: Vec<JsonField<'a>>,
+    /// The bytes of the type that neither a field of this variant nor the
+    /// discriminant occupy.
+    padding: Vec<JsonPadding>,
+}
+
+#[derive(Serialize)]
+struct JsonField<'a> {
+    name: &'a str,
+    offset: u64,
+    size: u64,
+    align: u64,
+}
+
+#[derive(Serialize)]
+struct JsonPadding {
+    offset: u64,
+    size: u64,
+}
+
+impl<'a> JsonTypeLayout<'a> {
+    fn new(info: &'a TypeSizeInfo) -> Self {
+        let discriminant = info.discriminant.as_ref().map(|discr| JsonDiscriminant {
+            offset: discr.offset,
+            size: discr.size,
+            encoding: match discr.encoding {
+                DiscriminantEncoding::Direct => JsonDiscriminantEncoding::Direct,
+                DiscriminantEncoding::Niche {
+                    ref untagged_variant,
+                    ref niche_variants_start,
+                    ref niche_variants_end,
+                    niche_start,
+                } => JsonDiscriminantEncoding::Niche {
+                    untagged_variant: untagged_variant.as_str(),
+                    niche_variants_start: niche_variants_start.as_str(),
+                    niche_variants_end: niche_variants_end.as_str(),
+                    niche_start,
+                },
+            },
+        });
+
+        // The discriminant is stored in every variant, either in its own tag
+        // field or in the niche of a field of the untagged variant.
+        let tag = info.discriminant.map(|discr| (discr.offset, discr.size));
+
+        let variants = info
+            .variants
+            .iter()
+            .map(|variant| {
+                let mut fields: Vec<_> = variant
+                    .fields
+                    .iter()
+                    .map(|field| JsonField {
+                        name: field.name.as_str(),
+                        offset: field.offset,
+                        size: field.size,
+                        align: field.align,
+                    })
+                    .collect();
+                fields.sort_by_key(|f| (f.offset, f.size));
+
+                let mut occupied: Vec<(u64, u64)> =
+                    fields.iter().map(|f| (f.offset, f.size)).chain(tag).collect();
+                occupied.sort();
+
+                let mut padding = vec![];
+                let mut end = 0;
+                for (offset, size) in occupied.into_iter().chain([(info.overall_size, 0)]) {
+                    if offset > end {
+                        padding.push(JsonPadding { offset: end, size: offset - end });
+                    }
+                    end = cmp::max(end, offset + size);
+                }
+
+                JsonVariant {
+                    name: variant.name.as_ref().map(|name| name.as_str()),
+                    size: variant.size,
+                    sized: variant.kind == SizeKind::Exact,
+                    align: variant.align,
+                    fields,
+                    padding,
+                }
+            })
+            .collect();
+
+        JsonTypeLayout {
+            path: &info.path,
+            kind: match info.kind {
+                DataTypeKind::Struct => "struct",
+                DataTypeKind::Union => "union",
+                DataTypeKind::Enum => "enum",
+                DataTypeKind::Closure => "closure",
+                DataTypeKind::Generator => "generator",
+            },
+            description: &info.type_description,
+            size: info.overall_size,
+            align: info.align,
+            packed: info.packed,
+            discriminant,
+            niche: info.niche.map(|niche| JsonNiche {
+                offset: niche.offset,
+                size: niche.size,
+                valid_range_start: niche.valid_range_start,
+                valid_range_end: niche.valid_range_end,
+            }),
+            variants,
+        }
+    }
 }
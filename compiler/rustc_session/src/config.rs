@@ -3,6 +3,7 @@
 
 pub use crate::options::*;
 
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
 use crate::{early_error, early_warn, Session};
@@ -376,6 +377,8 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF log covering the whole session, consumed by code-scanning tools.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -719,6 +722,8 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: None,
+            edition_migration_report: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -796,6 +801,7 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            collect_machine_applicable_suggestions: self.emit_fix_bundle.is_some(),
         }
     }
 }
@@ -1414,11 +1420,18 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         ),
         opt::opt_s("", "sysroot", "Override the system root", "PATH"),
         opt::multi("Z", "", "Set unstable / perma-unstable options", "FLAG"),
+        opt::opt("", "format", "Format of the explanation printed by `--explain`", "text|json"),
+        opt::opt(
+            "",
+            "edition-migration-report",
+            "Write a report of the migration lints of an edition instead of emitting them",
+            EDITION_NAME_LIST,
+        ),
         opt::opt_s(
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|short|sarif",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1599,12 +1612,13 @@ pub fn parse_error_format(
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+            Some("sarif") => ErrorOutputType::Sarif,
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `json`, `short` or \
+                     `sarif` (instead was `{arg}`)"
                 ),
             ),
         }
@@ -1661,6 +1675,35 @@ pub fn parse_crate_edition(matches: &getopts::Matches) -> Edition {
     edition
 }
 
+/// Parses the edition given to `--edition-migration-report`, which must be later than the
+/// edition of the crate.
+fn parse_edition_migration_report(
+    matches: &getopts::Matches,
+    edition: Edition,
+    error_format: ErrorOutputType,
+) -> Option<Edition> {
+    let arg = matches.opt_str("edition-migration-report")?;
+    let target = Edition::from_str(&arg).unwrap_or_else(|_| {
+        early_error(
+            error_format,
+            &format!(
+                "argument for `--edition-migration-report` must be one of: \
+                 {EDITION_NAME_LIST}. (instead was `{arg}`)"
+            ),
+        )
+    });
+    if target <= edition {
+        early_error(
+            error_format,
+            &format!(
+                "`--edition-migration-report` needs an edition later than the edition of \
+                 the crate ({edition}), instead was {target}"
+            ),
+        )
+    }
+    Some(target)
+}
+
 fn check_error_format_stability(
     unstable_opts: &UnstableOptions,
     error_format: ErrorOutputType,
@@ -1681,6 +1724,12 @@ fn check_error_format_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -2253,9 +2302,19 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let mut unstable_opts = UnstableOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = unstable_opts.lint_config.as_deref().map(|path| {
+        LintConfig::load(path).unwrap_or_else(|e| {
+            early_error(
+                error_format,
+                &format!("failed to load lint configuration file `{}`: {e}", path.display()),
+            )
+        })
+    });
 
     check_error_format_stability(&unstable_opts, error_format, json_rendered);
 
+    let edition_migration_report = parse_edition_migration_report(matches, edition, error_format);
+
     if !unstable_opts.unstable_options && json_unused_externs.is_enabled() {
         early_error(
             error_format,
@@ -2490,6 +2549,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_config,
+        edition_migration_report,
         describe_lints,
         output_types,
         search_paths,
@@ -2764,6 +2825,7 @@ pub(crate) mod dep_tracking {
         SwitchWithOptPath, SymbolManglingVersion, TrimmedDefPaths,
     };
     use crate::lint;
+    use crate::lint_config::LintConfig;
     use crate::options::WasiExecModel;
     use crate::utils::{NativeLib, NativeLibKind};
     use rustc_errors::LanguageIdentifier;
@@ -2861,6 +2923,7 @@ fn hash(
         BranchProtection,
         OomStrategy,
         LanguageIdentifier,
+        LintConfig,
     );
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
@@ -2981,3 +3044,21 @@ pub enum ProcMacroExecutionStrategy {
     /// Run the proc-macro code on a different thread.
     CrossThread,
 }
+
+/// The format of the `-Z save-analysis` output.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SaveAnalysisFormat {
+    /// The JSON format of the `rls-data` crate.
+    Json,
+    /// The Language Server Index Format, as line-delimited JSON.
+    Lsif,
+}
+
+impl SaveAnalysisFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SaveAnalysisFormat::Json => "json",
+            SaveAnalysisFormat::Lsif => "lsif",
+        }
+    }
+}
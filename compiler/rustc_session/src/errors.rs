@@ -127,6 +127,38 @@ pub struct FileIsNotWriteable<'a> {
     pub file: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_fix_bundle_write_error)]
+pub struct FixBundleWriteError<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_lint_baseline_write_error)]
+pub struct LintBaselineWriteError<'a> {
+    pub path: &'a std::path::Path,
+    pub error: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_stale_lint_baseline_entries)]
+#[note]
+#[help]
+pub struct StaleLintBaselineEntries<'a> {
+    pub path: &'a std::path::Path,
+    pub count: usize,
+    pub entries: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_missing_translations_summary)]
+pub struct MissingTranslationsSummary {
+    pub locale: String,
+    pub count: usize,
+    pub ids: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_crate_name_does_not_match)]
 pub struct CrateNameDoesNotMatch<'a> {
@@ -0,0 +1,132 @@
+//! The lint configuration file given with `-Z lint-config`, conventionally called
+//! `rustc-lints.toml`.
+//!
+//! The file sets lint levels for the whole crate, for the source files under some paths,
+//! and can define lint groups of its own:
+//!
+//! ```toml
+//! [lints]
+//! unused = "deny"
+//! style = "warn"
+//!
+//! [groups]
+//! style = ["non_snake_case", "non_camel_case_types"]
+//!
+//! [[override]]
+//! paths = ["tests", "benches/common.rs"]
+//! lints = { dead_code = "allow" }
+//! ```
+//!
+//! The levels of the `[lints]` table are applied before the `-A`/`-W`/`-D`/`-F` flags,
+//! which take precedence over them. The levels of an `[[override]]` are applied at the crate
+//! root or module loaded from each file under its `paths`, as if they were inner attributes
+//! preceding the ones of the file. In both cases, the levels of lint groups are applied
+//! before the levels of single lints, and a level never overrides `forbid` or `force-warn`.
+
+use crate::lint::Level;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A parsed lint configuration file.
+#[derive(Clone, Debug, Hash)]
+pub struct LintConfig {
+    /// The path of the file, for diagnostics.
+    pub path: PathBuf,
+    /// The levels of the `[lints]` table, which apply to the whole crate.
+    pub lints: Vec<(String, Level)>,
+    /// The lint groups defined in the `[groups]` table, with the lints they contain.
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// The `[[override]]` sections, in the order of the file.
+    pub overrides: Vec<LintOverride>,
+}
+
+/// The lint levels that apply to the source files under some paths.
+#[derive(Clone, Debug, Hash)]
+pub struct LintOverride {
+    /// The paths as written in the configuration file, relative to `root`.
+    pub paths: Vec<String>,
+    /// The directory of the configuration file.
+    pub root: PathBuf,
+    pub lints: Vec<(String, Level)>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLintConfig {
+    #[serde(default)]
+    lints: BTreeMap<String, String>,
+    #[serde(default)]
+    groups: BTreeMap<String, Vec<String>>,
+    #[serde(default, rename = "override")]
+    overrides: Vec<RawLintOverride>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLintOverride {
+    paths: Vec<String>,
+    lints: BTreeMap<String, String>,
+}
+
+impl LintConfig {
+    /// Reads and parses the lint configuration file at `path`.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let raw: RawLintConfig = toml::from_str(&contents).map_err(|e| e.to_string())?;
+
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let overrides = raw
+            .overrides
+            .into_iter()
+            .map(|o| {
+                Ok(LintOverride {
+                    paths: o.paths,
+                    root: root.clone(),
+                    lints: parse_levels(o.lints)?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        let groups = raw
+            .groups
+            .into_iter()
+            .map(|(name, lints)| {
+                let lints = lints.into_iter().map(|lint| lint.replace('-', "_")).collect();
+                (name.replace('-', "_"), lints)
+            })
+            .collect();
+
+        Ok(LintConfig {
+            path: path.to_path_buf(),
+            lints: parse_levels(raw.lints)?,
+            groups,
+            overrides,
+        })
+    }
+
+    /// Returns the overrides that apply to the source file at `file`, with the path of each
+    /// override that matched. Relative paths are relative to `working_dir`.
+    pub fn overrides_for<'a>(
+        &'a self,
+        file: &Path,
+        working_dir: &'a Path,
+    ) -> impl Iterator<Item = (&'a LintOverride, &'a str)> + 'a {
+        let file = working_dir.join(file);
+        self.overrides.iter().filter_map(move |o| {
+            let root = working_dir.join(&o.root);
+            let path = o.paths.iter().find(|path| file.starts_with(root.join(path)))?;
+            Some((o, path.as_str()))
+        })
+    }
+}
+
+fn parse_levels(lints: BTreeMap<String, String>) -> Result<Vec<(String, Level)>, String> {
+    lints
+        .into_iter()
+        .map(|(lint, level)| match Level::from_str(&level) {
+            Some(level) => Ok((lint.replace('-', "_"), level)),
+            None => Err(format!("unknown lint level `{level}` for `{lint}`")),
+        })
+        .collect()
+}
@@ -2,8 +2,10 @@
 
 use crate::early_error;
 use crate::lint;
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLib;
+use rustc_data_structures::profiling::SelfProfileSummaryFormat;
 use rustc_errors::LanguageIdentifier;
 use rustc_target::spec::{CodeModel, LinkerFlavorCli, MergeFunctions, PanicStrategy, SanitizerSet};
 use rustc_target::spec::{
@@ -140,6 +142,11 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The lint configuration file loaded from the path given with `-Z lint-config`.
+        lint_config: Option<LintConfig> [TRACKED_NO_CRATE_HASH],
+        /// The edition whose migration lints are run and counted instead of being emitted,
+        /// with `--edition-migration-report`.
+        edition_migration_report: Option<Edition> [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -355,6 +362,7 @@ mod desc {
     pub const parse_opt_string: &str = parse_string;
     pub const parse_string_push: &str = parse_string;
     pub const parse_opt_langid: &str = "a language identifier";
+    pub const parse_langid_list: &str = "a comma-separated list of language identifiers";
     pub const parse_opt_pathbuf: &str = "a path";
     pub const parse_list: &str = "a space-separated list of strings";
     pub const parse_list_with_polarity: &str =
@@ -388,6 +396,8 @@ mod desc {
     pub const parse_location_detail: &str = "either `none`, or a comma separated list of location details to track: `file`, `line`, or `column`";
     pub const parse_switch_with_opt_path: &str =
         "an optional path to the profiling data output directory";
+    pub const parse_self_profile_summary: &str = "either `table`, `json`, or omitted";
+    pub const parse_time_by_item: &str = "a number of items, or omitted";
     pub const parse_merge_functions: &str = "one of: `disabled`, `trampolines`, or `aliases`";
     pub const parse_symbol_mangling_version: &str = "either `legacy` or `v0` (RFC 2603)";
     pub const parse_src_file_hash: &str = "either `md5` or `sha1`";
@@ -408,6 +418,7 @@ mod desc {
         "a `,` separated combination of `bti`, `b-key`, `pac-ret`, or `leaf`";
     pub const parse_proc_macro_execution_strategy: &str =
         "one of supported execution strategies (`same-thread`, or `cross-thread`)";
+    pub const parse_save_analysis_format: &str = "one of `json` or `lsif`";
 }
 
 mod parse {
@@ -491,6 +502,17 @@ pub(crate) fn parse_opt_langid(slot: &mut Option<LanguageIdentifier>, v: Option<
         }
     }
 
+    pub(crate) fn parse_langid_list(slot: &mut Vec<LanguageIdentifier>, v: Option<&str>) -> bool {
+        let Some(v) = v else { return false };
+        let mut langids = vec![];
+        for s in v.split(',') {
+            let Ok(langid) = LanguageIdentifier::from_str(s) else { return false };
+            langids.push(langid);
+        }
+        *slot = langids;
+        true
+    }
+
     pub(crate) fn parse_opt_pathbuf(slot: &mut Option<PathBuf>, v: Option<&str>) -> bool {
         match v {
             Some(s) => {
@@ -912,6 +934,29 @@ pub(crate) fn parse_switch_with_opt_path(
         true
     }
 
+    pub(crate) fn parse_self_profile_summary(
+        slot: &mut Option<SelfProfileSummaryFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("table") => Some(SelfProfileSummaryFormat::Table),
+            Some("json") => Some(SelfProfileSummaryFormat::Json),
+            _ => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_time_by_item(slot: &mut Option<usize>, v: Option<&str>) -> bool {
+        *slot = match v {
+            None => Some(20),
+            Some(n) => match n.parse() {
+                Ok(n) => Some(n),
+                Err(_) => return false,
+            },
+        };
+        true
+    }
+
     pub(crate) fn parse_merge_functions(
         slot: &mut Option<MergeFunctions>,
         v: Option<&str>,
@@ -1070,6 +1115,18 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         };
         true
     }
+
+    pub(crate) fn parse_save_analysis_format(
+        slot: &mut SaveAnalysisFormat,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            Some("json") => SaveAnalysisFormat::Json,
+            Some("lsif") => SaveAnalysisFormat::Lsif,
+            _ => return false,
+        };
+        true
+    }
 }
 
 options! {
@@ -1235,6 +1292,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "the codegen unit partitioning strategy to use"),
     chalk: bool = (false, parse_bool, [TRACKED],
         "enable the experimental Chalk-based trait solving engine"),
+    check_explanations: bool = (false, parse_bool, [UNTRACKED],
+        "compile the erroneous examples of the error code explanations and check that each one \
+        triggers exactly the error codes it is annotated with (default: no)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
@@ -1269,6 +1329,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         (default: no)"),
     dump_drop_tracking_cfg: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump drop-tracking control-flow graph as a `.dot` file (default: no)"),
+    dump_item_deps: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON graph of the items that the body of each item refers to \
+        (calls, types, statics and trait impls) to the given path (default: no)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -1297,12 +1360,20 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
         "enables LTO for dylib crate type"),
+    emit_fix_bundle: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the machine-applicable suggestions of all emitted diagnostics as a single \
+        unified diff to the given path"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
+    emit_stack_sizes_report: bool = (false, parse_bool, [TRACKED],
+        "print the maximum stack usage of each function, computed from the stack size metadata \
+        and the call graph (default: no)"),
     emit_thin_lto: bool = (true, parse_bool, [TRACKED],
         "emit the bc module with thin LTO info (default: yes)"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
+    extern_usage_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write how the crates passed with `--extern` are used as JSON to the given path"),
     extra_const_ub_checks: bool = (false, parse_bool, [TRACKED],
         "turns on more checks to detect const UB, which can be slow (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::fewer_names` instead of this field")]
@@ -1370,6 +1441,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    lint_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "suppress the lints recorded in a baseline file (records one if the file does not exist)"),
+    lint_config: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "read lint levels from a configuration file, usually called `rustc-lints.toml`"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
         "a list LLVM plugins to enable (space separated)"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
@@ -1462,13 +1537,21 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         See #77382 and #74551."),
     print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
         "make rustc print the total optimization fuel used by a crate"),
+    print_lint_levels: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the level of every lint at a source position given as `FILE:LINE:COL`"),
     #[rustc_lint_opt_deny_field_access("use `Session::print_llvm_passes` instead of this field")]
     print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "print the LLVM optimization passes being run (default: no)"),
+    print_mono_bloat: bool = (false, parse_bool, [UNTRACKED],
+        "print the generic functions that were instantiated, with their number of \
+        instantiations, MIR statements, and the callers that introduced them (default: no)"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
+    print_type_sizes_json: bool = (false, parse_bool, [UNTRACKED],
+        "print layout information for each type encountered as JSON, sorted by the path of \
+        the type (default: no)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
          "show backtraces for panics during proc-macro execution (default: no)"),
     proc_macro_execution_strategy: ProcMacroExecutionStrategy = (ProcMacroExecutionStrategy::SameThread,
@@ -1509,6 +1592,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     save_analysis: bool = (false, parse_bool, [UNTRACKED],
         "write syntax and type analysis (in JSON format) information, in \
         addition to normal output (default: no)"),
+    save_analysis_format: SaveAnalysisFormat = (SaveAnalysisFormat::Json,
+        parse_save_analysis_format, [UNTRACKED],
+        "the format of the `-Z save-analysis` output: `json` for the rls-data format, or `lsif` \
+        for a Language Server Index Format dump (default: json)"),
     self_profile: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "run the self profiler and output the raw event data"),
@@ -1524,6 +1611,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_summary: Option<SelfProfileSummaryFormat> = (None,
+        parse_self_profile_summary, [UNTRACKED],
+        "run the self profiler and print a summary of the time spent in each query and \
+        activity to stderr at the end of compilation, as a `table` (default) or as `json`"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1576,6 +1667,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::threads` instead of this field")]
     threads: usize = (1, parse_threads, [UNTRACKED],
         "use a thread pool with N threads"),
+    time_by_item: Option<usize> = (None, parse_time_by_item, [UNTRACKED],
+        "run the self profiler and print the N (default: 20) items whose type checking, \
+        borrow checking, MIR optimization, monomorphization and codegen took the most time"),
     #[rustc_lint_opt_deny_field_access("use `Session::time_llvm_passes` instead of this field")]
     time_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "measure time of each LLVM pass (default: no)"),
@@ -1594,10 +1688,19 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     // translation options should be tracked.
     translate_additional_ftl: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "additional fluent translation to preferentially use (for testing translation)"),
+    translate_check: bool = (false, parse_bool, [UNTRACKED],
+        "check that the translation of `-Z translate-lang` has all messages of the compiler, \
+        report the problems that were found and exit (default: no)"),
     translate_directionality_markers: bool = (false, parse_bool, [TRACKED],
         "emit directionality isolation markers in translated diagnostics"),
+    translate_fallback: Vec<LanguageIdentifier> = (Vec::new(), parse_langid_list, [TRACKED],
+        "languages to use, in order, for diagnostic messages that are missing from \
+        `-Z translate-lang`, before falling back to `en-US`"),
     translate_lang: Option<LanguageIdentifier> = (None, parse_opt_langid, [TRACKED],
         "language identifier for diagnostic output"),
+    translate_missing_summary: bool = (false, parse_bool, [UNTRACKED],
+        "report the diagnostic messages that had to be emitted in `en-US` because \
+        `-Z translate-lang` has no translation for them, at the end of the session (default: no)"),
     translate_remapped_path_to_local_path: bool = (true, parse_bool, [TRACKED],
         "translate remapped paths into local paths when possible (default: yes)"),
     trap_unreachable: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1633,6 +1736,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::unstable_options` instead of this field")]
     unstable_options: bool = (false, parse_bool, [UNTRACKED],
         "adds unstable command line options to rustc interface (default: no)"),
+    update_lint_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "record the lints of this compilation in the `-Z lint-baseline` file instead of \
+        suppressing them (default: no)"),
     use_ctors_section: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "use legacy .ctors section for initializers rather than .init_array"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
@@ -1,12 +1,16 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+pub use crate::code_stats::{
+    DataTypeKind, DiscriminantEncoding, DiscriminantInfo, FieldInfo, NicheInfo, SizeKind,
+    VariantInfo,
+};
 use crate::config::{self, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath};
 use crate::errors::{
-    CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers, LinkerPluginToWindowsNotSupported,
+    CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers, FixBundleWriteError,
+    LinkerPluginToWindowsNotSupported, LintBaselineWriteError, MissingTranslationsSummary,
     NotCircumventFeature, ProfileSampleUseFileDoesNotExist, ProfileUseFileDoesNotExist,
     SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported, SkippingConstChecks,
-    SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget,
+    SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget, StaleLintBaselineEntries,
     TargetRequiresUnwindTables, UnleashedFeatureHelp, UnstableVirtualFunctionElimination,
     UnsupportedDwarfVersion,
 };
@@ -24,9 +28,14 @@
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
+use rustc_errors::edition_migration::EditionMigrationReport;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::fix_bundle::render_fix_bundle;
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::lint_baseline::LintBaseline;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
+use rustc_errors::translation::MissingTranslations;
 use rustc_errors::{
     error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
     ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle, MultiSpan, Noted,
@@ -210,6 +219,12 @@ pub struct Session {
 
     /// Set of enabled features for the current target, including unstable ones.
     pub unstable_target_features: FxHashSet<Symbol>,
+
+    /// The bundle with the translations of `-Z translate-lang` and its fallbacks, if any.
+    pub translation_bundle: Option<Lrc<FluentBundle>>,
+
+    /// The diagnostic messages that had no translation, for `-Z translate-missing-summary`.
+    missing_translations: Option<Lrc<MissingTranslations>>,
 }
 
 pub struct PerfStats {
@@ -257,8 +272,14 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.emit_missing_translations();
+        self.warn_stale_lint_baseline_entries();
+        // A failure to write the fix bundle is an error of its own, so it is
+        // emitted before the errors are counted.
+        self.emit_fix_bundle();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
+        self.write_lint_baseline();
     }
 
     fn emit_future_breakage(&self) {
@@ -273,6 +294,98 @@ fn emit_future_breakage(&self) {
         self.parse_sess.span_diagnostic.emit_future_breakage_report(diags);
     }
 
+    /// Writes the machine-applicable suggestions collected during the session
+    /// to the path given to `-Z emit-fix-bundle`.
+    fn emit_fix_bundle(&self) {
+        let Some(path) = &self.opts.unstable_opts.emit_fix_bundle else { return };
+
+        let suggestions = self.diagnostic().take_machine_applicable_suggestions();
+        let working_dir = self.opts.working_dir.local_path_if_available();
+        let bundle = render_fix_bundle(self.source_map(), working_dir, &suggestions);
+        if let Err(error) = std::fs::write(path, bundle) {
+            self.emit_err(FixBundleWriteError { path, error });
+        }
+    }
+
+    /// Warns about the entries of the `-Z lint-baseline` file that no lint
+    /// diagnostic matched. This only happens once all diagnostics have been
+    /// emitted, as some lints are only emitted during monomorphization or
+    /// codegen. If there were errors, the lints of the steps that didn't run are
+    /// missing, so nothing is reported.
+    fn warn_stale_lint_baseline_entries(&self) {
+        let Some(path) = &self.opts.unstable_opts.lint_baseline else { return };
+        if self.has_errors().is_some() {
+            return;
+        }
+
+        let entries = self.diagnostic().stale_lint_baseline_entries();
+        if entries.is_empty() {
+            return;
+        }
+        self.emit_warning(StaleLintBaselineEntries {
+            path,
+            count: entries.len(),
+            entries: entries
+                .iter()
+                .map(|entry| format!("`{}` in `{}`", entry.lint, entry.location))
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+
+    /// Writes the lints emitted during the session to the path given to
+    /// `-Z lint-baseline`, if a new baseline was recorded.
+    fn write_lint_baseline(&self) {
+        let Some(path) = &self.opts.unstable_opts.lint_baseline else { return };
+        let Some(baseline) = self.diagnostic().take_lint_baseline() else { return };
+        if !baseline.is_recording() {
+            return;
+        }
+
+        if let Err(error) = std::fs::write(path, baseline.render()) {
+            self.emit_err(LintBaselineWriteError { path, error });
+        }
+    }
+
+    /// Reports the diagnostic messages that were emitted in `en-US` because the locale
+    /// requested with `-Z translate-lang` has no translation for them.
+    fn emit_missing_translations(&self) {
+        let Some(missing_translations) = &self.missing_translations else { return };
+        let Some(locale) = &self.opts.unstable_opts.translate_lang else { return };
+
+        let ids = missing_translations.ids();
+        if ids.is_empty() {
+            return;
+        }
+        self.emit_note(MissingTranslationsSummary {
+            locale: locale.to_string(),
+            count: ids.len(),
+            ids: ids.iter().map(|id| format!("`{id}`")).collect::<Vec<_>>().join(", "),
+        });
+    }
+
+    /// Prints the summary requested by `-Z self-profile-summary` to stderr.
+    pub fn print_self_profile_summary(&self) {
+        let Some(format) = self.opts.unstable_opts.self_profile_summary else { return };
+
+        self.prof.with_profiler(|profiler| {
+            if let Some(summary) = profiler.render_summary(format) {
+                eprint!("{summary}");
+            }
+        });
+    }
+
+    /// Prints the report requested by `-Z time-by-item` to stderr.
+    pub fn print_time_by_item(&self) {
+        let Some(top_n) = self.opts.unstable_opts.time_by_item else { return };
+
+        self.prof.with_profiler(|profiler| {
+            if let Some(report) = profiler.render_item_times(top_n) {
+                eprint!("{report}");
+            }
+        });
+    }
+
     pub fn local_stable_crate_id(&self) -> StableCrateId {
         self.stable_crate_id.get().copied().unwrap()
     }
@@ -599,6 +712,7 @@ pub fn delay_span_bug<S: Into<MultiSpan>>(
     /// it's likely a bug.
     pub fn delay_good_path_bug(&self, msg: impl Into<DiagnosticMessage>) {
         if self.opts.unstable_opts.print_type_sizes
+            || self.opts.unstable_opts.print_type_sizes_json
             || self.opts.unstable_opts.query_dep_graph
             || self.opts.unstable_opts.dump_mir.is_some()
             || self.opts.unstable_opts.unpretty.is_some()
@@ -1224,6 +1338,14 @@ pub fn edition(&self) -> Edition {
         self.opts.edition
     }
 
+    /// Whether `lint` is run for `--edition-migration-report`, i.e. whether it is a migration
+    /// lint of an edition later than the edition of the crate and up to the one of the report.
+    pub fn is_edition_migration_lint(&self, lint: &lint::Lint) -> bool {
+        let Some(target) = self.opts.edition_migration_report else { return false };
+        let edition = lint.future_incompatible.and_then(|info| info.reason.edition());
+        edition.map_or(false, |edition| edition > self.edition() && edition <= target)
+    }
+
     pub fn link_dead_code(&self) -> bool {
         self.opts.cg.link_dead_code.unwrap_or(false)
     }
@@ -1237,6 +1359,7 @@ fn default_emitter(
     source_map: Lrc<SourceMap>,
     bundle: Option<Lrc<FluentBundle>>,
     fallback_bundle: LazyFallbackBundle,
+    missing_translations: Option<Lrc<MissingTranslations>>,
 ) -> Box<dyn Emitter + sync::Send> {
     let macro_backtrace = sopts.unstable_opts.macro_backtrace;
     let track_diagnostics = sopts.unstable_opts.track_diagnostics;
@@ -1252,7 +1375,11 @@ fn default_emitter(
                     short,
                     macro_backtrace,
                 );
-                Box::new(emitter.ui_testing(sopts.unstable_opts.ui_testing))
+                Box::new(
+                    emitter
+                        .ui_testing(sopts.unstable_opts.ui_testing)
+                        .track_missing_translations(missing_translations),
+                )
             } else {
                 let emitter = EmitterWriter::stderr(
                     color_config,
@@ -1265,7 +1392,11 @@ fn default_emitter(
                     macro_backtrace,
                     track_diagnostics,
                 );
-                Box::new(emitter.ui_testing(sopts.unstable_opts.ui_testing))
+                Box::new(
+                    emitter
+                        .ui_testing(sopts.unstable_opts.ui_testing)
+                        .track_missing_translations(missing_translations),
+                )
             }
         }
         config::ErrorOutputType::Json { pretty, json_rendered } => Box::new(
@@ -1280,7 +1411,12 @@ fn default_emitter(
                 macro_backtrace,
                 track_diagnostics,
             )
-            .ui_testing(sopts.unstable_opts.ui_testing),
+            .ui_testing(sopts.unstable_opts.ui_testing)
+            .track_missing_translations(missing_translations),
+        ),
+        config::ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::stderr(Some(registry), source_map, bundle, fallback_bundle)
+                .track_missing_translations(missing_translations),
         ),
     }
 }
@@ -1339,23 +1475,59 @@ pub fn build_session(
         rustc_errors::DEFAULT_LOCALE_RESOURCES,
         sopts.unstable_opts.translate_directionality_markers,
     );
-    let emitter = default_emitter(&sopts, registry, source_map.clone(), bundle, fallback_bundle);
+    // Messages missing from the default locale are not interesting, as they can only come
+    // from `-Z translate-additional-ftl`.
+    let missing_translations = (sopts.unstable_opts.translate_missing_summary
+        && sopts.unstable_opts.translate_lang.is_some())
+    .then(|| Lrc::new(MissingTranslations::default()));
+    let emitter = default_emitter(
+        &sopts,
+        registry,
+        source_map.clone(),
+        bundle.clone(),
+        fallback_bundle,
+        missing_translations.clone(),
+    );
 
     let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
         sopts.unstable_opts.diagnostic_handler_flags(can_emit_warnings),
     );
 
-    let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile
-    {
-        let directory =
-            if let Some(ref directory) = d { directory } else { std::path::Path::new(".") };
+    if let Some(path) = &sopts.unstable_opts.lint_baseline {
+        let baseline = if sopts.unstable_opts.update_lint_baseline || !path.exists() {
+            LintBaseline::recording()
+        } else {
+            LintBaseline::load(path).unwrap_or_else(|e| {
+                let msg = format!("failed to load lint baseline `{}`: {e}", path.display());
+                early_error(sopts.error_format, &msg)
+            })
+        };
+        span_diagnostic.set_lint_baseline(baseline);
+    }
+    if let Some(edition) = sopts.edition_migration_report {
+        span_diagnostic.set_edition_migration_report(EditionMigrationReport::new(edition));
+    }
 
+    let self_profile_summary = sopts.unstable_opts.self_profile_summary.is_some();
+    let time_by_item = sopts.unstable_opts.time_by_item.is_some();
+    let self_profile_directory = match sopts.unstable_opts.self_profile {
+        SwitchWithOptPath::Enabled(ref d) => {
+            Some(Some(d.as_deref().unwrap_or_else(|| std::path::Path::new("."))))
+        }
+        // The summary and the item times are aggregated by the self profiler,
+        // which then doesn't keep the raw event data.
+        SwitchWithOptPath::Disabled if self_profile_summary || time_by_item => Some(None),
+        SwitchWithOptPath::Disabled => None,
+    };
+    let self_profiler = if let Some(directory) = self_profile_directory {
         let profiler = SelfProfiler::new(
             directory,
             sopts.crate_name.as_deref(),
             sopts.unstable_opts.self_profile_events.as_ref().map(|xs| &xs[..]),
             &sopts.unstable_opts.self_profile_counter,
+            self_profile_summary,
+            time_by_item,
         );
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
@@ -1441,6 +1613,8 @@ pub fn build_session(
         asm_arch,
         target_features: FxHashSet::default(),
         unstable_target_features: FxHashSet::default(),
+        translation_bundle: bundle,
+        missing_translations,
     };
 
     validate_commandline_args_with_session_available(&sess);
@@ -1593,6 +1767,7 @@ fn early_error_handler(output: config::ErrorOutputType) -> rustc_errors::Handler
             false,
             false,
         )),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic(None, fallback_bundle)),
     };
     rustc_errors::Handler::with_emitter(true, None, emitter)
 }
@@ -0,0 +1,55 @@
+//! Module that implements the bridge between the Stable MIR and the compiler.
+//!
+//! Tools that drive the compiler themselves can use [`visit_local_bodies`]
+//! from their own callbacks. All other tools should use [`run`], which keeps
+//! them independent of the compiler's internal types.
+
+use crate::rustc_smir::Converter;
+use crate::stable_mir::mir::Body;
+use crate::stable_mir::{CompilerError, CrateItem};
+use crate::very_unstable::driver::{Callbacks, Compilation, RunCompiler};
+use crate::very_unstable::interface::{interface, Queries};
+use crate::very_unstable::middle::ty::TyCtxt;
+
+/// Converts the optimized MIR of every function, method, closure and
+/// generator of the local crate and passes it to `f`.
+pub fn visit_local_bodies<'tcx>(tcx: TyCtxt<'tcx>, mut f: impl FnMut(&CrateItem, &Body)) {
+    let converter = Converter { tcx };
+    for def_id in tcx.hir().body_owners() {
+        let def_id = def_id.to_def_id();
+        let Some(item) = converter.crate_item(def_id) else { continue };
+        let body = converter.body(tcx.optimized_mir(def_id));
+        f(&item, &body);
+    }
+}
+
+/// Driver callbacks that pass the Stable MIR of every local body to a
+/// function once analysis has finished without errors.
+pub struct LocalBodiesCallbacks<F> {
+    f: F,
+}
+
+impl<F: FnMut(&CrateItem, &Body)> LocalBodiesCallbacks<F> {
+    pub fn new(f: F) -> Self {
+        LocalBodiesCallbacks { f }
+    }
+}
+
+impl<F: FnMut(&CrateItem, &Body)> Callbacks for LocalBodiesCallbacks<F> {
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+
+        queries.global_ctxt().unwrap().peek_mut().enter(|tcx| visit_local_bodies(tcx, &mut self.f));
+        Compilation::Continue
+    }
+}
+
+/// Runs the compiler with the command line arguments `args`, and passes the
+/// Stable MIR of every body of the compiled crate to `f`.
+pub fn run(args: &[String], f: impl FnMut(&CrateItem, &Body) + Send) -> Result<(), CompilerError> {
+    RunCompiler::new(args, &mut LocalBodiesCallbacks::new(f)).run().map_err(|_| CompilerError)
+}
@@ -0,0 +1,499 @@
+//! Module that converts the compiler's internal MIR and types into the Stable MIR.
+//!
+//! The conversion is one-way: none of the internal types can be recovered from
+//! the stable ones.
+
+use crate::stable_mir::mir as smir;
+use crate::stable_mir::ty as sty;
+use crate::stable_mir::{self, CrateItem, ItemKind, ItemPath};
+use crate::very_unstable::hir::def::DefKind;
+use crate::very_unstable::hir::def_id::DefId;
+use crate::very_unstable::hir::Unsafety;
+use crate::very_unstable::middle::mir;
+use crate::very_unstable::middle::mir::interpret::{ConstValue, Scalar};
+use crate::very_unstable::middle::ty::adjustment::PointerCast;
+use crate::very_unstable::middle::ty::print::with_no_trimmed_paths;
+use crate::very_unstable::middle::ty::{self, ScalarInt, TyCtxt};
+use crate::very_unstable::span::Span;
+
+pub(crate) struct Converter<'tcx> {
+    pub(crate) tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Converter<'tcx> {
+    /// Returns the item for `def_id`, if it is a function-like item with a body.
+    pub(crate) fn crate_item(&self, def_id: DefId) -> Option<CrateItem> {
+        let kind = match self.tcx.def_kind(def_id) {
+            DefKind::Fn => ItemKind::Fn,
+            DefKind::AssocFn => ItemKind::AssocFn,
+            DefKind::Closure => ItemKind::Closure,
+            DefKind::Generator => ItemKind::Generator,
+            _ => return None,
+        };
+        Some(CrateItem {
+            path: self.item_path(def_id),
+            kind,
+            span: self.span(self.tcx.def_span(def_id)),
+        })
+    }
+
+    pub(crate) fn body(&self, body: &mir::Body<'tcx>) -> smir::Body {
+        let mut locals: Vec<_> = body
+            .local_decls
+            .iter()
+            .map(|decl| smir::LocalDecl {
+                ty: self.ty(decl.ty),
+                mutability: self.mutability(decl.mutability),
+                name: None,
+            })
+            .collect();
+        for var in &body.var_debuginfo {
+            if let mir::VarDebugInfoContents::Place(place) = var.value {
+                if let Some(local) = place.as_local() {
+                    locals[local.as_usize()].name.get_or_insert_with(|| var.name.to_string());
+                }
+            }
+        }
+
+        smir::Body {
+            blocks: body
+                .basic_blocks
+                .iter()
+                .map(|block| smir::BasicBlock {
+                    statements: block
+                        .statements
+                        .iter()
+                        .map(|statement| self.statement(statement))
+                        .collect(),
+                    terminator: self.terminator(block.terminator()),
+                    is_cleanup: block.is_cleanup,
+                })
+                .collect(),
+            locals,
+            arg_count: body.arg_count,
+            span: self.span(body.span),
+        }
+    }
+
+    fn item_path(&self, def_id: DefId) -> ItemPath {
+        format!(
+            "{}{}",
+            self.tcx.crate_name(def_id.krate),
+            self.tcx.def_path(def_id).to_string_no_crate_verbose()
+        )
+    }
+
+    fn span(&self, span: Span) -> stable_mir::Span {
+        let source_map = self.tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        stable_mir::Span {
+            file: source_map.filename_for_diagnostics(&lo.file.name).to_string(),
+            start_line: lo.line,
+            start_col: lo.col.0 + 1,
+            end_line: hi.line,
+            end_col: hi.col.0 + 1,
+        }
+    }
+
+    fn statement(&self, statement: &mir::Statement<'tcx>) -> smir::Statement {
+        use mir::StatementKind::*;
+        let kind = match statement.kind {
+            Assign(ref assign) => {
+                let (ref place, ref rvalue) = **assign;
+                smir::StatementKind::Assign(self.place(place), self.rvalue(rvalue))
+            }
+            SetDiscriminant { ref place, variant_index } => smir::StatementKind::SetDiscriminant {
+                place: self.place(place),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(ref place) => smir::StatementKind::Deinit(self.place(place)),
+            StorageLive(local) => smir::StatementKind::StorageLive(local.as_usize()),
+            StorageDead(local) => smir::StatementKind::StorageDead(local.as_usize()),
+            Intrinsic(ref intrinsic) => match **intrinsic {
+                mir::NonDivergingIntrinsic::Assume(ref operand) => {
+                    smir::StatementKind::Assume(self.operand(operand))
+                }
+                mir::NonDivergingIntrinsic::CopyNonOverlapping(ref copy) => {
+                    smir::StatementKind::CopyNonOverlapping {
+                        src: self.operand(&copy.src),
+                        dst: self.operand(&copy.dst),
+                        count: self.operand(&copy.count),
+                    }
+                }
+            },
+            FakeRead(..) | Retag(..) | AscribeUserType(..) | Coverage(..) | Nop => {
+                smir::StatementKind::Nop
+            }
+        };
+        smir::Statement { kind, span: self.span(statement.source_info.span) }
+    }
+
+    fn terminator(&self, terminator: &mir::Terminator<'tcx>) -> smir::Terminator {
+        use mir::TerminatorKind::*;
+        let block = |block: mir::BasicBlock| block.as_usize();
+        let kind = match terminator.kind {
+            Goto { target } => smir::TerminatorKind::Goto { target: block(target) },
+            SwitchInt { ref discr, switch_ty: _, ref targets } => smir::TerminatorKind::SwitchInt {
+                discr: self.operand(discr),
+                targets: targets.iter().map(|(value, target)| (value, block(target))).collect(),
+                otherwise: block(targets.otherwise()),
+            },
+            Resume => smir::TerminatorKind::Resume,
+            Abort => smir::TerminatorKind::Abort,
+            Return => smir::TerminatorKind::Return,
+            Unreachable => smir::TerminatorKind::Unreachable,
+            Drop { ref place, target, unwind } => smir::TerminatorKind::Drop {
+                place: self.place(place),
+                target: block(target),
+                unwind: unwind.map(block),
+            },
+            DropAndReplace { ref place, ref value, target, unwind } => {
+                smir::TerminatorKind::DropAndReplace {
+                    place: self.place(place),
+                    value: self.operand(value),
+                    target: block(target),
+                    unwind: unwind.map(block),
+                }
+            }
+            Call { ref func, ref args, ref destination, target, cleanup, .. } => {
+                smir::TerminatorKind::Call {
+                    func: self.operand(func),
+                    args: args.iter().map(|arg| self.operand(arg)).collect(),
+                    destination: self.place(destination),
+                    target: target.map(block),
+                    cleanup: cleanup.map(block),
+                }
+            }
+            Assert { ref cond, expected, ref msg, target, cleanup } => {
+                smir::TerminatorKind::Assert {
+                    cond: self.operand(cond),
+                    expected,
+                    msg: self.assert_message(msg),
+                    target: block(target),
+                    cleanup: cleanup.map(block),
+                }
+            }
+            Yield { ref value, resume, ref resume_arg, drop } => smir::TerminatorKind::Yield {
+                value: self.operand(value),
+                resume: block(resume),
+                resume_arg: self.place(resume_arg),
+                drop: drop.map(block),
+            },
+            GeneratorDrop => smir::TerminatorKind::GeneratorDrop,
+            // These only exist before drop elaboration, and only ever
+            // continue at their real target.
+            FalseEdge { real_target, .. } | FalseUnwind { real_target, .. } => {
+                smir::TerminatorKind::Goto { target: block(real_target) }
+            }
+            InlineAsm { destination, cleanup, .. } => smir::TerminatorKind::InlineAsm {
+                destination: destination.map(block),
+                cleanup: cleanup.map(block),
+            },
+        };
+        smir::Terminator { kind, span: self.span(terminator.source_info.span) }
+    }
+
+    fn assert_message(&self, msg: &mir::AssertMessage<'tcx>) -> smir::AssertMessage {
+        use mir::AssertKind::*;
+        match *msg {
+            BoundsCheck { ref len, ref index } => smir::AssertMessage::BoundsCheck {
+                len: self.operand(len),
+                index: self.operand(index),
+            },
+            Overflow(op, ref l, ref r) => {
+                smir::AssertMessage::Overflow(self.bin_op(op), self.operand(l), self.operand(r))
+            }
+            OverflowNeg(ref op) => smir::AssertMessage::OverflowNeg(self.operand(op)),
+            DivisionByZero(ref op) => smir::AssertMessage::DivisionByZero(self.operand(op)),
+            RemainderByZero(ref op) => smir::AssertMessage::RemainderByZero(self.operand(op)),
+            ResumedAfterReturn(_) => smir::AssertMessage::ResumedAfterReturn,
+            ResumedAfterPanic(_) => smir::AssertMessage::ResumedAfterPanic,
+        }
+    }
+
+    fn place(&self, place: &mir::Place<'tcx>) -> smir::Place {
+        use mir::ProjectionElem::*;
+        smir::Place {
+            local: place.local.as_usize(),
+            projection: place
+                .projection
+                .iter()
+                .map(|elem| match elem {
+                    Deref => smir::ProjectionElem::Deref,
+                    Field(field, ty) => smir::ProjectionElem::Field(field.as_usize(), self.ty(ty)),
+                    Index(local) => smir::ProjectionElem::Index(local.as_usize()),
+                    ConstantIndex { offset, min_length, from_end } => {
+                        smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                    }
+                    Subslice { from, to, from_end } => {
+                        smir::ProjectionElem::Subslice { from, to, from_end }
+                    }
+                    Downcast(_, variant) => smir::ProjectionElem::Downcast(variant.as_usize()),
+                    OpaqueCast(ty) => smir::ProjectionElem::OpaqueCast(self.ty(ty)),
+                })
+                .collect(),
+        }
+    }
+
+    fn operand(&self, operand: &mir::Operand<'tcx>) -> smir::Operand {
+        match *operand {
+            mir::Operand::Copy(ref place) => smir::Operand::Copy(self.place(place)),
+            mir::Operand::Move(ref place) => smir::Operand::Move(self.place(place)),
+            mir::Operand::Constant(ref constant) => {
+                smir::Operand::Constant(self.constant(constant))
+            }
+        }
+    }
+
+    fn rvalue(&self, rvalue: &mir::Rvalue<'tcx>) -> smir::Rvalue {
+        use mir::Rvalue::*;
+        match *rvalue {
+            Use(ref op) => smir::Rvalue::Use(self.operand(op)),
+            Repeat(ref op, count) => smir::Rvalue::Repeat(self.operand(op), self.ty_const(count)),
+            Ref(_, kind, ref place) => {
+                let kind = match kind {
+                    mir::BorrowKind::Shared => smir::BorrowKind::Shared,
+                    mir::BorrowKind::Shallow => smir::BorrowKind::Shallow,
+                    mir::BorrowKind::Unique => smir::BorrowKind::Unique,
+                    mir::BorrowKind::Mut { allow_two_phase_borrow } => {
+                        smir::BorrowKind::Mut { allow_two_phase_borrow }
+                    }
+                };
+                smir::Rvalue::Ref(kind, self.place(place))
+            }
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(self.item_path(def_id)),
+            AddressOf(mutability, ref place) => {
+                smir::Rvalue::AddressOf(self.mutability(mutability), self.place(place))
+            }
+            Len(ref place) => smir::Rvalue::Len(self.place(place)),
+            Cast(kind, ref op, ty) => {
+                smir::Rvalue::Cast(self.cast_kind(kind), self.operand(op), self.ty(ty))
+            }
+            BinaryOp(op, ref operands) => {
+                let (ref l, ref r) = **operands;
+                smir::Rvalue::BinaryOp(self.bin_op(op), self.operand(l), self.operand(r))
+            }
+            CheckedBinaryOp(op, ref operands) => {
+                let (ref l, ref r) = **operands;
+                smir::Rvalue::CheckedBinaryOp(self.bin_op(op), self.operand(l), self.operand(r))
+            }
+            NullaryOp(op, ty) => {
+                let op = match op {
+                    mir::NullOp::SizeOf => smir::NullOp::SizeOf,
+                    mir::NullOp::AlignOf => smir::NullOp::AlignOf,
+                };
+                smir::Rvalue::NullaryOp(op, self.ty(ty))
+            }
+            UnaryOp(op, ref operand) => {
+                let op = match op {
+                    mir::UnOp::Not => smir::UnOp::Not,
+                    mir::UnOp::Neg => smir::UnOp::Neg,
+                };
+                smir::Rvalue::UnaryOp(op, self.operand(operand))
+            }
+            Discriminant(ref place) => smir::Rvalue::Discriminant(self.place(place)),
+            Aggregate(ref kind, ref operands) => {
+                let kind = match **kind {
+                    mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(self.ty(ty)),
+                    mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+                    mir::AggregateKind::Adt(def_id, variant, substs, _, active_field) => {
+                        smir::AggregateKind::Adt(
+                            self.item_path(def_id),
+                            variant.as_usize(),
+                            self.generic_args(substs),
+                            active_field,
+                        )
+                    }
+                    mir::AggregateKind::Closure(def_id, substs) => smir::AggregateKind::Closure(
+                        self.item_path(def_id.to_def_id()),
+                        self.generic_args(substs),
+                    ),
+                    mir::AggregateKind::Generator(def_id, substs, _) => {
+                        smir::AggregateKind::Generator(
+                            self.item_path(def_id.to_def_id()),
+                            self.generic_args(substs),
+                        )
+                    }
+                };
+                smir::Rvalue::Aggregate(kind, operands.iter().map(|op| self.operand(op)).collect())
+            }
+            ShallowInitBox(ref op, ty) => {
+                smir::Rvalue::ShallowInitBox(self.operand(op), self.ty(ty))
+            }
+            CopyForDeref(ref place) => smir::Rvalue::CopyForDeref(self.place(place)),
+        }
+    }
+
+    fn cast_kind(&self, kind: mir::CastKind) -> smir::CastKind {
+        use mir::CastKind::*;
+        match kind {
+            PointerExposeAddress => smir::CastKind::PointerExposeAddress,
+            PointerFromExposedAddress => smir::CastKind::PointerFromExposedAddress,
+            Pointer(cast) => smir::CastKind::Pointer(match cast {
+                PointerCast::ReifyFnPointer => smir::PointerCast::ReifyFnPointer,
+                PointerCast::UnsafeFnPointer => smir::PointerCast::UnsafeFnPointer,
+                PointerCast::ClosureFnPointer(_) => smir::PointerCast::ClosureFnPointer,
+                PointerCast::MutToConstPointer => smir::PointerCast::MutToConstPointer,
+                PointerCast::ArrayToPointer => smir::PointerCast::ArrayToPointer,
+                PointerCast::Unsize => smir::PointerCast::Unsize,
+            }),
+            DynStar => smir::CastKind::DynStar,
+            IntToInt => smir::CastKind::IntToInt,
+            FloatToInt => smir::CastKind::FloatToInt,
+            FloatToFloat => smir::CastKind::FloatToFloat,
+            IntToFloat => smir::CastKind::IntToFloat,
+            PtrToPtr => smir::CastKind::PtrToPtr,
+            FnPtrToPtr => smir::CastKind::FnPtrToPtr,
+        }
+    }
+
+    fn bin_op(&self, op: mir::BinOp) -> smir::BinOp {
+        use mir::BinOp::*;
+        match op {
+            Add => smir::BinOp::Add,
+            Sub => smir::BinOp::Sub,
+            Mul => smir::BinOp::Mul,
+            Div => smir::BinOp::Div,
+            Rem => smir::BinOp::Rem,
+            BitXor => smir::BinOp::BitXor,
+            BitAnd => smir::BinOp::BitAnd,
+            BitOr => smir::BinOp::BitOr,
+            Shl => smir::BinOp::Shl,
+            Shr => smir::BinOp::Shr,
+            Eq => smir::BinOp::Eq,
+            Lt => smir::BinOp::Lt,
+            Le => smir::BinOp::Le,
+            Ne => smir::BinOp::Ne,
+            Ge => smir::BinOp::Ge,
+            Gt => smir::BinOp::Gt,
+            Offset => smir::BinOp::Offset,
+        }
+    }
+
+    fn mutability(&self, mutability: mir::Mutability) -> sty::Mutability {
+        match mutability {
+            mir::Mutability::Not => sty::Mutability::Not,
+            mir::Mutability::Mut => sty::Mutability::Mut,
+        }
+    }
+
+    fn ty(&self, ty: ty::Ty<'tcx>) -> sty::Ty {
+        match *ty.kind() {
+            ty::Bool => sty::Ty::Bool,
+            ty::Char => sty::Ty::Char,
+            ty::Int(int) => sty::Ty::Int(match int {
+                ty::IntTy::Isize => sty::IntTy::Isize,
+                ty::IntTy::I8 => sty::IntTy::I8,
+                ty::IntTy::I16 => sty::IntTy::I16,
+                ty::IntTy::I32 => sty::IntTy::I32,
+                ty::IntTy::I64 => sty::IntTy::I64,
+                ty::IntTy::I128 => sty::IntTy::I128,
+            }),
+            ty::Uint(uint) => sty::Ty::Uint(match uint {
+                ty::UintTy::Usize => sty::UintTy::Usize,
+                ty::UintTy::U8 => sty::UintTy::U8,
+                ty::UintTy::U16 => sty::UintTy::U16,
+                ty::UintTy::U32 => sty::UintTy::U32,
+                ty::UintTy::U64 => sty::UintTy::U64,
+                ty::UintTy::U128 => sty::UintTy::U128,
+            }),
+            ty::Float(float) => sty::Ty::Float(match float {
+                ty::FloatTy::F32 => sty::FloatTy::F32,
+                ty::FloatTy::F64 => sty::FloatTy::F64,
+            }),
+            ty::Str => sty::Ty::Str,
+            ty::Never => sty::Ty::Never,
+            ty::Adt(adt_def, substs) => {
+                sty::Ty::Adt(self.item_path(adt_def.did()), self.generic_args(substs))
+            }
+            ty::Foreign(def_id) => sty::Ty::Foreign(self.item_path(def_id)),
+            ty::Array(ty, len) => {
+                sty::Ty::Array(Box::new(self.ty(ty)), Box::new(self.ty_const(len)))
+            }
+            ty::Slice(ty) => sty::Ty::Slice(Box::new(self.ty(ty))),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::Ty::RawPtr(Box::new(self.ty(ty)), self.mutability(mutbl))
+            }
+            ty::Ref(_, ty, mutbl) => sty::Ty::Ref(Box::new(self.ty(ty)), self.mutability(mutbl)),
+            ty::FnDef(def_id, substs) => {
+                sty::Ty::FnDef(self.item_path(def_id), self.generic_args(substs))
+            }
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                sty::Ty::FnPtr(sty::FnSig {
+                    inputs: sig.inputs().iter().map(|&ty| self.ty(ty)).collect(),
+                    output: Box::new(self.ty(sig.output())),
+                    c_variadic: sig.c_variadic,
+                    is_unsafe: sig.unsafety == Unsafety::Unsafe,
+                })
+            }
+            ty::Dynamic(predicates, ..) => sty::Ty::Dynamic {
+                principal: predicates.principal_def_id().map(|def_id| self.item_path(def_id)),
+                auto_traits: predicates
+                    .auto_traits()
+                    .map(|def_id| self.item_path(def_id))
+                    .collect(),
+            },
+            ty::Closure(def_id, substs) => {
+                sty::Ty::Closure(self.item_path(def_id), self.generic_args(substs))
+            }
+            ty::Generator(def_id, substs, _) => {
+                sty::Ty::Generator(self.item_path(def_id), self.generic_args(substs))
+            }
+            ty::Tuple(tys) => sty::Ty::Tuple(tys.iter().map(|ty| self.ty(ty)).collect()),
+            ty::Param(param) => sty::Ty::Param { index: param.index, name: param.name.to_string() },
+            ty::GeneratorWitness(..)
+            | ty::Projection(..)
+            | ty::Opaque(..)
+            | ty::Bound(..)
+            | ty::Placeholder(..)
+            | ty::Infer(..)
+            | ty::Error(_) => sty::Ty::Unsupported(with_no_trimmed_paths!(ty.to_string())),
+        }
+    }
+
+    fn generic_args(&self, substs: ty::SubstsRef<'tcx>) -> Vec<sty::GenericArg> {
+        substs
+            .iter()
+            .map(|arg| match arg.unpack() {
+                ty::GenericArgKind::Lifetime(_) => sty::GenericArg::Lifetime,
+                ty::GenericArgKind::Type(ty) => sty::GenericArg::Type(self.ty(ty)),
+                ty::GenericArgKind::Const(ct) => sty::GenericArg::Const(self.ty_const(ct)),
+            })
+            .collect()
+    }
+
+    fn ty_const(&self, ct: ty::Const<'tcx>) -> sty::Const {
+        let kind = match ct.kind() {
+            ty::ConstKind::Value(ty::ValTree::Leaf(int)) => self.scalar(int),
+            ty::ConstKind::Param(param) => {
+                sty::ConstKind::Param { index: param.index, name: param.name.to_string() }
+            }
+            ty::ConstKind::Unevaluated(uv) => {
+                sty::ConstKind::Unevaluated(self.item_path(uv.def.did))
+            }
+            _ => sty::ConstKind::Unsupported(with_no_trimmed_paths!(ct.to_string())),
+        };
+        sty::Const { ty: self.ty(ct.ty()), kind }
+    }
+
+    fn constant(&self, constant: &mir::Constant<'tcx>) -> sty::Const {
+        let kind = match constant.literal {
+            mir::ConstantKind::Ty(ct) => return self.ty_const(ct),
+            mir::ConstantKind::Unevaluated(uv, _) => {
+                sty::ConstKind::Unevaluated(self.item_path(uv.def.did))
+            }
+            mir::ConstantKind::Val(ConstValue::Scalar(Scalar::Int(int)), _) => self.scalar(int),
+            mir::ConstantKind::Val(ConstValue::ZeroSized, _) => sty::ConstKind::ZeroSized,
+            mir::ConstantKind::Val(..) => {
+                sty::ConstKind::Unsupported(with_no_trimmed_paths!(constant.to_string()))
+            }
+        };
+        sty::Const { ty: self.ty(constant.ty()), kind }
+    }
+
+    fn scalar(&self, int: ScalarInt) -> sty::ConstKind {
+        sty::ConstKind::Scalar { bits: int.assert_bits(int.size()), size: int.size().bytes() }
+    }
+}
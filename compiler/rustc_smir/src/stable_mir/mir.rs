@@ -0,0 +1,253 @@
+use super::ty::{Const, GenericArg, Mutability, Ty};
+use super::{ItemPath, Span};
+
+/// The index of a local in [`Body::locals`].
+pub type Local = usize;
+
+/// The index of a basic block in [`Body::blocks`].
+pub type BasicBlockIdx = usize;
+
+/// The optimized MIR of a function, closure or generator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Body {
+    pub blocks: Vec<BasicBlock>,
+    /// The return place is local 0, followed by the `arg_count` arguments
+    /// and then by all other user variables and temporaries.
+    pub locals: Vec<LocalDecl>,
+    pub arg_count: usize,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LocalDecl {
+    pub ty: Ty,
+    pub mutability: Mutability,
+    /// The name of the user variable this local holds, if any.
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BasicBlock {
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+    pub is_cleanup: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+/// The statements that can appear in optimized MIR. Statements that have no
+/// effect on the behavior of the program, like coverage counters or retags,
+/// are represented as `Nop`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    Assign(Place, Rvalue),
+    SetDiscriminant { place: Place, variant_index: usize },
+    Deinit(Place),
+    StorageLive(Local),
+    StorageDead(Local),
+    Assume(Operand),
+    CopyNonOverlapping { src: Operand, dst: Operand, count: Operand },
+    Nop,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TerminatorKind {
+    Goto {
+        target: BasicBlockIdx,
+    },
+    SwitchInt {
+        discr: Operand,
+        targets: Vec<(u128, BasicBlockIdx)>,
+        otherwise: BasicBlockIdx,
+    },
+    Resume,
+    Abort,
+    Return,
+    Unreachable,
+    Drop {
+        place: Place,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    DropAndReplace {
+        place: Place,
+        value: Operand,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    Call {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        target: Option<BasicBlockIdx>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    Assert {
+        cond: Operand,
+        expected: bool,
+        msg: AssertMessage,
+        target: BasicBlockIdx,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    Yield {
+        value: Operand,
+        resume: BasicBlockIdx,
+        resume_arg: Place,
+        drop: Option<BasicBlockIdx>,
+    },
+    GeneratorDrop,
+    InlineAsm {
+        destination: Option<BasicBlockIdx>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AssertMessage {
+    BoundsCheck { len: Operand, index: Operand },
+    Overflow(BinOp, Operand, Operand),
+    OverflowNeg(Operand),
+    DivisionByZero(Operand),
+    RemainderByZero(Operand),
+    ResumedAfterReturn,
+    ResumedAfterPanic,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Place {
+    pub local: Local,
+    pub projection: Vec<ProjectionElem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProjectionElem {
+    Deref,
+    /// A field of a struct, tuple, closure or of the variant selected by a
+    /// preceding `Downcast`, with the type of the field.
+    Field(usize, Ty),
+    Index(Local),
+    ConstantIndex {
+        offset: u64,
+        min_length: u64,
+        from_end: bool,
+    },
+    Subslice {
+        from: u64,
+        to: u64,
+        from_end: bool,
+    },
+    Downcast(usize),
+    OpaqueCast(Ty),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Copy(Place),
+    Move(Place),
+    Constant(Const),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rvalue {
+    Use(Operand),
+    Repeat(Operand, Const),
+    Ref(BorrowKind, Place),
+    ThreadLocalRef(ItemPath),
+    AddressOf(Mutability, Place),
+    Len(Place),
+    Cast(CastKind, Operand, Ty),
+    BinaryOp(BinOp, Operand, Operand),
+    CheckedBinaryOp(BinOp, Operand, Operand),
+    NullaryOp(NullOp, Ty),
+    UnaryOp(UnOp, Operand),
+    Discriminant(Place),
+    Aggregate(AggregateKind, Vec<Operand>),
+    ShallowInitBox(Operand, Ty),
+    CopyForDeref(Place),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BorrowKind {
+    Shared,
+    Shallow,
+    Unique,
+    Mut { allow_two_phase_borrow: bool },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CastKind {
+    PointerExposeAddress,
+    PointerFromExposedAddress,
+    Pointer(PointerCast),
+    DynStar,
+    IntToInt,
+    FloatToInt,
+    FloatToFloat,
+    IntToFloat,
+    PtrToPtr,
+    FnPtrToPtr,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointerCast {
+    ReifyFnPointer,
+    UnsafeFnPointer,
+    ClosureFnPointer,
+    MutToConstPointer,
+    ArrayToPointer,
+    Unsize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitXor,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    Eq,
+    Lt,
+    Le,
+    Ne,
+    Ge,
+    Gt,
+    Offset,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NullOp {
+    SizeOf,
+    AlignOf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AggregateKind {
+    Array(Ty),
+    Tuple,
+    /// An ADT, the index of the variant, its generic arguments and, for
+    /// unions, the index of the initialized field.
+    Adt(ItemPath, usize, Vec<GenericArg>, Option<usize>),
+    Closure(ItemPath, Vec<GenericArg>),
+    Generator(ItemPath, Vec<GenericArg>),
+}
@@ -0,0 +1,53 @@
+//! Module that implements the public interface to the Stable MIR.
+//!
+//! This module shall contain all type definitions and APIs that we expect 3P tools to invoke to
+//! interact with the compiler.
+//!
+//! The goal is to eventually move this module to its own crate which shall be published on
+//! [crates.io](https://crates.io).
+//!
+//! All types in this module are owned and do not borrow from the compiler, so tools can keep
+//! them around after the compilation session has ended.
+
+pub mod mir;
+pub mod ty;
+
+/// The version of the types in this module.
+///
+/// It is increased whenever a type changes in a way that can break a tool
+/// that constructs it or matches on it.
+pub const VERSION: u32 = 1;
+
+/// A path that identifies an item across compilation sessions, starting with
+/// the name of its crate, e.g. `core::option::Option` or `my_crate::{impl#0}::new`.
+pub type ItemPath = String;
+
+/// A function-like item of the local crate that has a body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CrateItem {
+    pub path: ItemPath,
+    pub kind: ItemKind,
+    pub span: Span,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ItemKind {
+    Fn,
+    AssocFn,
+    Closure,
+    Generator,
+}
+
+/// A source location, with 1-based lines and columns.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub file: String,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+/// The compilation failed; its errors have already been emitted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CompilerError;
@@ -0,0 +1,110 @@
+use super::ItemPath;
+
+/// A monomorphic or generic type. Lifetimes are not represented.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Ty {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Str,
+    Never,
+    Adt(ItemPath, Vec<GenericArg>),
+    Foreign(ItemPath),
+    Array(Box<Ty>, Box<Const>),
+    Slice(Box<Ty>),
+    RawPtr(Box<Ty>, Mutability),
+    Ref(Box<Ty>, Mutability),
+    FnDef(ItemPath, Vec<GenericArg>),
+    FnPtr(FnSig),
+    Dynamic {
+        principal: Option<ItemPath>,
+        auto_traits: Vec<ItemPath>,
+    },
+    Closure(ItemPath, Vec<GenericArg>),
+    Generator(ItemPath, Vec<GenericArg>),
+    Tuple(Vec<Ty>),
+    Param {
+        index: u32,
+        name: String,
+    },
+    /// A type that has no stable representation yet, such as an associated
+    /// type projection or an opaque type, in its printed form.
+    Unsupported(String),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Box<Ty>,
+    pub c_variadic: bool,
+    pub is_unsafe: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GenericArg {
+    Lifetime,
+    Type(Ty),
+    Const(Const),
+}
+
+/// A constant, either in a type (like the length of an array) or in MIR.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Const {
+    pub ty: Ty,
+    pub kind: ConstKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstKind {
+    /// An integer, boolean, character or float, as the bits of its `size`
+    /// bytes long representation.
+    Scalar {
+        bits: u128,
+        size: u64,
+    },
+    /// The value of a zero-sized type, such as a function item.
+    ZeroSized,
+    Param {
+        index: u32,
+        name: String,
+    },
+    /// A named or anonymous constant that has not been evaluated yet.
+    Unevaluated(ItemPath),
+    /// A constant that has no stable representation yet, such as a
+    /// reference to an allocation, in its printed form.
+    Unsupported(String),
+}
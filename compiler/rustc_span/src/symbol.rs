@@ -991,6 +991,7 @@
         no_link,
         no_main,
         no_mangle,
+        no_panic,
         no_sanitize,
         no_stack_check,
         no_start,
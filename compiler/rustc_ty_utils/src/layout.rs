@@ -5,10 +5,14 @@
 use rustc_middle::ty::layout::{
     IntegerExt, LayoutCx, LayoutError, LayoutOf, TyAndLayout, MAX_SIMD_LANES,
 };
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{
     self, subst::SubstsRef, EarlyBinder, ReprOptions, Ty, TyCtxt, TypeVisitable,
 };
-use rustc_session::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+use rustc_session::{
+    DataTypeKind, DiscriminantEncoding, DiscriminantInfo, FieldInfo, NicheInfo, SizeKind,
+    VariantInfo,
+};
 use rustc_span::symbol::Symbol;
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::*;
@@ -1673,9 +1677,11 @@ fn generator_layout<'tcx>(
 /// layout of each type.
 #[inline(always)]
 fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: TyAndLayout<'tcx>) {
-    // If we are running with `-Zprint-type-sizes`, maybe record layouts
-    // for dumping later.
-    if cx.tcx.sess.opts.unstable_opts.print_type_sizes {
+    // If we are running with `-Zprint-type-sizes` or `-Zprint-type-sizes-json`,
+    // maybe record layouts for dumping later.
+    if cx.tcx.sess.opts.unstable_opts.print_type_sizes
+        || cx.tcx.sess.opts.unstable_opts.print_type_sizes_json
+    {
         record_layout_for_printing_outlined(cx, layout)
     }
 }
@@ -1692,15 +1698,23 @@ fn record_layout_for_printing_outlined<'tcx>(
     }
 
     // (delay format until we actually need it)
-    let record = |kind, packed, opt_discr_size, variants| {
+    let record = |kind, packed, discriminant, variants| {
         let type_desc = format!("{:?}", layout.ty);
+        let niche = layout.largest_niche.map(|niche| NicheInfo {
+            offset: niche.offset.bytes(),
+            size: niche.size(cx).bytes(),
+            valid_range_start: niche.valid_range.start,
+            valid_range_end: niche.valid_range.end,
+        });
         cx.tcx.sess.code_stats.record_type_size(
             kind,
             type_desc,
+            type_path(cx.tcx, layout.ty),
             layout.align.abi,
             layout.size,
             packed,
-            opt_discr_size,
+            discriminant,
+            niche,
             variants,
         );
     };
@@ -1713,7 +1727,28 @@ fn record_layout_for_printing_outlined<'tcx>(
 
         ty::Closure(..) => {
             debug!("print-type-size t: `{:?}` record closure", layout.ty);
-            record(DataTypeKind::Closure, false, None, vec![]);
+            // Closures are laid out like tuples of their captures.
+            let fields = (0..layout.fields.count()).map(|i| (Symbol::intern(&i.to_string()), i));
+            record(
+                DataTypeKind::Closure,
+                false,
+                None,
+                vec![variant_info(cx, None, fields, layout)],
+            );
+            return;
+        }
+
+        ty::Generator(def_id, substs, _) => {
+            debug!("print-type-size t: `{:?}` record generator", layout.ty);
+            let discriminant = discriminant_info(cx, layout, |index| {
+                Symbol::intern(&ty::GeneratorSubsts::variant_name(index))
+            });
+            record(
+                DataTypeKind::Generator,
+                false,
+                discriminant,
+                variant_info_for_generator(cx, layout, def_id, substs),
+            );
             return;
         }
 
@@ -1727,33 +1762,7 @@ fn record_layout_for_printing_outlined<'tcx>(
     let adt_packed = adt_def.repr().pack.is_some();
 
     let build_variant_info = |n: Option<Symbol>, flds: &[Symbol], layout: TyAndLayout<'tcx>| {
-        let mut min_size = Size::ZERO;
-        let field_info: Vec<_> = flds
-            .iter()
-            .enumerate()
-            .map(|(i, &name)| {
-                let field_layout = layout.field(cx, i);
-                let offset = layout.fields.offset(i);
-                let field_end = offset + field_layout.size;
-                if min_size < field_end {
-                    min_size = field_end;
-                }
-                FieldInfo {
-                    name,
-                    offset: offset.bytes(),
-                    size: field_layout.size.bytes(),
-                    align: field_layout.align.abi.bytes(),
-                }
-            })
-            .collect();
-
-        VariantInfo {
-            name: n,
-            kind: if layout.is_unsized() { SizeKind::Min } else { SizeKind::Exact },
-            align: layout.align.abi.bytes(),
-            size: if min_size.bytes() == 0 { layout.size.bytes() } else { min_size.bytes() },
-            fields: field_info,
-        }
+        variant_info(cx, n, flds.iter().copied().enumerate().map(|(i, name)| (name, i)), layout)
     };
 
     match layout.variants {
@@ -1775,7 +1784,7 @@ fn record_layout_for_printing_outlined<'tcx>(
             }
         }
 
-        Variants::Multiple { tag, ref tag_encoding, .. } => {
+        Variants::Multiple { .. } => {
             debug!(
                 "print-type-size `{:#?}` adt general variants def {}",
                 layout.ty,
@@ -1792,12 +1801,135 @@ fn record_layout_for_printing_outlined<'tcx>(
             record(
                 adt_kind.into(),
                 adt_packed,
-                match tag_encoding {
-                    TagEncoding::Direct => Some(tag.size(cx)),
-                    _ => None,
-                },
+                discriminant_info(cx, layout, |index| adt_def.variant(index).name),
                 variant_infos,
             );
         }
     }
 }
+
+/// The path of `ty` that `-Zprint-type-sizes-json` sorts by. Closures
+/// and generators are identified by their definition rather than their span.
+fn type_path<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> String {
+    with_no_trimmed_paths!(match *ty.kind() {
+        ty::Closure(def_id, substs) | ty::Generator(def_id, substs, _) => {
+            tcx.def_path_str_with_substs(def_id, substs)
+        }
+        _ => ty.to_string(),
+    })
+}
+
+fn variant_info<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    name: Option<Symbol>,
+    fields: impl Iterator<Item = (Symbol, usize)>,
+    layout: TyAndLayout<'tcx>,
+) -> VariantInfo {
+    let mut min_size = Size::ZERO;
+    let field_info: Vec<_> = fields
+        .map(|(name, i)| {
+            let field_layout = layout.field(cx, i);
+            let offset = layout.fields.offset(i);
+            let field_end = offset + field_layout.size;
+            if min_size < field_end {
+                min_size = field_end;
+            }
+            FieldInfo {
+                name,
+                offset: offset.bytes(),
+                size: field_layout.size.bytes(),
+                align: field_layout.align.abi.bytes(),
+            }
+        })
+        .collect();
+
+    VariantInfo {
+        name,
+        kind: if layout.is_unsized() { SizeKind::Min } else { SizeKind::Exact },
+        align: layout.align.abi.bytes(),
+        size: if min_size.bytes() == 0 { layout.size.bytes() } else { min_size.bytes() },
+        fields: field_info,
+    }
+}
+
+fn discriminant_info<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+    variant_name: impl Fn(VariantIdx) -> Symbol,
+) -> Option<DiscriminantInfo> {
+    let Variants::Multiple { tag, ref tag_encoding, tag_field, .. } = layout.variants else {
+        return None;
+    };
+    let encoding = match *tag_encoding {
+        TagEncoding::Direct => DiscriminantEncoding::Direct,
+        TagEncoding::Niche { untagged_variant, ref niche_variants, niche_start } => {
+            DiscriminantEncoding::Niche {
+                untagged_variant: variant_name(untagged_variant),
+                niche_variants_start: variant_name(*niche_variants.start()),
+                niche_variants_end: variant_name(*niche_variants.end()),
+                niche_start,
+            }
+        }
+    };
+    Some(DiscriminantInfo {
+        offset: layout.fields.offset(tag_field).bytes(),
+        size: tag.size(cx).bytes(),
+        encoding,
+    })
+}
+
+/// Every variant of a generator contains its upvars, followed by the locals
+/// that are saved across the corresponding suspension point.
+fn variant_info_for_generator<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+    def_id: hir::def_id::DefId,
+    substs: SubstsRef<'tcx>,
+) -> Vec<VariantInfo> {
+    let Variants::Multiple { tag, ref tag_encoding, tag_field, .. } = layout.variants else {
+        return vec![];
+    };
+    let Some(generator) = cx.tcx.generator_layout(def_id) else {
+        return vec![];
+    };
+
+    let upvars = variant_info(
+        cx,
+        None,
+        (0..substs.as_generator().prefix_tys().count())
+            .map(|i| (Symbol::intern(&format!("upvar{i}")), i)),
+        layout,
+    );
+    let upvars_size =
+        upvars.fields.iter().map(|field| field.offset + field.size).max().unwrap_or(0);
+
+    generator
+        .variant_fields
+        .iter_enumerated()
+        .map(|(variant_index, locals)| {
+            let mut info = variant_info(
+                cx,
+                Some(Symbol::intern(&ty::GeneratorSubsts::variant_name(variant_index))),
+                locals
+                    .iter()
+                    .enumerate()
+                    .map(|(i, local)| (Symbol::intern(&format!("local{}", local.as_usize())), i)),
+                layout.for_variant(cx, variant_index),
+            );
+
+            // `variant_info` falls back to the size of the whole generator for
+            // variants without saved locals, but those only hold the upvars.
+            let mut size = if locals.is_empty() { upvars_size } else { info.size };
+            // The tag is only included in the size of the variant if it is
+            // placed before the saved locals; account for it if it is not.
+            if let TagEncoding::Direct = tag_encoding {
+                if layout.fields.offset(tag_field).bytes() >= size {
+                    size += tag.size(cx).bytes();
+                }
+            }
+            info.size = cmp::max(size, upvars_size);
+            info.fields.extend(upvars.fields.iter().cloned());
+            info
+        })
+        .collect()
+}
@@ -0,0 +1,20 @@
+// unit-test: CopyProp
+
+// EMIT_MIR basic.chain.CopyProp.diff
+fn chain(x: u8) -> u8 {
+    let a = x;
+    let b = a;
+    b
+}
+
+// EMIT_MIR basic.reassign.CopyProp.diff
+fn reassign(mut x: u8) -> u8 {
+    let y = x;
+    x = 5; // `y` must keep the value `x` had before this assignment
+    y
+}
+
+fn main() {
+    chain(0);
+    reassign(0);
+}
@@ -1,5 +1,5 @@
 //! Tests that cyclic assignments don't hang DestinationPropagation, and result in reasonable code.
-// compile-flags: -Zunsound-mir-opts
+// compile-flags: -Zunsound-mir-opts -Zmir-enable-passes=-CopyProp
 fn val() -> i32 {
     1
 }
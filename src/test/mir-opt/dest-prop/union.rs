@@ -1,5 +1,5 @@
 //! Tests that we can propagate into places that are projections into unions
-// compile-flags: -Zunsound-mir-opts
+// compile-flags: -Zunsound-mir-opts -Zmir-enable-passes=-CopyProp
 fn val() -> u32 {
     1
 }
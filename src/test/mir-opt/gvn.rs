@@ -0,0 +1,54 @@
+// unit-test: GVN
+
+// EMIT_MIR gvn.repeated_index.GVN.diff
+fn repeated_index(x: [f32; 2]) -> f32 {
+    let a = x[0];
+    let b = x[1];
+    a*a + b*b
+}
+
+// EMIT_MIR gvn.repeated_binary_op.GVN.diff
+pub fn repeated_binary_op(a: u32, b: u32) -> u32 {
+    (a ^ b) & (a ^ b)
+}
+
+// `x` is borrowed, so the second `a ^ b` can't reuse it.
+// EMIT_MIR gvn.borrowed_local.GVN.diff
+pub fn borrowed_local(a: u32, b: u32) -> u32 {
+    let x = a ^ b;
+    let r = &x;
+    *r & (a ^ b)
+}
+
+// `a` is reassigned, so the two `a ^ b` don't compute the same value.
+// EMIT_MIR gvn.reassigned_local.GVN.diff
+pub fn reassigned_local(mut a: u32, b: u32) -> u32 {
+    let x = a ^ b;
+    a = b;
+    x & (a ^ b)
+}
+
+// The first `a ^ b` is only computed if `c` is true.
+// EMIT_MIR gvn.non_dominating.GVN.diff
+pub fn non_dominating(c: bool, a: u32, b: u32) -> u32 {
+    let x = if c { (a ^ b) & 1 } else { 0 };
+    x | (a ^ b)
+}
+
+// `x` is reassigned, so the length of the slice it points to can change.
+// EMIT_MIR gvn.len_of_reassigned_slice.GVN.diff
+pub fn len_of_reassigned_slice<'a>(mut x: &'a [u32], y: &'a [u32]) -> u32 {
+    let a = x[0];
+    x = y;
+    let b = x[0];
+    a + b
+}
+
+fn main() {
+    repeated_index([3.0, 4.0]);
+    repeated_binary_op(1, 2);
+    borrowed_local(1, 2);
+    reassigned_local(1, 2);
+    non_dominating(true, 1, 2);
+    len_of_reassigned_slice(&[1], &[2]);
+}
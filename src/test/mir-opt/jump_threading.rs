@@ -0,0 +1,31 @@
+#![feature(control_flow_enum)]
+#![feature(try_trait_v2)]
+
+use std::ops::ControlFlow;
+
+// EMIT_MIR jump_threading.too_complex.JumpThreading.diff
+fn too_complex(x: Result<i32, usize>) -> Option<i32> {
+    // Each arm of the inner match sets the discriminant
+    // that the outer match switches on, so the pass
+    // should make both arms jump straight to the arm
+    // of the outer match that they select.
+    match {
+        match x {
+            Ok(v) => ControlFlow::Continue(v),
+            Err(r) => ControlFlow::Break(r),
+        }
+    } {
+        ControlFlow::Continue(v) => Some(v),
+        ControlFlow::Break(r) => None,
+    }
+}
+
+// EMIT_MIR jump_threading.identity.JumpThreading.diff
+fn identity(x: Result<i32, i32>) -> Result<i32, i32> {
+    Ok(x?)
+}
+
+fn main() {
+    too_complex(Ok(0));
+    identity(Ok(0));
+}
@@ -0,0 +1,67 @@
+#![feature(rustc_private)]
+
+//! This program implements a rustc driver that registers a MIR pass at each of the points of
+//! the MIR pipeline where drivers can run passes of their own. The passes don't change the MIR,
+//! they record the bodies they run on in `RAN`, which is printed once the compilation is done.
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+
+use rustc_interface::Config;
+use rustc_middle::mir::{Body, ExtraMirPasses, MirPass, MirPassPoint};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::Session;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// The passes that ran, with the bodies they ran on.
+static RAN: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+fn main() {
+    let exit_code = rustc_driver::catch_with_exit_code(move || {
+        let rustc_args: Vec<_> = std::env::args().collect();
+        rustc_driver::RunCompiler::new(&rustc_args, &mut CompilerCalls).run()
+    });
+    for ran in RAN.lock().unwrap().iter() {
+        println!("{}", ran);
+    }
+    std::process::exit(exit_code);
+}
+
+pub struct CompilerCalls;
+
+impl rustc_driver::Callbacks for CompilerCalls {
+    // In this callback we register the passes.
+    fn config(&mut self, config: &mut Config) {
+        assert!(config.register_mir_passes.is_none());
+        config.register_mir_passes = Some(Box::new(register_mir_passes));
+    }
+}
+
+fn register_mir_passes(_sess: &Session, passes: &mut ExtraMirPasses) {
+    for (point, name) in [
+        (MirPassPoint::AfterBorrowck, "DriverAfterBorrowck"),
+        (MirPassPoint::BeforeInlining, "DriverBeforeInlining"),
+        (MirPassPoint::AfterInlining, "DriverAfterInlining"),
+        (MirPassPoint::PreCodegen, "DriverPreCodegen"),
+    ] {
+        passes.register(point, Box::new(RecordBody(name)));
+    }
+}
+
+/// A pass named after its field, which records the bodies it runs on.
+struct RecordBody(&'static str);
+
+impl<'tcx> MirPass<'tcx> for RecordBody {
+    fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.0)
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let item = tcx.def_path_str(body.source.def_id());
+        RAN.lock().unwrap().insert(format!("{} ran on {}", self.0, item));
+    }
+}
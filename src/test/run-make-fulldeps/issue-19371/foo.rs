@@ -58,6 +58,7 @@ fn compile(code: String, output: PathBuf, sysroot: PathBuf) {
         parse_sess_created: None,
         register_lints: None,
         override_queries: None,
+        register_mir_passes: None,
         make_codegen_backend: None,
         registry: rustc_driver::diagnostics_registry(),
     };
@@ -0,0 +1,55 @@
+#!/usr/bin/env python
+
+# Checks the edges between the items of `deps.rs` in the graph dumped by `-Z dump-item-deps`.
+# The edges to items of other crates depend on the standard library, and are not checked.
+
+import json
+import sys
+
+EXPECTED_EDGES = {
+    ("deps::{impl#0}::greet", "deps::Config", "type"),
+    ("deps::{impl#0}::greet", "deps::GREETING", "static"),
+    ("deps::caller", "deps::Config", "type"),
+    ("deps::caller", "deps::Greet::greet", "call"),
+    ("deps::caller", "deps::{impl#0}::greet", "call"),
+    ("deps::caller", "deps::{impl#0}", "trait_impl"),
+    ("deps::calls_a", "deps::helper_a", "call"),
+    ("deps::calls_b", "deps::helper_b", "call"),
+    ("deps::refers_to_a", "deps::helper_a", "reference"),
+}
+
+EXPECTED_SOURCES = {
+    "deps::GREETING",
+    "deps::{impl#0}::greet",
+    "deps::caller",
+    "deps::helper_a",
+    "deps::helper_b",
+    "deps::calls_a",
+    "deps::calls_b",
+    "deps::refers_to_a",
+    "deps::unused",
+}
+
+with open(sys.argv[1]) as f:
+    graph = json.load(f)
+
+nodes = {node["id"]: node for node in graph["nodes"]}
+edges = set()
+for edge in graph["edges"]:
+    source, target = nodes[edge["from"]], nodes[edge["to"]]
+    if source["local"] and target["local"]:
+        edges.add((source["path"], target["path"], edge["kind"]))
+
+errors = []
+for path in sorted(EXPECTED_SOURCES):
+    if not any(node["path"] == path for node in nodes.values()):
+        errors.append("missing node: `{}`".format(path))
+for edge in sorted(EXPECTED_EDGES - edges):
+    errors.append("missing edge: `{}` -> `{}` ({})".format(*edge))
+for edge in sorted(edges - EXPECTED_EDGES):
+    errors.append("unexpected edge: `{}` -> `{}` ({})".format(*edge))
+
+for error in errors:
+    print("=> {}".format(error))
+if errors:
+    sys.exit(1)
@@ -0,0 +1,43 @@
+#![crate_type = "lib"]
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+pub static GREETING: &str = "hello";
+
+pub trait Greet {
+    fn greet(&self) -> String;
+}
+
+impl Greet for Config {
+    fn greet(&self) -> String {
+        GREETING.to_string()
+    }
+}
+
+pub fn caller(config: &Config) -> String {
+    config.greet()
+}
+
+pub fn helper_a() -> u32 {
+    1
+}
+
+pub fn helper_b() -> u32 {
+    2
+}
+
+pub fn calls_a() -> u32 {
+    helper_a()
+}
+
+pub fn calls_b() -> u32 {
+    helper_b()
+}
+
+pub fn refers_to_a() -> fn() -> u32 {
+    helper_a
+}
+
+pub fn unused() {}
@@ -0,0 +1,11 @@
+#![crate_type = "lib"]
+
+pub fn async() {}
+
+pub mod iter {
+    pub fn try() {}
+
+    pub fn sum() -> i32 {
+        [1, 2, 3].into_iter().map(|x| *x).sum()
+    }
+}
@@ -0,0 +1,21 @@
+extern crate externonly;
+extern crate testitem;
+
+fn main() {
+    used::f();
+    assert_eq!(macros::answer!(), 42);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn f() {
+        testing::f();
+        testitem::f();
+    }
+}
+
+#[cfg(any(test, feature = "bench"))]
+pub fn any_test() {
+    anytest::f();
+}
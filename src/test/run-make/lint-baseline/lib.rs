@@ -0,0 +1,11 @@
+#![crate_type = "lib"]
+
+pub fn old() {
+    #[cfg(not(fixed))]
+    let unused = 1;
+}
+
+#[cfg(new_lint)]
+pub fn new() {
+    let also_unused = 2;
+}
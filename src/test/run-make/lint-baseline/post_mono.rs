@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+#![feature(large_assignments)]
+#![move_size_limit = "1000"]
+
+pub fn large(x: [u8; 2000]) -> ([u8; 2000], u8) {
+    (x, 1)
+}
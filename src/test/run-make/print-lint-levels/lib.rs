@@ -0,0 +1,6 @@
+pub mod inner {
+    #[allow(dead_code)]
+    fn unused() {
+        let _x = 0;
+    }
+}
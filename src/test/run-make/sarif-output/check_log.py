@@ -0,0 +1,76 @@
+#!/usr/bin/env python
+
+# Checks the SARIF log written by `--error-format=sarif` for `lib.rs`: there must be exactly
+# one log, whose results have the locations, rules and fixes code-scanning tools rely on.
+
+import json
+import sys
+
+with open(sys.argv[1]) as f:
+    # Fails if the session wrote more than one log.
+    log = json.load(f)
+
+errors = []
+
+
+def check(condition, message):
+    if not condition:
+        errors.append(message)
+
+
+check(log["version"] == "2.1.0", "unexpected version: {}".format(log["version"]))
+check(len(log["runs"]) == 1, "expected one run, found {}".format(len(log["runs"])))
+run = log["runs"][0]
+rules = run["tool"]["driver"]["rules"]
+results = run["results"]
+
+# The remark warning is emitted before the session exists, and has no location.
+remarks = [r for r in results if "-C remark" in r["message"]["text"]]
+check(len(remarks) == 1, "expected one early warning, found {}".format(len(remarks)))
+
+# No result may come from the summary of the number of errors and warnings.
+for result in results:
+    text = result["message"]["text"]
+    check("aborting due to" not in text and "emitted" not in text,
+          "unexpected summary: {}".format(text))
+
+
+def find_result(rule_id):
+    found = [r for r in results if r.get("ruleId") == rule_id]
+    check(len(found) == 1, "expected one `{}` result, found {}".format(rule_id, len(found)))
+    return found[0] if found else None
+
+
+for rule_id, level, line, fixed in [
+    ("E0384", "error", 5, "mut x"),
+    ("unused_variables", "warning", 10, "_y"),
+]:
+    result = find_result(rule_id)
+    if result is None:
+        continue
+    check(result["level"] == level, "`{}` has level {}".format(rule_id, result["level"]))
+    check(rules[result["ruleIndex"]]["id"] == rule_id,
+          "`{}` refers to rule {}".format(rule_id, rules[result["ruleIndex"]]["id"]))
+
+    location = result["locations"][0]["physicalLocation"]
+    check(location["artifactLocation"]["uri"] == "lib.rs",
+          "`{}` is in {}".format(rule_id, location["artifactLocation"]["uri"]))
+    check(location["region"]["startLine"] == line,
+          "`{}` starts on line {}".format(rule_id, location["region"]["startLine"]))
+
+    replacements = [
+        replacement
+        for fix in result.get("fixes", [])
+        for change in fix["artifactChanges"]
+        for replacement in change["replacements"]
+    ]
+    check(any(r["insertedContent"]["text"] == fixed for r in replacements),
+          "`{}` has no fix inserting `{}`".format(rule_id, fixed))
+
+check(any(rule["id"] == "E0384" and "fullDescription" in rule for rule in rules),
+      "the rule of E0384 has no description")
+
+for error in errors:
+    print("=> {}".format(error))
+if errors:
+    sys.exit(1)
@@ -0,0 +1,11 @@
+#![crate_type = "lib"]
+
+pub fn assign_twice() -> u32 {
+    let x = 1;
+    x = 2;
+    x
+}
+
+pub fn unused() {
+    let y = 3;
+}
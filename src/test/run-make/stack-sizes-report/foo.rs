@@ -0,0 +1,22 @@
+#![crate_type = "lib"]
+#![no_std]
+
+#[no_mangle]
+pub fn leaf(x: u32) -> u32 {
+    x.rotate_left(3) ^ 0x5a
+}
+
+#[no_mangle]
+pub fn caller(x: u32) -> u32 {
+    leaf(x) ^ leaf(x >> 1)
+}
+
+#[no_mangle]
+pub fn recursive(x: u32) -> u32 {
+    if x == 0 { 0 } else { recursive(x >> 1) }
+}
+
+#[no_mangle]
+pub fn indirect(f: fn(u32) -> u32) -> u32 {
+    f(1)
+}
@@ -0,0 +1 @@
+parser_maybe_report_ambiguous_plus = this is an untranslated test message
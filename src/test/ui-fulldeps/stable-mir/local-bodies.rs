@@ -0,0 +1,77 @@
+// run-pass
+// Test that the stable MIR of all local bodies can be retrieved without
+// depending on the compiler's internal types.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_smir;
+
+use rustc_smir::rustc_internal;
+use rustc_smir::stable_mir::mir::{Operand, Rvalue, StatementKind, TerminatorKind};
+use rustc_smir::stable_mir::ty::{Ty, UintTy};
+use rustc_smir::stable_mir::ItemKind;
+
+const INPUT: &str = r#"
+pub fn add(x: u8, y: u8) -> u8 {
+    x.wrapping_add(y)
+}
+
+pub struct S;
+
+impl S {
+    pub fn call(&self) -> u32 {
+        let f = |z: u32| z + 1;
+        f(41)
+    }
+}
+"#;
+
+fn main() {
+    let dir = std::env::temp_dir().join("stable-mir-local-bodies");
+    std::fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("input.rs");
+    std::fs::write(&input, INPUT).unwrap();
+
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name=input".to_string(),
+        "--emit=metadata".to_string(),
+        format!("--out-dir={}", dir.display()),
+        input.display().to_string(),
+    ];
+
+    let mut items = vec![];
+    rustc_internal::run(&args, |item, body| items.push((item.clone(), body.clone()))).unwrap();
+
+    let paths: Vec<_> = items.iter().map(|(item, _)| item.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["input::add", "input::{impl#0}::call", "input::{impl#0}::call::{closure#0}"]
+    );
+
+    let (add, add_body) = &items[0];
+    assert_eq!(add.kind, ItemKind::Fn);
+    assert_eq!(add.span.start_line, 2);
+    assert_eq!(add_body.arg_count, 2);
+    assert_eq!(add_body.locals[0].ty, Ty::Uint(UintTy::U8));
+    assert_eq!(add_body.locals[1].name.as_deref(), Some("x"));
+    assert!(add_body.blocks.iter().any(|block| matches!(
+        block.terminator.kind,
+        TerminatorKind::Call { func: Operand::Constant(ref func), .. }
+            if matches!(func.ty, Ty::FnDef(ref path, _) if path.ends_with("::wrapping_add"))
+    )));
+
+    let (call, _) = &items[1];
+    assert_eq!(call.kind, ItemKind::AssocFn);
+
+    let (closure, closure_body) = &items[2];
+    assert_eq!(closure.kind, ItemKind::Closure);
+    assert!(closure_body.blocks.iter().flat_map(|block| &block.statements).any(|statement| {
+        matches!(statement.kind, StatementKind::Assign(_, Rvalue::CheckedBinaryOp(..)))
+    }));
+}
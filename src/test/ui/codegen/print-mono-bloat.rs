@@ -0,0 +1,31 @@
+// compile-flags: -Z print-mono-bloat
+// build-pass
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+
+// Check the report of `-Z print-mono-bloat` for a generic function that is instantiated both
+// directly and through another generic function: the instances of each definition are counted,
+// along with their copies in the codegen units and their MIR statements, and each instance is
+// reported with the chain of callers that introduced it.
+
+#![crate_type = "lib"]
+
+fn id<T>(x: T) -> T {
+    x
+}
+
+fn wrap<T>(x: T) -> T {
+    id(x)
+}
+
+pub fn use_u8(x: u8) -> u8 {
+    id(x)
+}
+
+pub fn use_u16(x: u16) -> u16 {
+    wrap(x)
+}
+
+pub fn use_u32(x: u32) -> u32 {
+    wrap(x)
+}
@@ -0,0 +1,5 @@
+// Loaded as a module by `lint-config.rs`, `dead_code` and `naming` are allowed here.
+
+fn NeverUsed() {}
+
+const lowercase: u32 = 0;
@@ -0,0 +1,3 @@
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config/invalid-level.toml
+
+fn main() {}
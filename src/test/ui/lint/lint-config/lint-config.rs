@@ -0,0 +1,17 @@
+// Check that lint levels are read from the lint configuration file, that the levels of single
+// lints take precedence over the ones of groups, and that `[[override]]` sections apply to the
+// modules loaded from the files under their paths.
+
+// compile-flags: -Z lint-config={{src-base}}/lint/lint-config/rustc-lints.toml
+
+#[path = "auxiliary/overridden.rs"]
+mod overridden;
+
+fn unused_function() {} //~ WARN function `unused_function` is never used
+
+fn BadName() {} //~ ERROR function `BadName` should have a snake case name
+
+fn main() {
+    let unused_variable = 0; //~ ERROR unused variable: `unused_variable`
+    BadName();
+}
@@ -0,0 +1,21 @@
+// Check that a misspelled method name is corrected to the name of a method of a trait that
+// is not in scope, along with a suggestion to import the trait.
+
+mod shapes {
+    pub trait Area {
+        fn area(&self) -> f64;
+    }
+
+    pub struct Square;
+
+    impl Area for Square {
+        fn area(&self) -> f64 {
+            1.0
+        }
+    }
+}
+
+fn main() {
+    let _ = shapes::Square.aria();
+    //~^ ERROR no method named `aria` found for struct `Square` in the current scope
+}
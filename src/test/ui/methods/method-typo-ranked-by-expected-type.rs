@@ -0,0 +1,21 @@
+// Check that among the methods with a name similar to a misspelled one, the ones returning
+// the expected type are suggested first.
+
+struct Measure;
+
+impl Measure {
+    fn get_length(&self) -> usize {
+        0
+    }
+
+    fn get_lengths(&self) -> Vec<usize> {
+        Vec::new()
+    }
+}
+
+fn main() {
+    let _ = Measure.get_lenght();
+    //~^ ERROR no method named `get_lenght` found for struct `Measure` in the current scope
+    let _: Vec<usize> = Measure.get_lenght();
+    //~^ ERROR no method named `get_lenght` found for struct `Measure` in the current scope
+}
@@ -0,0 +1,27 @@
+#![crate_type = "lib"]
+#![feature(no_panic)]
+
+#[no_panic]
+pub fn function() {}
+
+pub struct S;
+
+impl S {
+    #[no_panic]
+    pub fn method(&self) {}
+}
+
+pub trait T {
+    #[no_panic]
+    fn provided(&self) {}
+
+    #[no_panic]
+    //~^ ERROR attribute should be applied to a function definition
+    fn required(&self);
+    //~^ NOTE not a function definition
+}
+
+#[no_panic]
+//~^ ERROR attribute should be applied to a function definition
+pub struct NotAFunction;
+//~^ NOTE not a function definition
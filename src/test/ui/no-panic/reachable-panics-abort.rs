@@ -0,0 +1,48 @@
+// Checks that with `-C panic=abort`, where no Rust function unwinds, the calls to functions
+// whose body is not checked and the drops of trait objects are still reported.
+
+// build-fail
+// compile-flags: -C panic=abort
+// no-prefer-dynamic
+
+#![feature(no_panic)]
+
+trait Check {
+    fn check(&self);
+}
+
+struct Quiet;
+
+impl Check for Quiet {
+    fn check(&self) {}
+}
+
+fn nothing() {}
+
+#[no_panic]
+fn pointer(f: fn()) { //~ ERROR `pointer` is marked `#[no_panic]` but can panic
+    f();
+}
+
+#[no_panic]
+fn dynamic(c: &dyn Check) { //~ ERROR `dynamic` is marked `#[no_panic]` but can panic
+    c.check();
+}
+
+#[no_panic]
+fn upstream() -> u32 { //~ ERROR `upstream` is marked `#[no_panic]` but can panic
+    std::process::id()
+}
+
+#[no_panic]
+unsafe fn drop_dynamic(c: *mut dyn Check) {
+    //~^ ERROR `drop_dynamic` is marked `#[no_panic]` but can panic
+    std::ptr::drop_in_place(c);
+}
+
+fn main() {
+    pointer(nothing);
+    dynamic(&Quiet);
+    upstream();
+    unsafe { drop_dynamic(&mut Quiet) };
+}
@@ -0,0 +1,63 @@
+// Checks the panics that are reachable from functions marked `#[no_panic]` once they are
+// monomorphized.
+
+// build-fail
+// edition:2021
+// compile-flags: -C overflow-checks=on
+
+#![feature(no_panic)]
+
+trait Check {
+    fn check(&self);
+}
+
+struct Quiet;
+struct Loud(u32);
+
+impl Check for Quiet {
+    fn check(&self) {}
+}
+
+impl Check for Loud {
+    fn check(&self) {
+        let _ = self.0 + 1;
+    }
+}
+
+#[no_panic]
+fn wrapping(x: u32) -> u32 {
+    x.wrapping_add(1)
+}
+
+#[no_panic]
+fn first(v: &[u32]) -> u32 { //~ ERROR `first` is marked `#[no_panic]` but can panic
+    v[0]
+}
+
+#[no_panic]
+fn explicit(x: u32) { //~ ERROR `explicit` is marked `#[no_panic]` but can panic
+    if x > 1 {
+        panic!();
+    }
+    let _ = x * 2;
+}
+
+#[no_panic]
+fn run<C: Check>(c: &C) {
+    //~^ ERROR `run::<Loud>` is marked `#[no_panic]` but can panic
+    c.check();
+}
+
+#[no_panic]
+fn dynamic(c: &dyn Check) { //~ ERROR `dynamic` is marked `#[no_panic]` but can panic
+    c.check();
+}
+
+fn main() {
+    wrapping(1);
+    first(&[1]);
+    explicit(1);
+    run(&Quiet);
+    run(&Loud(1));
+    dynamic(&Quiet);
+}
@@ -0,0 +1,12 @@
+// run-rustfix
+
+fn main() {}
+
+impl T for () {
+    fn foo(&self) {}
+
+trait T {
+    fn foo(&self);
+}
+
+pub(crate) struct Bar<T>(T); //~ ERROR this file contains an unclosed delimiter
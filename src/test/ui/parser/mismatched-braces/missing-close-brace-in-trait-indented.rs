@@ -0,0 +1,12 @@
+// run-rustfix
+
+trait T {
+    fn foo(&self);
+
+pub(crate) struct Bar<T>(T);
+
+impl T for Bar<usize> {
+    fn foo(&self) {}
+}
+
+fn main() {} //~ ERROR this file contains an unclosed delimiter
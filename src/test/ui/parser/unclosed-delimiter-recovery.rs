@@ -0,0 +1,14 @@
+// Check that the position of a missing closing brace is guessed from the indentation of the
+// code, and that the items after it are parsed normally.
+
+// run-rustfix
+
+fn main() {
+    let x = 1;
+    if x == 1 {
+        println!("{}", x);
+}
+
+fn foo() -> u32 {
+    2
+} //~ ERROR this file contains an unclosed delimiter
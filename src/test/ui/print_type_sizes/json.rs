@@ -0,0 +1,39 @@
+// compile-flags: -Z print-type-sizes-json
+// build-pass
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+
+// This file illustrates the JSON layout output: field offsets, padding, the
+// largest niche, both kinds of discriminant encoding, the captures of closures
+// and the states of generators.
+
+#![feature(generators, start)]
+#![allow(dead_code)]
+
+pub struct S {
+    a: bool,
+    b: u32,
+}
+
+pub enum E {
+    A(u8),
+    B(u16),
+}
+
+pub enum MyOption<T> {
+    None,
+    Some(T),
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _s = S { a: true, b: 0 };
+    let _e = E::A(0);
+    let _o: MyOption<bool> = MyOption::Some(true);
+    let x = 0u32;
+    let _c = move || x;
+    let _g = || {
+        yield 1u8;
+    };
+    0
+}
@@ -0,0 +1,25 @@
+// run-rustfix
+// rustfix-only-machine-applicable
+// Check that the imports suggested for the unresolved names of a module can be applied
+// together, and that they are merged into the `use` items importing from the same modules.
+
+#![allow(dead_code)]
+
+mod config {
+    pub struct Config;
+    pub struct Options;
+    pub fn load() {}
+}
+
+use config::Config;
+use std::collections::HashMap;
+
+fn main() {
+    let _config = Config;
+    let _options = Options; //~ ERROR cannot find value `Options` in this scope
+    load(); //~ ERROR cannot find function `load` in this scope
+    let _: HashMap<u8, u8> = HashMap::new();
+    let _: BTreeMap<u8, u8>; //~ ERROR cannot find type `BTreeMap` in this scope
+    let _: HashSet<u8>; //~ ERROR cannot find type `HashSet` in this scope
+    let _: Rc<u8>; //~ ERROR cannot find type `Rc` in this scope
+}
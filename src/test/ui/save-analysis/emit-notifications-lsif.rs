@@ -0,0 +1,7 @@
+// build-pass
+// compile-flags: -Zsave-analysis -Zsave-analysis-format=lsif --json artifacts
+// compile-flags: --crate-type rlib --error-format=json
+// ignore-pass
+// ^-- needed because otherwise, the .stderr file changes with --pass check
+
+pub fn foo() {}
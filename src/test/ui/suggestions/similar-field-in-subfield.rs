@@ -0,0 +1,24 @@
+// Check that a misspelled field name is corrected to the name of a field nested in another
+// field or of a field of a deref target, preferring the fields of the expected type.
+
+struct Inner {
+    total_length: usize,
+    total_lengths: Vec<usize>,
+}
+
+struct Outer {
+    inner: Inner,
+    name: String,
+}
+
+fn main() {
+    let inner = Inner { total_length: 0, total_lengths: Vec::new() };
+    let outer = Outer { inner, name: String::new() };
+    let _ = outer.total_lenght;
+    //~^ ERROR no field `total_lenght` on type `Outer`
+    let _: Vec<usize> = outer.total_lenght;
+    //~^ ERROR no field `total_lenght` on type `Outer`
+    let boxed = Box::new(outer);
+    let _ = boxed.nme;
+    //~^ ERROR no field `nme` on type `Box<Outer>`
+}
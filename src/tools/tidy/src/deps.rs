@@ -222,6 +222,7 @@
     "tinyvec",
     "tinyvec_macros",
     "thin-vec",
+    "toml",
     "tracing",
     "tracing-attributes",
     "tracing-core",